@@ -1,4 +1,4 @@
-use arpfloat::{BigInt, Float, RoundingMode, Semantics};
+use arpfloat::{BigInt, Float, RoundingMode, Semantics, FP16, FP32, FP64};
 
 use RoundingMode::NearestTiesToEven as rme;
 
@@ -102,7 +102,67 @@ fn test_bigint_div_2() {
     black_box(a / b);
 }
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+fn test_fp_throughput(c: &mut Criterion) {
+    // Emulate a stream of small-float operations, where the cost of the
+    // storage of the mantissa dominates the arithmetic.
+    let mut group = c.benchmark_group("fp_throughput");
+    for (name, sem) in [("fp32", FP32), ("fp64", FP64)] {
+        let vals: Vec<Float> = (1..=100)
+            .map(|i| Float::from_f64(i as f64 * 1.37 - 50.).cast(sem))
+            .collect();
+        group.bench_function(name, |x| {
+            x.iter(|| {
+                let mut acc = Float::one(sem, false);
+                for v in vals.iter() {
+                    let prod = &acc * v;
+                    let sum = &prod + v;
+                    acc = &sum / v;
+                    black_box(acc.cast(FP16));
+                }
+                black_box(acc)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn test_bigint_mul_sizes(c: &mut Criterion) {
+    // Multiply numbers of increasing sizes (in words), around the points where
+    // we switch from Karatsuba to Toom-3 (256) and to the NTT (2048).
+    let mut group = c.benchmark_group("bigint_mul_sizes");
+    group.sample_size(10);
+    for size in [128, 256, 512, 1024, 2048, 4096, 16384, 65536] {
+        let a = BigInt::pseudorandom(size, 98765);
+        let b = BigInt::pseudorandom(size, 43210);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &size,
+            |x, _| x.iter(|| black_box(&a * &b)),
+        );
+    }
+    group.finish();
+}
+
+fn test_bigint_div_sizes(c: &mut Criterion) {
+    // Divide numbers of increasing sizes (in words), around the point where
+    // we switch from Knuth's algorithm to the Newton reciprocal (4096).
+    let mut group = c.benchmark_group("bigint_div_sizes");
+    group.sample_size(10);
+    for size in [64, 512, 2048, 4096, 8192, 16384] {
+        let a = BigInt::pseudorandom(2 * size, 98765);
+        let b = BigInt::pseudorandom(size, 43210);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &size,
+            |x, _| x.iter(|| black_box(a.divrem(&b))),
+        );
+    }
+    group.finish();
+}
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
+};
 
 pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("pi", |b| b.iter(test_pi));
@@ -121,6 +181,9 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("bigint_mul_4", |b| b.iter(test_bigint_mul_4));
     c.bench_function("bigint_div_1", |b| b.iter(test_bigint_div_1));
     c.bench_function("bigint_div_2", |b| b.iter(test_bigint_div_2));
+    test_fp_throughput(c);
+    test_bigint_mul_sizes(c);
+    test_bigint_div_sizes(c);
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -4,7 +4,9 @@ extern crate alloc;
 use crate::bigint::BigInt;
 
 use super::bigint::LossFraction;
+use super::float::Status;
 use super::float::{shift_right_with_loss, Category, Float, RoundingMode};
+use alloc::borrow::Cow;
 use core::cmp::Ordering;
 use core::ops::{
     Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign,
@@ -12,20 +14,20 @@ use core::ops::{
 
 impl Float {
     /// An inner function that performs the addition and subtraction of normal
-    /// numbers (no NaN, Inf, Zeros).
+    /// numbers (no NaN, Inf, Zeros), and stores the result in `a`.
     /// See Pg 247.  Chapter 8. Algorithms for the Five Basic Operations.
     /// This implementation follows the APFloat implementation, that does not
     /// swap the operands.
     fn add_or_sub_normals(
-        a: &Self,
+        a: &mut Self,
         b: &Self,
         subtract: bool,
-    ) -> (Self, LossFraction) {
+    ) -> LossFraction {
         debug_assert_eq!(a.get_semantics(), b.get_semantics());
         let sem = a.get_semantics();
         let loss;
-        let mut a = a.clone();
-        let mut b = b.clone();
+        // The mantissa of `b` is copied only if it needs to be shifted.
+        let mut b_mantissa = Cow::Borrowed(b.mantissa_ref());
 
         // Align the input numbers on the same exponent.
         let bits = a.get_exp() - b.get_exp();
@@ -41,18 +43,20 @@ impl Float {
                     loss = LossFraction::ExactlyZero;
                 }
                 Ordering::Greater => {
-                    loss = b.shift_significand_right((bits - 1) as u64);
+                    let shifted;
+                    (shifted, loss) =
+                        shift_right_with_loss(&b_mantissa, (bits - 1) as usize);
+                    b_mantissa = Cow::Owned(shifted);
                     a.shift_significand_left(1);
                 }
                 Ordering::Less => {
                     loss = a.shift_significand_right((-bits - 1) as u64);
-                    b.shift_significand_left(1);
+                    b_mantissa.to_mut().shift_left(1);
                 }
             }
 
-            let a_mantissa = a.get_mantissa();
-            let b_mantissa = b.get_mantissa();
-            let ab_mantissa;
+            let exp = a.get_exp();
+            let mut ab_mantissa = a.take_mantissa();
             let mut sign = a.get_sign();
 
             // Figure out the carry from the shifting operations that dropped
@@ -62,54 +66,102 @@ impl Float {
 
             // Figure out which mantissa is larger, to make sure that we don't
             // overflow the subtraction.
-            if a_mantissa < b_mantissa {
+            if ab_mantissa < *b_mantissa {
                 // A < B
-                ab_mantissa = b_mantissa - a_mantissa - c;
+                core::mem::swap(&mut ab_mantissa, b_mantissa.to_mut());
                 sign = !sign;
-            } else {
-                // A >= B
-                ab_mantissa = a_mantissa - b_mantissa - c;
             }
-            (
-                Self::new(sem, sign, a.get_exp(), ab_mantissa),
-                loss.invert(),
-            )
+            let borrow = ab_mantissa.inplace_sub(&b_mantissa);
+            debug_assert!(!borrow);
+            let borrow = ab_mantissa.inplace_sub(&c);
+            debug_assert!(!borrow);
+            *a = Self::new(sem, sign, exp, ab_mantissa);
+            loss.invert()
         } else {
             // Handle the easy case of Add:
-            let mut b = b.clone();
-            let mut a = a.clone();
             if bits > 0 {
-                loss = b.shift_significand_right(bits as u64);
+                let shifted;
+                (shifted, loss) =
+                    shift_right_with_loss(&b_mantissa, bits as usize);
+                b_mantissa = Cow::Owned(shifted);
             } else {
                 loss = a.shift_significand_right(-bits as u64);
             }
-            debug_assert!(a.get_exp() == b.get_exp());
-            let ab_mantissa = a.get_mantissa() + b.get_mantissa();
-            (Self::new(sem, a.get_sign(), a.get_exp(), ab_mantissa), loss)
+            let (sign, exp) = (a.get_sign(), a.get_exp());
+            let mut ab_mantissa = a.take_mantissa();
+            ab_mantissa.inplace_add(&b_mantissa);
+            *a = Self::new(sem, sign, exp, ab_mantissa);
+            loss
         }
     }
 
     /// Computes a+b using the rounding mode `rm`.
     pub fn add_with_rm(a: &Self, b: &Self, rm: RoundingMode) -> Self {
-        Self::add_sub(a, b, false, rm)
+        Self::add_sub(a, b, false, rm).0
     }
     /// Computes a-b using the rounding mode `rm`.
     pub fn sub_with_rm(a: &Self, b: &Self, rm: RoundingMode) -> Self {
+        Self::add_sub(a, b, true, rm).0
+    }
+    /// Computes a+b using the rounding mode `rm`, and returns the exceptions
+    /// that the operation raised.
+    pub fn add_with_status(
+        a: &Self,
+        b: &Self,
+        rm: RoundingMode,
+    ) -> (Self, Status) {
+        Self::add_sub(a, b, false, rm)
+    }
+    /// Computes a-b using the rounding mode `rm`, and returns the exceptions
+    /// that the operation raised.
+    pub fn sub_with_status(
+        a: &Self,
+        b: &Self,
+        rm: RoundingMode,
+    ) -> (Self, Status) {
         Self::add_sub(a, b, true, rm)
     }
 
-    fn add_sub(a: &Self, b: &Self, subtract: bool, rm: RoundingMode) -> Self {
+    /// Computes self+rhs using the rounding mode `rm`, and stores the result
+    /// in self. This reuses the storage of self.
+    pub fn add_assign_with_rm(&mut self, rhs: &Self, rm: RoundingMode) {
+        self.add_sub_assign(rhs, false, rm);
+    }
+    /// Computes self-rhs using the rounding mode `rm`, and stores the result
+    /// in self. This reuses the storage of self.
+    pub fn sub_assign_with_rm(&mut self, rhs: &Self, rm: RoundingMode) {
+        self.add_sub_assign(rhs, true, rm);
+    }
+
+    fn add_sub(
+        a: &Self,
+        b: &Self,
+        subtract: bool,
+        rm: RoundingMode,
+    ) -> (Self, Status) {
+        let mut res = a.clone();
+        let status = res.add_sub_assign(b, subtract, rm);
+        (res, status)
+    }
+
+    fn add_sub_assign(
+        &mut self,
+        b: &Self,
+        subtract: bool,
+        rm: RoundingMode,
+    ) -> Status {
+        let a = &*self;
         let sem = a.get_semantics();
         // Table 8.2: Specification of addition for positive floating-point
         // data. Pg 247.
-        match (a.get_category(), b.get_category()) {
+        let res = match (a.get_category(), b.get_category()) {
             (Category::NaN, Category::Infinity)
             | (Category::NaN, Category::NaN)
             | (Category::NaN, Category::Normal)
             | (Category::NaN, Category::Zero)
             | (Category::Normal, Category::Zero)
             | (Category::Infinity, Category::Normal)
-            | (Category::Infinity, Category::Zero) => a.clone(),
+            | (Category::Infinity, Category::Zero) => return Status::OK,
 
             (Category::Zero, Category::NaN)
             | (Category::Normal, Category::NaN)
@@ -135,17 +187,19 @@ impl Float {
 
             (Category::Infinity, Category::Infinity) => {
                 if a.get_sign() ^ b.get_sign() ^ subtract {
-                    return Self::nan(sem, a.get_sign() ^ b.get_sign());
+                    *self = Self::nan(sem, a.get_sign() ^ b.get_sign());
+                    return Status::INVALID_OP;
                 }
                 Self::inf(sem, a.get_sign())
             }
 
             (Category::Normal, Category::Normal) => {
-                let mut res = Self::add_or_sub_normals(a, b, subtract);
-                res.0.normalize(rm, res.1);
-                res.0
+                let loss = Self::add_or_sub_normals(self, b, subtract);
+                return self.normalize(rm, loss);
             }
-        }
+        };
+        *self = res;
+        Status::OK
     }
 }
 
@@ -194,6 +248,78 @@ fn test_addition() {
     }
 }
 
+#[test]
+fn test_in_place_operations() {
+    use super::float::FP64;
+    use crate::utils;
+    let modes = [
+        RoundingMode::NearestTiesToEven,
+        RoundingMode::Zero,
+        RoundingMode::Positive,
+        RoundingMode::Negative,
+    ];
+
+    // The in-place operations match the operations that return new values.
+    for a in utils::get_special_test_values() {
+        for b in utils::get_special_test_values() {
+            let a = Float::from_f64(a);
+            let b = Float::from_f64(b);
+            for rm in modes {
+                let mut res = a.clone();
+                res.add_assign_with_rm(&b, rm);
+                let expected = Float::add_with_rm(&a, &b, rm);
+                assert!(res.is_nan() && expected.is_nan() || res == expected);
+                assert_eq!(res.get_sign(), expected.get_sign());
+
+                let mut res = a.clone();
+                res.sub_assign_with_rm(&b, rm);
+                let expected = Float::sub_with_rm(&a, &b, rm);
+                assert!(res.is_nan() && expected.is_nan() || res == expected);
+                assert_eq!(res.get_sign(), expected.get_sign());
+
+                let mut res = a.clone();
+                res.mul_assign_with_rm(&b, rm);
+                let expected = Float::mul_with_rm(&a, &b, rm);
+                assert!(res.is_nan() && expected.is_nan() || res == expected);
+                assert_eq!(res.get_sign(), expected.get_sign());
+
+                let mut res = a.clone();
+                res.div_assign_with_rm(&b, rm);
+                let expected = Float::div_with_rm(&a, &b, rm);
+                assert!(res.is_nan() && expected.is_nan() || res == expected);
+                assert_eq!(res.get_sign(), expected.get_sign());
+            }
+        }
+    }
+
+    let mut x = Float::from_f64(-1.5);
+    x.neg_in_place();
+    assert_eq!(x.as_f64(), 1.5);
+    x.scale_in_place(3, RoundingMode::NearestTiesToEven);
+    assert_eq!(x.as_f64(), 12.);
+    assert_eq!(x.mantissa_ref(), &x.get_mantissa());
+    let mut tiny = Float::from_f64(f64::MIN_POSITIVE);
+    tiny.scale_in_place(-1, RoundingMode::NearestTiesToEven);
+    assert_eq!(tiny.as_f64(), f64::MIN_POSITIVE / 2.);
+    let mut inf = Float::inf(FP64, false);
+    inf.scale_in_place(-10, RoundingMode::NearestTiesToEven);
+    assert!(inf.is_inf());
+
+    // Subnormal operands are normalized before the division.
+    let tiny = f64::MIN_POSITIVE / 8.;
+    let mut x = Float::from_f64(3e-300);
+    x /= Float::from_f64(tiny);
+    assert_eq!(x.as_f64(), 3e-300 / tiny);
+    let mut x = Float::from_f64(tiny);
+    x /= Float::from_f64(1e-300);
+    assert_eq!(x.as_f64(), tiny / 1e-300);
+    x /= Float::from_f64(1e300);
+    assert_eq!(x.as_f64(), tiny / 1e-300 / 1e300);
+    let mut x = Float::from_f64(f64::MIN_POSITIVE / 3.);
+    x *= Float::from_f64(1.5);
+    assert_eq!(x.as_f64(), f64::MIN_POSITIVE / 3. * 1.5);
+}
+
 // Pg 120.  Chapter 4. Basic Properties and Algorithms.
 #[test]
 fn test_addition_large_numbers() {
@@ -329,12 +455,22 @@ fn test_add_random_vals() {
 impl Float {
     /// Compute a*b using the rounding mode `rm`.
     pub fn mul_with_rm(a: &Self, b: &Self, rm: RoundingMode) -> Self {
+        Self::mul_with_status(a, b, rm).0
+    }
+
+    /// Compute a*b using the rounding mode `rm`, and returns the exceptions
+    /// that the operation raised.
+    pub fn mul_with_status(
+        a: &Self,
+        b: &Self,
+        rm: RoundingMode,
+    ) -> (Self, Status) {
         let sem = a.get_semantics();
         let sign = a.get_sign() ^ b.get_sign();
 
         // Table 8.4: Specification of multiplication for floating-point data of
         // positive sign. Page 251.
-        match (a.get_category(), b.get_category()) {
+        let res = match (a.get_category(), b.get_category()) {
             (Category::Zero, Category::NaN)
             | (Category::Normal, Category::NaN)
             | (Category::Infinity, Category::NaN) => {
@@ -352,48 +488,66 @@ impl Float {
             | (Category::Zero, Category::Zero) => Self::zero(sem, sign),
 
             (Category::Zero, Category::Infinity)
-            | (Category::Infinity, Category::Zero) => Self::nan(sem, sign),
+            | (Category::Infinity, Category::Zero) => {
+                return (Self::nan(sem, sign), Status::INVALID_OP);
+            }
 
             (Category::Normal, Category::Normal) => {
                 let (mut res, loss) = Self::mul_normals(a, b, sign);
-                res.normalize(rm, loss);
-                res
+                let status = res.normalize(rm, loss);
+                return (res, status);
             }
-        }
+        };
+        (res, Status::OK)
     }
 
-    /// See Pg 251. 8.4 Floating-Point Multiplication
-    fn mul_normals(a: &Self, b: &Self, sign: bool) -> (Self, LossFraction) {
-        debug_assert_eq!(a.get_semantics(), b.get_semantics());
-        let sem = a.get_semantics();
+    /// Computes the exact product of the normals `a` and `b`. The result is
+    /// not normalized, and the significand may be up to twice as wide as the
+    /// precision of the semantics.
+    fn mul_normals_unrounded(a: &Self, b: &Self, sign: bool) -> Self {
+        let mut ab = a.clone();
+        ab.mul_normals_unrounded_in_place(b, sign);
+        ab
+    }
+
+    /// Replaces this normal number with the exact product of it and the
+    /// normal `b`, and reuses the storage of the mantissa.
+    fn mul_normals_unrounded_in_place(&mut self, b: &Self, sign: bool) {
+        debug_assert_eq!(self.get_semantics(), b.get_semantics());
+        let sem = self.get_semantics();
         // We multiply digits in the format 1.xx * 2^(e), or mantissa * 2^(e+1).
         // When we multiply two 2^(e+1) numbers, we get:
         // log(2^(e_a+1)*2^(e_b+1)) = e_a + e_b + 2.
-        let mut exp = a.get_exp() + b.get_exp();
-
-        let mut loss = LossFraction::ExactlyZero;
-
-        let a_significand = a.get_mantissa();
-        let b_significand = b.get_mantissa();
+        let mut exp = self.get_exp() + b.get_exp();
 
-        let mut ab_significand = a_significand * b_significand;
-        let first_non_zero = ab_significand.msb_index();
+        let mut ab_significand = self.take_mantissa();
+        ab_significand.inplace_mul(b.mantissa_ref());
 
         // The exponent is correct, but the bits are not in the right place.
-        // Set the right exponent for where the bits are placed, and fix the
-        // exponent below.
+        // Set the right exponent for where the bits are placed.
         exp -= sem.get_mantissa_len() as i64;
+        *self = Self::new(sem, sign, exp, ab_significand);
+    }
 
-        let precision = a.get_semantics().get_precision();
+    fn mul_normals(a: &Self, b: &Self, sign: bool) -> (Self, LossFraction) {
+        let mut ab = a.clone();
+        let loss = ab.mul_normals_in_place(b, sign);
+        (ab, loss)
+    }
+
+    /// Replaces this normal number with the product of it and the normal `b`,
+    /// with the bits below the precision shifted out, and returns the loss.
+    fn mul_normals_in_place(&mut self, b: &Self, sign: bool) -> LossFraction {
+        self.mul_normals_unrounded_in_place(b, sign);
+
+        // Fix the exponent if the product has more bits than the precision.
+        let precision = self.get_semantics().get_precision();
+        let first_non_zero = self.mantissa_ref().msb_index();
         if first_non_zero > precision {
             let bits = first_non_zero - precision;
-
-            (ab_significand, loss) =
-                shift_right_with_loss(&ab_significand, bits);
-            exp += bits as i64;
+            return self.shift_significand_right(bits as u64);
         }
-
-        (Self::new(sem, sign, exp, ab_significand), loss)
+        LossFraction::ExactlyZero
     }
 }
 
@@ -498,50 +652,76 @@ fn test_mul_random_vals() {
 impl Float {
     /// Compute a/b, with the rounding mode `rm`.
     pub fn div_with_rm(a: &Self, b: &Self, rm: RoundingMode) -> Self {
+        Self::div_with_status(a, b, rm).0
+    }
+
+    /// Compute a/b, with the rounding mode `rm`, and returns the exceptions
+    /// that the operation raised.
+    pub fn div_with_status(
+        a: &Self,
+        b: &Self,
+        rm: RoundingMode,
+    ) -> (Self, Status) {
         let sem = a.get_semantics();
         let sign = a.get_sign() ^ b.get_sign();
         // Table 8.5: Special values for x/y - Page 263.
-        match (a.get_category(), b.get_category()) {
-            (Category::NaN, _)
-            | (_, Category::NaN)
-            | (Category::Zero, Category::Zero)
-            | (Category::Infinity, Category::Infinity) => Self::nan(sem, sign),
+        let res = match (a.get_category(), b.get_category()) {
+            (Category::NaN, _) | (_, Category::NaN) => Self::nan(sem, sign),
+            (Category::Zero, Category::Zero)
+            | (Category::Infinity, Category::Infinity) => {
+                return (Self::nan(sem, sign), Status::INVALID_OP);
+            }
 
             (_, Category::Infinity) => Self::zero(sem, sign),
             (Category::Zero, _) => Self::zero(sem, sign),
-            (_, Category::Zero) => Self::inf(sem, sign),
             (Category::Infinity, _) => Self::inf(sem, sign),
+            (_, Category::Zero) => {
+                return (Self::inf(sem, sign), Status::DIV_BY_ZERO);
+            }
             (Category::Normal, Category::Normal) => {
                 let (mut res, loss) = Self::div_normals(a, b);
-                res.normalize(rm, loss);
-                res
+                let status = res.normalize(rm, loss);
+                return (res, status);
             }
-        }
+        };
+        (res, Status::OK)
     }
 
     /// Compute a/b, where both `a` and `b` are normals.
     /// Page 262 8.6. Floating-Point Division.
     /// This implementation uses a regular integer division for the mantissa.
     fn div_normals(a: &Self, b: &Self) -> (Self, LossFraction) {
-        debug_assert_eq!(a.get_semantics(), b.get_semantics());
-        let sem = a.get_semantics();
-
-        let mut a = a.clone();
-        let mut b = b.clone();
-        // Start by normalizing the dividend and divisor to the MSB.
-        a.align_mantissa(); // Normalize the dividend.
-        b.align_mantissa(); // Normalize the divisor.
+        let mut res = a.clone();
+        let loss = res.div_normals_in_place(b);
+        (res, loss)
+    }
 
-        let mut a_mantissa = a.get_mantissa();
-        let b_mantissa = b.get_mantissa();
+    /// Replaces this normal number with the quotient of it and the normal
+    /// `b`, and returns the loss. Reuses the storage of the mantissa.
+    fn div_normals_in_place(&mut self, b: &Self) -> LossFraction {
+        debug_assert_eq!(self.get_semantics(), b.get_semantics());
+        let sem = self.get_semantics();
+
+        // Start by normalizing the dividend and divisor to the MSB. The
+        // divisor is copied only if it is not normalized.
+        self.align_mantissa(); // Normalize the dividend.
+        let mut aligned = None;
+        if b.mantissa_ref().msb_index() < sem.get_precision() {
+            let mut b = b.clone();
+            b.align_mantissa(); // Normalize the divisor.
+            aligned = Some(b);
+        }
+        let b = aligned.as_ref().unwrap_or(b);
+        let b_mantissa = b.mantissa_ref();
 
         // Calculate the sign and exponent.
-        let mut exp = a.get_exp() - b.get_exp();
-        let sign = a.get_sign() ^ b.get_sign();
+        let mut exp = self.get_exp() - b.get_exp();
+        let sign = self.get_sign() ^ b.get_sign();
+        let mut a_mantissa = self.take_mantissa();
 
         // Make sure that A >= B, to allow the integer division to generate all
         // of the bits of the result.
-        if a_mantissa < b_mantissa {
+        if a_mantissa < *b_mantissa {
             a_mantissa.shift_left(1);
             exp -= 1;
         }
@@ -551,14 +731,14 @@ impl Float {
         // Shift the dividend to make sure that we generate the bits after
         // the period.
         a_mantissa.shift_left(sem.get_mantissa_len());
-        let reminder = a_mantissa.inplace_div(&b_mantissa);
+        let reminder = a_mantissa.inplace_div(b_mantissa);
 
         // Find 2 x reminder, to be able to compare to the reminder and figure
         // out the kind of loss that we have.
         let mut reminder_2x = reminder;
         reminder_2x.shift_left(1);
 
-        let reminder = reminder_2x.cmp(&b_mantissa);
+        let reminder = reminder_2x.cmp(b_mantissa);
         let is_zero = reminder_2x.is_zero();
         let loss = match reminder {
             Ordering::Less => {
@@ -572,8 +752,8 @@ impl Float {
             Ordering::Greater => LossFraction::MoreThanHalf,
         };
 
-        let x = Self::new(sem, sign, exp, a_mantissa);
-        (x, loss)
+        *self = Self::new(sem, sign, exp, a_mantissa);
+        loss
     }
 }
 
@@ -620,6 +800,353 @@ fn test_div_special_values() {
     }
 }
 
+impl Float {
+    /// Compute a*b+c, with the rounding mode `rm`. The product and the sum are
+    /// computed exactly, and the result is rounded once.
+    /// See IEEE754-2019 Section 5.4.1 fusedMultiplyAdd.
+    pub fn fma_with_rm(a: &Self, b: &Self, c: &Self, rm: RoundingMode) -> Self {
+        Self::fma_with_status(a, b, c, rm).0
+    }
+
+    /// Compute a*b+c, with the rounding mode `rm`, and returns the exceptions
+    /// that the operation raised.
+    pub fn fma_with_status(
+        a: &Self,
+        b: &Self,
+        c: &Self,
+        rm: RoundingMode,
+    ) -> (Self, Status) {
+        debug_assert_eq!(a.get_semantics(), b.get_semantics());
+        debug_assert_eq!(a.get_semantics(), c.get_semantics());
+
+        // If one of the factors is not a normal number then the product is
+        // exact (Zero, Inf, NaN), and the addition handles the special values.
+        if !a.is_normal() || !b.is_normal() {
+            let (ab, status) = Self::mul_with_status(a, b, rm);
+            let (res, add_status) = Self::add_with_status(&ab, c, rm);
+            return (res, status | add_status);
+        }
+
+        match c.get_category() {
+            // The product is the result, and it is only rounded once.
+            Category::Zero => Self::mul_with_status(a, b, rm),
+            Category::NaN | Category::Infinity => (c.clone(), Status::OK),
+            Category::Normal => {
+                let sign = a.get_sign() ^ b.get_sign();
+                let ab = Self::mul_normals_unrounded(a, b, sign);
+                Self::add_normals_unrounded(&ab, c, rm)
+            }
+        }
+    }
+
+    /// Compute a*b+c, with the rounding mode of the semantics of `a`.
+    pub fn fma(a: &Self, b: &Self, c: &Self) -> Self {
+        let rm = a.get_semantics().get_rounding_mode();
+        Self::fma_with_rm(a, b, c, rm)
+    }
+
+    /// Adds the normals `a` and `b`, whose significands may be wider than the
+    /// precision, and round the sum once. The operands are aligned exactly,
+    /// except for bits that are far below the top of the wider operand. These
+    /// bits can only affect the rounding, so they are folded into a sticky bit.
+    fn add_normals_unrounded(
+        a: &Self,
+        b: &Self,
+        rm: RoundingMode,
+    ) -> (Self, Status) {
+        let sem = a.get_semantics();
+
+        // The value of each number is mantissa * 2^(exp - M). Find the highest
+        // bit of each number in these units, and the lowest bit that we keep.
+        let top_a = a.get_exp() + a.get_mantissa().msb_index() as i64;
+        let top_b = b.get_exp() + b.get_mantissa().msb_index() as i64;
+        // The unrounded product is up to 2 x precision wide, and we keep a few
+        // more guard bits to allow the cancellation of the leading bit.
+        let width = 2 * sem.get_precision() as i64 + 3;
+        let lsb = a.get_exp().min(b.get_exp()).max(top_a.max(top_b) - width);
+
+        let align = |x: &Self| -> BigInt {
+            let shift = x.get_exp() - lsb;
+            if shift >= 0 {
+                let mut m = x.get_mantissa();
+                m.shift_left(shift as usize);
+                return m;
+            }
+            let (mut m, loss) =
+                shift_right_with_loss(&x.get_mantissa(), -shift as usize);
+            if !loss.is_exactly_zero() && m.is_even() {
+                m.inplace_add(&BigInt::one());
+            }
+            m
+        };
+
+        let a_mantissa = align(a);
+        let b_mantissa = align(b);
+        let (sign, ab_mantissa) = if a.get_sign() == b.get_sign() {
+            (a.get_sign(), a_mantissa + b_mantissa)
+        } else if a_mantissa < b_mantissa {
+            (b.get_sign(), b_mantissa - a_mantissa)
+        } else {
+            (a.get_sign(), a_mantissa - b_mantissa)
+        };
+
+        // An exact cancellation is a positive zero, except when rounding
+        // down. See IEEE754-2019 Section 6.3.
+        if ab_mantissa.is_zero() {
+            let zero = Self::zero(sem, rm == RoundingMode::Negative);
+            return (zero, Status::OK);
+        }
+
+        let mut res = Self::new(sem, sign, lsb, ab_mantissa);
+        let status = res.normalize(rm, LossFraction::ExactlyZero);
+        (res, status)
+    }
+}
+
+#[test]
+fn test_fma_single_rounding() {
+    use crate::FP64;
+    // (1 + 2^-30) * (1 - 2^-30) - 1 = -2^-60. The separate multiplication
+    // rounds the product to 1.0, and the result collapses to zero.
+    let a = Float::from_f64(1.0 + 1.0 / (1u64 << 30) as f64);
+    let b = Float::from_f64(1.0 - 1.0 / (1u64 << 30) as f64);
+    let c = Float::from_i64(FP64, -1);
+    let fused = Float::fma(&a, &b, &c);
+    let separate = &(&a * &b) + &c;
+    assert!(separate.is_zero());
+    assert_eq!(fused.as_f64(), -1.0 / (1u64 << 60) as f64);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_fma_special_values() {
+    use super::utils;
+
+    let values = utils::get_special_test_values();
+
+    fn fma_f64(a: f64, b: f64, c: f64) -> f64 {
+        let a = Float::from_f64(a);
+        let b = Float::from_f64(b);
+        let c = Float::from_f64(c);
+        Float::fma(&a, &b, &c).as_f64()
+    }
+
+    for v0 in values {
+        for v1 in values {
+            for v2 in values {
+                let r0 = fma_f64(v0, v1, v2);
+                let r1 = v0.mul_add(v1, v2);
+                assert_eq!(r0.is_nan(), r1.is_nan());
+                // Check that the results are bit identical, or are both NaN.
+                assert!(r1.is_nan() || r0.to_bits() == r1.to_bits());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_fma_random_vals() {
+    use super::utils;
+
+    let mut lfsr = utils::Lfsr::new();
+
+    fn check_f64(a: f64, b: f64, c: f64) {
+        let fa = Float::from_f64(a);
+        let fb = Float::from_f64(b);
+        let fc = Float::from_f64(c);
+        let r0 = Float::fma(&fa, &fb, &fc).as_f64();
+        let r1 = a.mul_add(b, c);
+        assert_eq!(r0.is_nan(), r1.is_nan());
+        assert!(r1.is_nan() || r0.to_bits() == r1.to_bits());
+    }
+
+    fn check_f32(a: f32, b: f32, c: f32) {
+        let fa = Float::from_f32(a);
+        let fb = Float::from_f32(b);
+        let fc = Float::from_f32(c);
+        let r0 = Float::fma(&fa, &fb, &fc).as_f32();
+        let r1 = a.mul_add(b, c);
+        assert_eq!(r0.is_nan(), r1.is_nan());
+        assert!(r1.is_nan() || r0.to_bits() == r1.to_bits());
+    }
+
+    // Generate numbers with a random mantissa and an exponent around 1.0, to
+    // make the operands overlap.
+    fn near_one(lfsr: &mut utils::Lfsr) -> f64 {
+        let bits = lfsr.get64();
+        let exp = 1020 + (bits >> 60);
+        f64::from_bits((bits & ((1 << 52) - 1)) | (exp << 52))
+    }
+
+    for _ in 0..5000 {
+        let v0 = f64::from_bits(lfsr.get64());
+        let v1 = f64::from_bits(lfsr.get64());
+        let v2 = f64::from_bits(lfsr.get64());
+        check_f64(v0, v1, v2);
+        // Cancel the product, and leave only the rounding error.
+        check_f64(v0, v1, -(v0 * v1));
+
+        let v0 = near_one(&mut lfsr);
+        let v1 = near_one(&mut lfsr);
+        let v2 = near_one(&mut lfsr);
+        check_f64(v0, v1, v2);
+        check_f64(v0, v1, -v2);
+        check_f64(v0, -v1, -(v0 * v1));
+
+        let v0 = f32::from_bits(lfsr.get64() as u32);
+        let v1 = f32::from_bits(lfsr.get64() as u32);
+        let v2 = f32::from_bits(lfsr.get64() as u32);
+        check_f32(v0, v1, v2);
+        check_f32(v0, v1, -(v0 * v1));
+    }
+}
+
+#[test]
+fn test_fma_rounding_modes() {
+    use super::utils;
+    use crate::{Semantics, FP64};
+
+    // A format that's wide enough to hold the exact result of a*b+c.
+    let wide = Semantics::new(16, 512, RoundingMode::NearestTiesToEven);
+    let modes = [
+        RoundingMode::None,
+        RoundingMode::NearestTiesToEven,
+        RoundingMode::NearestTiesToAway,
+        RoundingMode::Zero,
+        RoundingMode::Positive,
+        RoundingMode::Negative,
+    ];
+
+    let mut lfsr = utils::Lfsr::new();
+    fn near_one(lfsr: &mut utils::Lfsr) -> Float {
+        let bits = lfsr.get64();
+        let exp = 1020 + (bits >> 60);
+        let sign = (bits >> 59) & 1;
+        let bits = (bits & ((1 << 52) - 1)) | (exp << 52) | (sign << 63);
+        Float::from_f64(f64::from_bits(bits))
+    }
+
+    for _ in 0..500 {
+        let a = near_one(&mut lfsr);
+        let b = near_one(&mut lfsr);
+        let c = near_one(&mut lfsr);
+        let exact = &(&a.cast(wide) * &b.cast(wide)) + &c.cast(wide);
+        for rm in modes {
+            let fused = Float::fma_with_rm(&a, &b, &c, rm);
+            assert_eq!(fused.get_semantics(), FP64);
+            assert_eq!(fused, exact.cast_with_rm(FP64, rm));
+        }
+    }
+}
+
+#[test]
+fn test_status_special_values() {
+    use crate::FP64;
+    let rm = RoundingMode::NearestTiesToEven;
+    let zero = Float::zero(FP64, false);
+    let one = Float::one(FP64, false);
+    let inf = Float::inf(FP64, false);
+    let nan = Float::nan(FP64, false);
+    let max = Float::from_f64(f64::MAX);
+
+    // Invalid operations.
+    let (r, s) = Float::sub_with_status(&inf, &inf, rm);
+    assert!(r.is_nan() && s == Status::INVALID_OP);
+    let (r, s) = Float::mul_with_status(&zero, &inf, rm);
+    assert!(r.is_nan() && s == Status::INVALID_OP);
+    let (r, s) = Float::div_with_status(&zero, &zero, rm);
+    assert!(r.is_nan() && s == Status::INVALID_OP);
+    let (r, s) = Float::div_with_status(&inf, &inf, rm);
+    assert!(r.is_nan() && s == Status::INVALID_OP);
+    let (r, s) = Float::fma_with_status(&inf, &zero, &one, rm);
+    assert!(r.is_nan() && s == Status::INVALID_OP);
+    let (r, s) = Float::fma_with_status(&inf, &one, &inf.neg(), rm);
+    assert!(r.is_nan() && s == Status::INVALID_OP);
+
+    // Quiet NaNs propagate without raising exceptions.
+    assert!(Float::add_with_status(&nan, &one, rm).1.is_ok());
+    assert!(Float::mul_with_status(&nan, &inf, rm).1.is_ok());
+    assert!(Float::div_with_status(&nan, &zero, rm).1.is_ok());
+
+    // Division by zero.
+    let (r, s) = Float::div_with_status(&one.neg(), &zero, rm);
+    assert!(r.is_inf() && r.is_negative() && s == Status::DIV_BY_ZERO);
+    let (r, s) = Float::div_with_status(&inf, &zero, rm);
+    assert!(r.is_inf() && s.is_ok());
+
+    // Overflow.
+    let (r, s) = Float::add_with_status(&max, &max, rm);
+    assert!(r.is_inf() && s == Status::OVERFLOW | Status::INEXACT);
+    let (r, s) = Float::mul_with_status(&max, &max, RoundingMode::Zero);
+    assert!(r == max && s == Status::OVERFLOW | Status::INEXACT);
+    // Rounding carries the largest number into infinity.
+    let half_ulp = one.scale(970, rm);
+    let (r, s) = Float::add_with_status(&max, &half_ulp, rm);
+    assert!(r.is_inf() && s == Status::OVERFLOW | Status::INEXACT);
+    let (r, s) = Float::add_with_status(&max, &half_ulp.scale(-1, rm), rm);
+    assert!(r == max && s == Status::INEXACT);
+
+    // Underflow: the result is tiny and inexact.
+    let tiny = Float::from_f64(f64::MIN_POSITIVE);
+    let three = Float::from_u64(FP64, 3);
+    let (_, s) = Float::div_with_status(&tiny, &three, rm);
+    assert_eq!(s, Status::UNDERFLOW | Status::INEXACT);
+    // An exact denormal result does not underflow.
+    let two = Float::from_u64(FP64, 2);
+    let (r, s) = Float::div_with_status(&tiny, &two, rm);
+    assert!(s.is_ok());
+    assert_eq!(r.as_f64(), f64::MIN_POSITIVE / 2.);
+    // The tininess is detected before rounding, so rounding a tiny result up
+    // to the smallest normal number still underflows.
+    let almost_one = Float::from_f64(1. - f64::EPSILON / 2.);
+    let (r, s) = Float::mul_with_status(&tiny, &almost_one, rm);
+    assert_eq!(r.as_f64(), f64::MIN_POSITIVE);
+    assert_eq!(s, Status::UNDERFLOW | Status::INEXACT);
+
+    // Exact operations.
+    assert!(Float::add_with_status(&one, &one, rm).1.is_ok());
+    assert!(Float::sub_with_status(&one, &one, rm).1.is_ok());
+    assert!(Float::mul_with_status(&three, &two, rm).1.is_ok());
+    assert!(Float::div_with_status(&three, &two, rm).1.is_ok());
+    assert!(Float::fma_with_status(&three, &two, &one, rm).1.is_ok());
+}
+
+#[test]
+fn test_status_inexact_random_vals() {
+    use super::utils;
+    use crate::{Semantics, FP64};
+
+    // Check that the inexact flag is raised exactly when the rounded result is
+    // different from the exact result, which is computed in a wide format.
+    let wide = Semantics::new(16, 2200, RoundingMode::NearestTiesToEven);
+    let rm = RoundingMode::NearestTiesToEven;
+    let mut lfsr = utils::Lfsr::new();
+
+    fn is_exact(res: &Float, exact: &Float) -> bool {
+        res.cast(exact.get_semantics()) == *exact
+    }
+
+    for _ in 0..2000 {
+        let a = Float::from_f64(f64::from_bits(lfsr.get64() >> 2));
+        let b = Float::from_f64(f64::from_bits(lfsr.get64() >> 2));
+        let c = Float::from_u64(FP64, lfsr.get64() & 0xffff);
+        let (aw, bw) = (a.cast(wide), b.cast(wide));
+
+        let (res, status) = Float::add_with_status(&a, &b, rm);
+        let exact = &aw + &bw;
+        assert_eq!(status.contains(Status::INEXACT), !is_exact(&res, &exact));
+
+        let (res, status) = Float::mul_with_status(&a, &c, rm);
+        let exact = &aw * &c.cast(wide);
+        assert_eq!(status.contains(Status::INEXACT), !is_exact(&res, &exact));
+
+        let (res, status) = Float::div_with_status(&a, &c, rm);
+        let is_inexact = (&res.cast(wide) * &c.cast(wide)) != aw;
+        assert_eq!(status.contains(Status::INEXACT), is_inexact);
+    }
+}
+
 macro_rules! declare_operator {
     ($trait_name:ident,
      $func_name:ident,
@@ -697,25 +1224,47 @@ macro_rules! declare_assign_operator {
         impl $trait_name for Float {
             fn $func_name(&mut self, rhs: Self) {
                 let sem = self.get_semantics();
-                *self =
-                    Self::$func_impl_name(self, &rhs, sem.get_rounding_mode());
+                self.$func_impl_name(&rhs, sem.get_rounding_mode());
             }
         }
 
         impl $trait_name<&Float> for Float {
             fn $func_name(&mut self, rhs: &Self) {
                 let sem = self.get_semantics();
-                *self =
-                    Self::$func_impl_name(self, rhs, sem.get_rounding_mode());
+                self.$func_impl_name(rhs, sem.get_rounding_mode());
             }
         }
     };
 }
 
-declare_assign_operator!(AddAssign, add_assign, add_with_rm);
-declare_assign_operator!(SubAssign, sub_assign, sub_with_rm);
-declare_assign_operator!(MulAssign, mul_assign, mul_with_rm);
-declare_assign_operator!(DivAssign, div_assign, div_with_rm);
+impl Float {
+    /// Computes self*rhs using the rounding mode `rm`, and stores the result
+    /// in self. This reuses the storage of self.
+    pub fn mul_assign_with_rm(&mut self, rhs: &Self, rm: RoundingMode) {
+        if self.is_normal() && rhs.is_normal() {
+            let sign = self.get_sign() ^ rhs.get_sign();
+            let loss = self.mul_normals_in_place(rhs, sign);
+            self.normalize(rm, loss);
+            return;
+        }
+        *self = Self::mul_with_rm(self, rhs, rm);
+    }
+    /// Computes self/rhs using the rounding mode `rm`, and stores the result
+    /// in self. This reuses the storage of self.
+    pub fn div_assign_with_rm(&mut self, rhs: &Self, rm: RoundingMode) {
+        if self.is_normal() && rhs.is_normal() {
+            let loss = self.div_normals_in_place(rhs);
+            self.normalize(rm, loss);
+            return;
+        }
+        *self = Self::div_with_rm(self, rhs, rm);
+    }
+}
+
+declare_assign_operator!(AddAssign, add_assign, add_assign_with_rm);
+declare_assign_operator!(SubAssign, sub_assign, sub_assign_with_rm);
+declare_assign_operator!(MulAssign, mul_assign, mul_assign_with_rm);
+declare_assign_operator!(DivAssign, div_assign, div_assign_with_rm);
 
 #[test]
 fn test_operators() {
@@ -5,11 +5,16 @@ extern crate alloc;
 
 use core::cmp::Ordering;
 use core::ops::{
-    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign,
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor,
+    BitXorAssign, Div, DivAssign, Mul, MulAssign, Not, Rem, RemAssign, Shl,
+    ShlAssign, Shr, ShrAssign, Sub, SubAssign,
 };
 
 use alloc::vec::Vec;
 
+use crate::parts::Parts;
+use crate::BigSInt;
+
 /// Reports the kind of values that are lost when we shift right bits. In some
 /// context this used as the two guard bits.
 #[derive(Debug, Clone, Copy)]
@@ -52,8 +57,8 @@ impl LossFraction {
 }
 /// This is an arbitrary-size unsigned big number implementation. It is used to
 /// store the mantissa of the floating point number. The BigInt data structure
-/// is backed by `Vec<u64>`, and the data is heap-allocated. BigInt implements
-/// the basic arithmetic operations such as add, sub, div, mul, etc.
+/// stores a few words inline, and larger numbers are heap-allocated. BigInt
+/// implements the basic arithmetic operations such as add, sub, div, mul, etc.
 ///
 /// # Examples
 ///
@@ -72,7 +77,7 @@ impl LossFraction {
 ///
 #[derive(Debug, Clone)]
 pub struct BigInt {
-    parts: Vec<u64>,
+    parts: Parts,
 }
 
 impl BigInt {
@@ -107,16 +112,18 @@ impl BigInt {
 
     /// Create a number and set the lowest 64 bits to `val`.
     pub fn from_u64(val: u64) -> Self {
-        let vec = Vec::from([val]);
-        BigInt { parts: vec }
+        BigInt {
+            parts: Parts::from_slice(&[val]),
+        }
     }
 
     /// Create a number and set the lowest 128 bits to `val`.
     pub fn from_u128(val: u128) -> Self {
         let a = val as u64;
         let b = (val >> 64) as u64;
-        let vec = Vec::from([a, b]);
-        BigInt { parts: vec }
+        BigInt {
+            parts: Parts::from_slice(&[a, b]),
+        }
     }
 
     /// Create a pseudorandom number with `parts` number of parts in the word.
@@ -185,6 +192,26 @@ impl BigInt {
         self.parts[which_word] ^= 1 << bit_in_word;
     }
 
+    /// Returns the value of the bit at index `bit_num`.
+    pub fn get_bit(&self, bit_num: usize) -> bool {
+        let which_word = bit_num / u64::BITS as usize;
+        let bit_in_word = bit_num % u64::BITS as usize;
+        which_word < self.len()
+            && (self.parts[which_word] >> bit_in_word) & 1 == 1
+    }
+
+    /// Set the `bit_num` bit to `val`.
+    pub fn set_bit(&mut self, bit_num: usize, val: bool) {
+        if self.get_bit(bit_num) != val {
+            self.flip_bit(bit_num);
+        }
+    }
+
+    /// Returns the number of bits that are set to '1'.
+    pub fn count_ones(&self) -> usize {
+        self.parts.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
     /// Zero out all of the bits above `bits`.
     pub fn mask(&mut self, bits: usize) {
         let mut bits = bits;
@@ -256,21 +283,23 @@ impl BigInt {
 
     // Construct a bigint from the words in 'parts'.
     pub fn from_parts(parts: &[u64]) -> Self {
-        let parts: Vec<u64> = parts.to_vec();
-        BigInt { parts }
+        BigInt {
+            parts: Parts::from_slice(parts),
+        }
     }
 
     // Construct a bigint from an iterator that generates u64 parts.
     // Take the first 'k' words.
     pub fn from_iter<I: Iterator<Item = u64>>(iter: &mut I, k: usize) -> Self {
-        let parts: Vec<u64> = iter.take(k).collect();
-        BigInt { parts }
+        BigInt {
+            parts: iter.take(k).collect(),
+        }
     }
 
     /// Ensure that there are at least 'size' words in the bigint.
     pub fn grow(&mut self, size: usize) {
-        for _ in self.len()..size {
-            self.parts.push(0);
+        if size > self.len() {
+            self.parts.resize(size);
         }
     }
 
@@ -290,18 +319,19 @@ impl BigInt {
     #[allow(clippy::needless_range_loop)]
     pub(crate) fn inplace_add_slice(&mut self, rhs: &[u64]) {
         self.grow(rhs.len());
+        let parts = &mut self.parts[..];
         let mut carry: bool = false;
         for i in 0..rhs.len() {
-            let first = self.parts[i].overflowing_add(rhs[i]);
+            let first = parts[i].overflowing_add(rhs[i]);
             let second = first.0.overflowing_add(carry as u64);
             carry = first.1 || second.1;
-            self.parts[i] = second.0;
+            parts[i] = second.0;
         }
         // Continue to propagate the carry flag.
-        for i in rhs.len()..self.len() {
-            let second = self.parts[i].overflowing_add(carry as u64);
+        for i in rhs.len()..parts.len() {
+            let second = parts[i].overflowing_add(carry as u64);
             carry = second.1;
-            self.parts[i] = second.0;
+            parts[i] = second.0;
         }
         if carry {
             self.parts.push(1);
@@ -322,27 +352,28 @@ impl BigInt {
     #[allow(clippy::needless_range_loop)]
     fn inplace_sub_slice(&mut self, rhs: &[u64], bottom_zeros: usize) -> bool {
         self.grow(rhs.len());
+        let parts = &mut self.parts[..];
         let mut borrow: bool = false;
         // Do the part of the vectors that both sides have.
 
         for i in bottom_zeros..rhs.len() {
-            let first = self.parts[i].overflowing_sub(rhs[i]);
+            let first = parts[i].overflowing_sub(rhs[i]);
             let second = first.0.overflowing_sub(borrow as u64);
             borrow = first.1 || second.1;
-            self.parts[i] = second.0;
+            parts[i] = second.0;
         }
         // Propagate the carry bit.
-        for i in rhs.len()..self.len() {
-            let second = self.parts[i].overflowing_sub(borrow as u64);
-            self.parts[i] = second.0;
+        for i in rhs.len()..parts.len() {
+            let second = parts[i].overflowing_sub(borrow as u64);
+            parts[i] = second.0;
             borrow = second.1;
         }
         self.shrink();
         borrow
     }
 
-    fn zeros(size: usize) -> Vec<u64> {
-        core::iter::repeat(0).take(size).collect()
+    fn zeros(size: usize) -> Parts {
+        Parts::zeros(size)
     }
 
     /// Multiply `rhs` to self, and return true if the operation overflowed.
@@ -350,7 +381,7 @@ impl BigInt {
         if self.len() > KARATSUBA_SIZE_THRESHOLD
             || rhs.len() > KARATSUBA_SIZE_THRESHOLD
         {
-            *self = Self::mul_karatsuba(self, rhs);
+            *self = Self::mul_slices(self, rhs);
             return;
         }
         self.inplace_mul_slice(rhs);
@@ -359,12 +390,13 @@ impl BigInt {
     /// Implements multiplication of the 'rhs' sequence of words to this number.
     fn inplace_mul_slice(&mut self, rhs: &[u64]) {
         let size = self.len() + rhs.len() + 1;
-        let mut parts = Self::zeros(size);
-        let mut carries = Self::zeros(size);
+        let mut prod = Self::zeros(size);
+        let mut carry_words = Self::zeros(size);
+        let (parts, carries) = (&mut prod[..], &mut carry_words[..]);
 
-        for i in 0..self.len() {
+        for (i, &word) in self.parts.iter().enumerate() {
             for j in 0..rhs.len() {
-                let pi = self.parts[i] as u128;
+                let pi = word as u128;
                 let pij = pi * rhs[j] as u128;
 
                 let add0 = parts[i + j].overflowing_add(pij as u64);
@@ -377,9 +409,9 @@ impl BigInt {
         }
         self.grow(size);
         let mut carry: u64 = 0;
-        for i in 0..size {
+        for (i, word) in self.parts.iter_mut().enumerate() {
             let add0 = parts[i].overflowing_add(carry);
-            self.parts[i] = add0.0;
+            *word = add0.0;
             carry = add0.1 as u64 + carries[i];
         }
         self.shrink();
@@ -388,51 +420,61 @@ impl BigInt {
 
     /// Divide self by `divisor`, and return the reminder.
     pub fn inplace_div(&mut self, divisor: &Self) -> Self {
-        let mut dividend = self.clone();
-        let mut divisor = divisor.clone();
-        let mut quotient = Self::zero();
-
         // Single word division.
-        if self.len() == 1 && divisor.parts.len() == 1 {
-            let a = dividend.get_part(0);
+        if self.len() == 1 && divisor.len() == 1 {
+            let a = self.get_part(0);
             let b = divisor.get_part(0);
-            let res = a / b;
-            let rem = a % b;
-            self.parts[0] = res;
-            return Self::from_u64(rem);
+            assert_ne!(b, 0, "division by zero");
+            self.parts[0] = a / b;
+            return Self::from_u64(a % b);
         }
 
-        let dividend_msb = dividend.msb_index();
-        let divisor_msb = divisor.msb_index();
-        assert_ne!(divisor_msb, 0, "division by zero");
+        let (quotient, rem) = self.divrem(divisor);
+        *self = quotient;
+        rem
+    }
 
-        if divisor_msb > dividend_msb {
-            let ret = self.clone();
-            *self = Self::zero();
-            return ret;
-        }
+    /// Divide this number by `divisor`, and return the quotient and the
+    /// reminder.
+    pub fn divrem(&self, divisor: &Self) -> (Self, Self) {
+        let divisor_words = divisor.msb_index().div_ceil(64);
+        let dividend_words = self.msb_index().div_ceil(64);
+        assert_ne!(divisor_words, 0, "division by zero");
 
-        // Align the first bit of the divisor with the first bit of the
-        // dividend.
-        let bits = dividend_msb - divisor_msb;
-        divisor.shift_left(bits);
+        if divisor_words == 1 {
+            let mut quotient = self.clone();
+            let rem = quotient.inplace_div_word(divisor.get_part(0));
+            return (quotient, Self::from_u64(rem));
+        }
 
-        // Perform the long division.
-        for i in (0..bits + 1).rev() {
-            // Find out how many of the lower words of the divisor are zeros.
-            let low_zeros = i / 64;
+        if *self < *divisor {
+            return (Self::zero(), self.clone());
+        }
 
-            if dividend >= divisor {
-                let overflow = dividend.inplace_sub_slice(&divisor, low_zeros);
-                debug_assert!(!overflow);
-                quotient.flip_bit(i);
-            }
-            divisor.shift_right(1);
+        // Newton division is faster only if both the divisor and the quotient
+        // are large.
+        let quotient_words = dividend_words - divisor_words + 1;
+        if divisor_words.min(quotient_words) >= NEWTON_DIV_SIZE_THRESHOLD {
+            return Self::div_newton(self, divisor);
         }
+        Self::div_knuth(
+            &self.parts[..dividend_words],
+            &divisor.parts[..divisor_words],
+        )
+    }
 
-        *self = quotient;
+    /// Divide self by the single word `divisor`, and return the reminder.
+    pub(crate) fn inplace_div_word(&mut self, divisor: u64) -> u64 {
+        assert_ne!(divisor, 0, "division by zero");
+        let divisor = divisor as u128;
+        let mut rem: u128 = 0;
+        for word in self.parts.iter_mut().rev() {
+            let cur = (rem << 64) | *word as u128;
+            *word = (cur / divisor) as u64;
+            rem = cur % divisor;
+        }
         self.shrink();
-        dividend
+        rem as u64
     }
 
     /// Shift the bits in the numbers `bits` to the left.
@@ -440,15 +482,14 @@ impl BigInt {
         let words_to_shift = bits / u64::BITS as usize;
         let bits_in_word = bits % u64::BITS as usize;
 
-        for _ in 0..words_to_shift + 1 {
-            self.parts.push(0);
-        }
+        self.grow(self.len() + words_to_shift + 1);
+        let parts = &mut self.parts[..];
 
         // If we only need to move blocks.
         if bits_in_word == 0 {
-            for i in (0..self.len()).rev() {
-                self.parts[i] = if i >= words_to_shift {
-                    self.parts[i - words_to_shift]
+            for i in (0..parts.len()).rev() {
+                parts[i] = if i >= words_to_shift {
+                    parts[i - words_to_shift]
                 } else {
                     0
                 };
@@ -456,20 +497,20 @@ impl BigInt {
             return;
         }
 
-        for i in (0..self.len()).rev() {
+        for i in (0..parts.len()).rev() {
             let left_val = if i >= words_to_shift {
-                self.parts[i - words_to_shift]
+                parts[i - words_to_shift]
             } else {
                 0
             };
             let right_val = if i > words_to_shift {
-                self.parts[i - words_to_shift - 1]
+                parts[i - words_to_shift - 1]
             } else {
                 0
             };
             let right = right_val >> (u64::BITS as usize - bits_in_word);
             let left = left_val << bits_in_word;
-            self.parts[i] = left | right;
+            parts[i] = left | right;
         }
     }
 
@@ -478,11 +519,14 @@ impl BigInt {
         let words_to_shift = bits / u64::BITS as usize;
         let bits_in_word = bits % u64::BITS as usize;
 
+        let parts = &mut self.parts[..];
+        let len = parts.len();
+
         // If we only need to move blocks.
         if bits_in_word == 0 {
-            for i in 0..self.len() {
-                self.parts[i] = if i + words_to_shift < self.len() {
-                    self.parts[i + words_to_shift]
+            for i in 0..len {
+                parts[i] = if i + words_to_shift < len {
+                    parts[i + words_to_shift]
                 } else {
                     0
                 };
@@ -491,24 +535,64 @@ impl BigInt {
             return;
         }
 
-        for i in 0..self.len() {
-            let left_val = if i + words_to_shift < self.len() {
-                self.parts[i + words_to_shift]
+        for i in 0..len {
+            let left_val = if i + words_to_shift < len {
+                parts[i + words_to_shift]
             } else {
                 0
             };
-            let right_val = if i + 1 + words_to_shift < self.len() {
-                self.parts[i + 1 + words_to_shift]
+            let right_val = if i + 1 + words_to_shift < len {
+                parts[i + 1 + words_to_shift]
             } else {
                 0
             };
             let right = right_val << (u64::BITS as usize - bits_in_word);
             let left = left_val >> bits_in_word;
-            self.parts[i] = left | right;
+            parts[i] = left | right;
+        }
+        self.shrink();
+    }
+
+    /// Replace this number with the bitwise AND of this number and `rhs`.
+    pub fn inplace_and(&mut self, rhs: &Self) {
+        for i in 0..self.len() {
+            self.parts[i] &= rhs.parts.get(i).copied().unwrap_or(0);
+        }
+        self.shrink();
+    }
+
+    /// Replace this number with the bitwise OR of this number and `rhs`.
+    pub fn inplace_or(&mut self, rhs: &Self) {
+        self.grow(rhs.len());
+        for (i, part) in rhs.parts.iter().enumerate() {
+            self.parts[i] |= part;
+        }
+    }
+
+    /// Replace this number with the bitwise XOR of this number and `rhs`.
+    pub fn inplace_xor(&mut self, rhs: &Self) {
+        self.grow(rhs.len());
+        for (i, part) in rhs.parts.iter().enumerate() {
+            self.parts[i] ^= part;
+        }
+        self.shrink();
+    }
+
+    /// Invert the lowest `bits` bits, and zero out all of the bits above.
+    pub fn inplace_not(&mut self, bits: usize) {
+        self.grow(bits.div_ceil(u64::BITS as usize));
+        for part in self.parts.iter_mut() {
+            *part = !*part;
         }
+        self.mask(bits);
         self.shrink();
     }
 
+    /// Replace this number with the reminder of the division by `divisor`.
+    pub fn inplace_rem(&mut self, divisor: &Self) {
+        *self = self.inplace_div(divisor);
+    }
+
     /// Raise this number to the power of `exp` and return the value.
     pub fn powi(&self, mut exp: u64) -> Self {
         let mut v = Self::one();
@@ -938,6 +1022,71 @@ declare_assign_operator!(SubAssign, sub_assign, inplace_sub);
 declare_assign_operator!(MulAssign, mul_assign, inplace_mul);
 declare_assign_operator!(DivAssign, div_assign, inplace_div);
 
+declare_operator!(BitAnd, bitand, inplace_and);
+declare_operator!(BitOr, bitor, inplace_or);
+declare_operator!(BitXor, bitxor, inplace_xor);
+declare_operator!(Rem, rem, inplace_rem);
+
+declare_assign_operator!(BitAndAssign, bitand_assign, inplace_and);
+declare_assign_operator!(BitOrAssign, bitor_assign, inplace_or);
+declare_assign_operator!(BitXorAssign, bitxor_assign, inplace_xor);
+declare_assign_operator!(RemAssign, rem_assign, inplace_rem);
+
+macro_rules! declare_shift_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $assign_trait_name:ident,
+     $assign_func_name:ident,
+     $func_impl_name:ident) => {
+        // Self << usize -> Self
+        impl $trait_name<usize> for BigInt {
+            type Output = Self;
+            fn $func_name(self, rhs: usize) -> Self::Output {
+                let mut n = self;
+                n.$func_impl_name(rhs);
+                n
+            }
+        }
+
+        // &Self << usize -> Self
+        impl $trait_name<usize> for &BigInt {
+            type Output = BigInt;
+            fn $func_name(self, rhs: usize) -> Self::Output {
+                let mut n = self.clone();
+                n.$func_impl_name(rhs);
+                n
+            }
+        }
+
+        impl $assign_trait_name<usize> for BigInt {
+            fn $assign_func_name(&mut self, rhs: usize) {
+                self.$func_impl_name(rhs);
+            }
+        }
+    };
+}
+
+declare_shift_operator!(Shl, shl, ShlAssign, shl_assign, shift_left);
+declare_shift_operator!(Shr, shr, ShrAssign, shr_assign, shift_right);
+
+/// Inverts the bits of the number, up to and including the most significant
+/// '1'. For example, !0b1011 is 0b0100. Use `inplace_not` to select the width.
+impl Not for BigInt {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        let mut n = self;
+        n.inplace_not(n.msb_index());
+        n
+    }
+}
+
+impl Not for &BigInt {
+    type Output = BigInt;
+    fn not(self) -> Self::Output {
+        !self.clone()
+    }
+}
+
 #[test]
 fn test_bigint_operators() {
     type BI = BigInt;
@@ -949,6 +1098,60 @@ fn test_bigint_operators() {
     assert_eq!((&y + &y).as_u64(), 2);
 }
 
+#[test]
+fn test_bigint_bitwise_operators() {
+    use crate::utils::Lfsr;
+
+    let mut lfsr = Lfsr::new();
+    for _ in 0..500 {
+        let a = (lfsr.get64() as u128) << (lfsr.get64() % 64);
+        let b = lfsr.get64() as u128 >> (lfsr.get64() % 64);
+        let x = BigInt::from_u128(a);
+        let y = BigInt::from_u128(b);
+        assert_eq!((&x & &y).as_u128(), a & b);
+        assert_eq!((&x | &y).as_u128(), a | b);
+        assert_eq!((&x ^ &y).as_u128(), a ^ b);
+        if b != 0 {
+            assert_eq!((&x % &y).as_u128(), a % b);
+        }
+        let shift = (lfsr.get64() % 64) as usize;
+        assert_eq!((&y << shift).as_u128(), b << shift);
+        assert_eq!((&x >> shift).as_u128(), a >> shift);
+        assert_eq!(x.count_ones(), a.count_ones() as usize);
+
+        let mut z = x.clone();
+        z &= &y;
+        z |= BigInt::from_u64(5);
+        z ^= &x;
+        z >>= 3;
+        z <<= 1;
+        assert_eq!(z.as_u128(), ((((a & b) | 5) ^ a) >> 3) << 1);
+    }
+
+    let x = BigInt::from_u64(0b1011);
+    assert_eq!((!&x).as_u64(), 0b0100);
+    assert_eq!((!BigInt::zero()).as_u64(), 0);
+    let mut y = BigInt::from_u64(0b1011);
+    y.inplace_not(70);
+    assert_eq!(y, BigInt::all1s(70) ^ BigInt::from_u64(0b1011));
+    assert_eq!(BigInt::from_u64(17) % 5, BigInt::from_u64(2));
+}
+
+#[test]
+fn test_get_set_bit() {
+    let mut x = BigInt::zero();
+    x.set_bit(100, true);
+    x.set_bit(3, true);
+    x.set_bit(3, true);
+    assert!(x.get_bit(100) && x.get_bit(3));
+    assert!(!x.get_bit(4) && !x.get_bit(1000));
+    assert_eq!(x.count_ones(), 2);
+    x.set_bit(100, false);
+    x.set_bit(5, false);
+    assert_eq!(x.as_u64(), 8);
+    assert_eq!(x.count_ones(), 1);
+}
+
 #[test]
 fn test_all1s_ctor() {
     type BI = BigInt;
@@ -1023,24 +1226,24 @@ fn test_mul_div_encode_decode() {
 }
 
 impl BigInt {
-    /// Converts this number into a sequence of digits in the range 0..DIGIT.
+    /// Converts this number into a sequence of digits in the range 0..radix.
     /// Use a recursive algorithm to split the number in half, if the number is
     /// too big.
     /// Return the number of digits that were converted.
-    fn to_digits_impl<const DIGIT: u8>(
+    fn to_digits_impl(
         num: &mut BigInt,
+        radix: u8,
         num_digits: usize,
         output: &mut Vec<u8>,
     ) -> usize {
         const SPLIT_WORD_THRESHOLD: usize = 5;
 
-        // Figure out how many digits fit in a single word.
-        let bits_per_digit = (8 - DIGIT.leading_zeros()) as usize;
-        let digits_per_word = 64 / bits_per_digit;
-        let digit = DIGIT as u64;
+        let digits_per_word = Self::digits_per_word(radix);
+        let digit = radix as u64;
 
-        // If the word is too big, split it in half.
-        let len = num.len();
+        // If the word is too big, split it in half. The split point must not
+        // exceed the number of digits that we need to extract.
+        let len = num.len().min(num_digits / digits_per_word);
         if len > SPLIT_WORD_THRESHOLD {
             let half = len / 2 - 1;
             // Figure out how many digits to extract:
@@ -1051,8 +1254,8 @@ impl BigInt {
             let mut rem = num.inplace_div(&mega_digit);
 
             // Convert the two parts to digits:
-            let tail = Self::to_digits_impl::<DIGIT>(&mut rem, k, output);
-            let hd = Self::to_digits_impl::<DIGIT>(num, num_digits - k, output);
+            let tail = Self::to_digits_impl(&mut rem, radix, k, output);
+            let hd = Self::to_digits_impl(num, radix, num_digits - k, output);
             debug_assert_eq!(tail, k);
             debug_assert_eq!(hd, num_digits - k);
             return num_digits;
@@ -1068,43 +1271,93 @@ impl BigInt {
             let mut rem = num.inplace_div(&divisor);
             // This is fast because we operate on a single word.
             extracted += digits_per_word;
-            Self::extract_digits::<DIGIT>(digits_per_word, &mut rem, output);
+            Self::extract_digits(radix, digits_per_word, &mut rem, output);
         }
 
         // Handle the rest of the digits.
         let iters = num_digits % digits_per_word;
-        Self::extract_digits::<DIGIT>(iters, num, output);
+        Self::extract_digits(radix, iters, num, output);
         extracted += iters;
 
         extracted
     }
 
+    /// Returns the number of digits in the range 0..radix that fit in a
+    /// single word.
+    fn digits_per_word(radix: u8) -> usize {
+        let mut digits = 0;
+        let mut val: u64 = 1;
+        while let Some(next) = val.checked_mul(radix as u64) {
+            val = next;
+            digits += 1;
+        }
+        digits
+    }
+
     // Extract 'iter' digits from 'num', one by one, and push them to 'vec'.
-    fn extract_digits<const DIGIT: u8>(
+    fn extract_digits(
+        radix: u8,
         iter: usize,
         num: &mut BigInt,
         vec: &mut Vec<u8>,
     ) {
-        let digit = BigInt::from_u64(DIGIT as u64);
+        let digit = BigInt::from_u64(radix as u64);
         for _ in 0..iter {
             let d = num.inplace_div(&digit).as_u64();
             vec.push(d as u8);
         }
     }
 
+    /// Converts the sequence of digits in the range 0..radix, starting from
+    /// the most significant digit, into a number. Use a recursive algorithm
+    /// to split the digits in half, if there are too many digits.
+    pub(crate) fn from_radix_digits(digits: &[u8], radix: u8) -> BigInt {
+        const SPLIT_WORD_THRESHOLD: usize = 5;
+
+        // Figure out how many digits fit in a single word.
+        let bits_per_digit = (8 - radix.leading_zeros()) as usize;
+        let digits_per_word = 64 / bits_per_digit;
+        let digit = radix as u64;
+
+        // If there are too many digits, split them in half, and compute
+        // high * radix^k + low.
+        if digits.len() > digits_per_word * SPLIT_WORD_THRESHOLD {
+            let k = digits.len() / 2;
+            let (high, low) = digits.split_at(digits.len() - k);
+            let mut num = Self::from_radix_digits(high, radix);
+            num.inplace_mul(&BigInt::from_u64(digit).powi(k as u64));
+            num.inplace_add(&Self::from_radix_digits(low, radix));
+            return num;
+        }
+
+        // Add the digits one word at a time.
+        let mut num = BigInt::zero();
+        for chunk in digits.chunks(digits_per_word) {
+            let word = chunk.iter().fold(0, |acc, d| acc * digit + *d as u64);
+            num.inplace_mul(&BigInt::from_u64(digit.pow(chunk.len() as u32)));
+            num.inplace_add(&BigInt::from_u64(word));
+        }
+        num
+    }
+
     /// Converts this number into a sequence of digits in the range 0..DIGIT.
     pub(crate) fn to_digits<const DIGIT: u8>(&self) -> Vec<u8> {
+        self.to_radix_digits(DIGIT)
+    }
+
+    /// Converts this number into a sequence of digits in the range 0..radix.
+    pub(crate) fn to_radix_digits(&self, radix: u8) -> Vec<u8> {
+        debug_assert!(radix >= 2);
         let mut num = self.clone();
         num.shrink();
 
         let mut output: Vec<u8> = Vec::new();
 
         while !num.is_zero() {
-            let len = num.len();
-            // Figure out how many digits fit in the number.
-            // See 'get_decimal_accuracy'.
-            let digits = (len * 64 * 59) / 196;
-            Self::to_digits_impl::<DIGIT>(&mut num, digits, &mut output);
+            // Extract the digits that fit in the words of the number. The
+            // loop converts the rest of the digits, if there are any.
+            let digits = num.len() * Self::digits_per_word(radix);
+            Self::to_digits_impl(&mut num, radix, digits, &mut output);
         }
 
         // Eliminate leading zeros.
@@ -1150,14 +1403,73 @@ pub fn test_bigint_to_digits() {
     let num = BigInt::from_u128(123_456_123_456_987_654_987_654u128);
     let digits = num.to_digits::<10>();
     assert_eq!(vec_to_string(digits, 10), "123456123456987654987654");
+
+    // Test long numbers in radixes that are not powers of two.
+    let num = BigInt::from_u64(3).powi(2000);
+    assert!(num.len() >= 40);
+    let digits = num.to_radix_digits(3);
+    assert_eq!(digits.len(), 2001);
+    assert_eq!(digits[0], 1);
+    assert!(digits[1..].iter().all(|d| *d == 0));
+    for radix in [3, 7, 10, 36] {
+        let num = BigInt::pseudorandom(48, 7);
+        let digits = num.to_radix_digits(radix);
+        assert_eq!(BigInt::from_radix_digits(&digits, radix), num);
+    }
 }
 
 /// Bigint numbers above this size use the karatsuba algorithm for
 /// multiplication. The number represents the number of words in the bigint.
 /// Numbers below this threshold use the traditional O(n^2) multiplication.
 const KARATSUBA_SIZE_THRESHOLD: usize = 64;
+const TOOM3_SIZE_THRESHOLD: usize = 256;
+const NTT_SIZE_THRESHOLD: usize = 2048;
 
 impl BigInt {
+    /// Multiply the two sequences of words, and select the algorithm based on
+    /// the size of the shorter input.
+    fn mul_slices(lhs: &[u64], rhs: &[u64]) -> BigInt {
+        let (short, long) = if lhs.len() <= rhs.len() {
+            (lhs, rhs)
+        } else {
+            (rhs, lhs)
+        };
+
+        if short.len() >= NTT_SIZE_THRESHOLD {
+            return Self::mul_ntt(lhs, rhs);
+        }
+        if short.len() < TOOM3_SIZE_THRESHOLD {
+            return Self::mul_karatsuba(lhs, rhs);
+        }
+        if long.len() <= 2 * short.len() {
+            return Self::mul_toom3(lhs, rhs);
+        }
+
+        // Toom-3 is inefficient when the inputs are unbalanced, so split the
+        // long input into chunks, and add the partial products at their
+        // offset.
+        let mut res = BigInt::zero();
+        res.grow(long.len() + short.len() + 1);
+        for (i, chunk) in long.chunks(short.len()).enumerate() {
+            let prod = Self::mul_slices(chunk, short);
+            let offset = i * short.len();
+            let mut carry = false;
+            for j in offset..res.len() {
+                let part = prod.parts.get(j - offset).copied().unwrap_or(0);
+                if j - offset >= prod.len() && !carry {
+                    break;
+                }
+                let first = res.parts[j].overflowing_add(part);
+                let second = first.0.overflowing_add(carry as u64);
+                carry = first.1 || second.1;
+                res.parts[j] = second.0;
+            }
+            debug_assert!(!carry);
+        }
+        res.shrink();
+        res
+    }
+
     fn mul_karatsuba(lhs: &[u64], rhs: &[u64]) -> BigInt {
         // Algorithm description:
         // https://en.wikipedia.org/wiki/Karatsuba_algorithm
@@ -1236,6 +1548,600 @@ fn test_mul_karatsuba() {
     }
 }
 
+impl BigInt {
+    /// Returns the words `[k * idx .. k * (idx + 1)]` of the input, as a
+    /// signed number.
+    fn toom_part(x: &[u64], k: usize, idx: usize) -> BigSInt {
+        let lo = (k * idx).min(x.len());
+        let hi = (k * (idx + 1)).min(x.len());
+        if lo == hi {
+            return BigSInt::zero();
+        }
+        BigSInt::from(BigInt::from_parts(&x[lo..hi]))
+    }
+
+    /// Evaluate the polynomial x0 + x1*t + x2*t^2 at the points 0, 1, -1, -2
+    /// and infinity.
+    fn toom_evaluate(x: &[u64], k: usize) -> [BigSInt; 5] {
+        let x0 = Self::toom_part(x, k, 0);
+        let x1 = Self::toom_part(x, k, 1);
+        let x2 = Self::toom_part(x, k, 2);
+        let x02 = &x0 + &x2;
+        let p1 = &x02 + &x1;
+        let pm1 = &x02 - &x1;
+        let pm2 = &pm1 + &x2;
+        let pm2 = &(&pm2 + &pm2) - &x0;
+        [x0, p1, pm1, pm2, x2]
+    }
+
+    /// Divide the signed number `x` by `divisor`. The division must be exact.
+    fn toom_div_exact(x: &BigSInt, divisor: u64) -> BigSInt {
+        let mut magnitude = x.get_magnitude();
+        let rem = magnitude.inplace_div_word(divisor);
+        debug_assert_eq!(rem, 0);
+        BigSInt::new(x.is_negative(), magnitude)
+    }
+
+    fn mul_toom3(lhs: &[u64], rhs: &[u64]) -> BigInt {
+        // Algorithm description:
+        // https://en.wikipedia.org/wiki/Toom%E2%80%93Cook_multiplication
+        // We use the evaluation points and the interpolation sequence of
+        // Bodrato (0, 1, -1, -2, inf).
+
+        // Split the inputs into three parts of k words.
+        let k = lhs.len().max(rhs.len()).div_ceil(3);
+        let p = Self::toom_evaluate(lhs, k);
+        let q = Self::toom_evaluate(rhs, k);
+
+        // Multiply the values at each of the points.
+        let [r0, r1, rm1, rm2, rinf] = [0, 1, 2, 3, 4].map(|i| &p[i] * &q[i]);
+
+        // Interpolate the coefficients of the result.
+        let t3 = Self::toom_div_exact(&(&rm2 - &r1), 3);
+        let t1 = Self::toom_div_exact(&(&r1 - &rm1), 2);
+        let t2 = &rm1 - &r0;
+        let t3 = Self::toom_div_exact(&(&t2 - &t3), 2) + &(&rinf + &rinf);
+        let t2 = &(&t2 + &t1) - &rinf;
+        let t1 = &t1 - &t3;
+
+        // Add the coefficients together, at their offset.
+        let mut res = BigInt::zero();
+        for (i, coef) in [r0, t1, t2, t3, rinf].iter().enumerate() {
+            debug_assert!(!coef.is_negative());
+            let mut coef = coef.get_magnitude();
+            coef.shift_left(64 * k * i);
+            res.inplace_add(&coef);
+        }
+        res
+    }
+}
+
+#[test]
+fn test_mul_toom3() {
+    use crate::utils::Lfsr;
+    let mut ll = Lfsr::new();
+
+    // Compare the multiplication of Toom-3 to Karatsuba on two random numbers
+    // of lengths 'r' and 'l'.
+    fn test_sizes(l: usize, r: usize, ll: &mut Lfsr) {
+        let a = BigInt::from_iter(ll, l);
+        let b = BigInt::from_iter(ll, r);
+        let res = BigInt::mul_toom3(&a, &b);
+        assert_eq!(res, BigInt::mul_karatsuba(&a, &b));
+    }
+
+    test_sizes(1, 1, &mut ll);
+    test_sizes(3, 2, &mut ll);
+    test_sizes(100, 1, &mut ll);
+    test_sizes(1, 100, &mut ll);
+    test_sizes(300, 300, &mut ll);
+    test_sizes(1000, 1001, &mut ll);
+    test_sizes(1000, 400, &mut ll);
+
+    for i in 250..270 {
+        for j in [1, 2, 50, 128, 200, 255, 256, 257] {
+            test_sizes(i, j, &mut ll);
+        }
+    }
+
+    // Check numbers with all of the bits set.
+    let a = BigInt::all1s(64 * 900);
+    let b = BigInt::all1s(64 * 899 + 3);
+    assert_eq!(BigInt::mul_toom3(&a, &b), BigInt::mul_karatsuba(&a, &b));
+}
+
+/// The primes that we use for the number-theoretic transform, and a generator
+/// of the multiplicative group of each prime. The primes are of the form
+/// c*2^k+1 with k >= 55, so they support transforms of length up to 2^55. The
+/// product of the primes is larger than the largest coefficient of the
+/// product, so we can reconstruct the coefficients with the Chinese remainder
+/// theorem.
+const NTT_PRIMES: [(u64, u64); 3] = [
+    (4179340454199820289, 3),
+    (2485986994308513793, 5),
+    (1945555039024054273, 5),
+];
+
+/// Returns (a * b) mod p.
+fn mul_mod(a: u64, b: u64, p: u64) -> u64 {
+    ((a as u128 * b as u128) % p as u128) as u64
+}
+
+/// Returns (a - b) mod p, where a and b are in the range [0, p).
+fn sub_mod(a: u64, b: u64, p: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        a + p - b
+    }
+}
+
+/// Returns (base ^ exp) mod p.
+fn pow_mod(mut base: u64, mut exp: u64, p: u64) -> u64 {
+    let mut res = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = mul_mod(res, base, p);
+        }
+        base = mul_mod(base, base, p);
+        exp >>= 1;
+    }
+    res
+}
+
+impl BigInt {
+    /// Perform the number-theoretic transform of `a` in place, modulo the
+    /// prime `p` with the generator `g`. The length of `a` must be a power of
+    /// two.
+    fn ntt(a: &mut [u64], p: u64, g: u64, invert: bool) {
+        let n = a.len();
+        debug_assert!(n.is_power_of_two());
+
+        // Reorder the inputs using the bit-reversal permutation.
+        let mut j = 0;
+        for i in 1..n {
+            let mut bit = n >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j ^= bit;
+            if i < j {
+                a.swap(i, j);
+            }
+        }
+
+        // Perform the butterflies, one stage at a time.
+        let mut twiddles = Vec::with_capacity(n / 2);
+        let mut len = 2;
+        while len <= n {
+            let mut root = pow_mod(g, (p - 1) / len as u64, p);
+            if invert {
+                root = pow_mod(root, p - 2, p);
+            }
+            let half = len / 2;
+            twiddles.clear();
+            let mut w = 1;
+            for _ in 0..half {
+                twiddles.push(w);
+                w = mul_mod(w, root, p);
+            }
+
+            for chunk in a.chunks_mut(len) {
+                let (lo, hi) = chunk.split_at_mut(half);
+                for i in 0..half {
+                    let u = lo[i];
+                    let v = mul_mod(hi[i], twiddles[i], p);
+                    // The primes are below 2^62, so this does not overflow.
+                    let sum = u + v;
+                    lo[i] = if sum >= p { sum - p } else { sum };
+                    hi[i] = sub_mod(u, v, p);
+                }
+            }
+            len <<= 1;
+        }
+
+        if invert {
+            let n_inv = pow_mod(n as u64 % p, p - 2, p);
+            for x in a.iter_mut() {
+                *x = mul_mod(*x, n_inv, p);
+            }
+        }
+    }
+
+    fn mul_ntt(lhs: &[u64], rhs: &[u64]) -> BigInt {
+        // Algorithm description:
+        // https://en.wikipedia.org/wiki/Sch%C3%B6nhage%E2%80%93Strassen_algorithm
+        // We compute the convolution of the words modulo three primes, and
+        // reconstruct the coefficients with the Chinese remainder theorem.
+        if lhs.is_empty() || rhs.is_empty() {
+            return BigInt::zero();
+        }
+        let coefs = lhs.len() + rhs.len() - 1;
+        let size = coefs.next_power_of_two();
+
+        let residues = NTT_PRIMES.map(|(p, g)| {
+            let mut a: Vec<u64> = lhs.iter().map(|x| x % p).collect();
+            let mut b: Vec<u64> = rhs.iter().map(|x| x % p).collect();
+            a.resize(size, 0);
+            b.resize(size, 0);
+            Self::ntt(&mut a, p, g, false);
+            Self::ntt(&mut b, p, g, false);
+            for (x, y) in a.iter_mut().zip(b.iter()) {
+                *x = mul_mod(*x, *y, p);
+            }
+            Self::ntt(&mut a, p, g, true);
+            a
+        });
+
+        // Use Garner's algorithm to find x = v0 + v1*p0 + v2*p0*p1.
+        let (p0, p1, p2) = (NTT_PRIMES[0].0, NTT_PRIMES[1].0, NTT_PRIMES[2].0);
+        let p0_inv = pow_mod(p0 % p1, p1 - 2, p1);
+        let p01_inv = pow_mod(mul_mod(p0 % p2, p1 % p2, p2), p2 - 2, p2);
+        let p01 = p0 as u128 * p1 as u128;
+        let (p01_lo, p01_hi) = (p01 as u64 as u128, p01 >> 64);
+
+        // The accumulator holds the sum of the coefficient and the carry from
+        // the previous word, in three words.
+        let mut acc = [0u64; 3];
+        let mut parts = Self::zeros(lhs.len() + rhs.len());
+        for (i, part) in parts.iter_mut().enumerate() {
+            if i < coefs {
+                let (r0, r1, r2) =
+                    (residues[0][i], residues[1][i], residues[2][i]);
+                let v1 = mul_mod(sub_mod(r1, r0 % p1, p1), p0_inv, p1);
+                let x01 = r0 as u128 + p0 as u128 * v1 as u128;
+                let x01_mod = (x01 % p2 as u128) as u64;
+                let v2 = mul_mod(sub_mod(r2, x01_mod, p2), p01_inv, p2) as u128;
+
+                // Compute the three words of x01 + p01 * v2.
+                let t = p01_lo * v2 + (x01 as u64 as u128);
+                let w0 = t as u64;
+                let t = (t >> 64) + p01_hi * v2 + (x01 >> 64);
+                let words = [w0, t as u64, (t >> 64) as u64];
+
+                let mut carry = false;
+                for (a, w) in acc.iter_mut().zip(words) {
+                    let first = a.overflowing_add(w);
+                    let second = first.0.overflowing_add(carry as u64);
+                    carry = first.1 || second.1;
+                    *a = second.0;
+                }
+                debug_assert!(!carry);
+            }
+            *part = acc[0];
+            acc = [acc[1], acc[2], 0];
+        }
+        debug_assert_eq!(acc, [0, 0, 0]);
+
+        let mut res = BigInt { parts };
+        res.shrink();
+        res
+    }
+}
+
+#[test]
+fn test_mul_ntt() {
+    use crate::utils::Lfsr;
+    let mut ll = Lfsr::new();
+
+    // Compare the multiplication of the NTT to Karatsuba on two random
+    // numbers of lengths 'r' and 'l'.
+    fn test_sizes(l: usize, r: usize, ll: &mut Lfsr) {
+        let a = BigInt::from_iter(ll, l);
+        let b = BigInt::from_iter(ll, r);
+        let res = BigInt::mul_ntt(&a, &b);
+        assert_eq!(res, BigInt::mul_karatsuba(&a, &b));
+    }
+
+    test_sizes(1, 1, &mut ll);
+    test_sizes(2, 3, &mut ll);
+    test_sizes(100, 1, &mut ll);
+    test_sizes(1, 100, &mut ll);
+    test_sizes(1000, 1000, &mut ll);
+    test_sizes(1000, 1001, &mut ll);
+    test_sizes(3000, 50, &mut ll);
+    for i in 1..40 {
+        test_sizes(i, 41 - i, &mut ll);
+    }
+
+    // The largest coefficients come from numbers with all of the bits set.
+    let a = BigInt::all1s(64 * 3000);
+    let b = BigInt::all1s(64 * 2500 - 7);
+    assert_eq!(BigInt::mul_ntt(&a, &b), BigInt::mul_karatsuba(&a, &b));
+}
+
+#[test]
+fn test_mul_select_algorithm() {
+    // Check the products of numbers around the thresholds, and unbalanced
+    // products, against Karatsuba.
+    let sizes = [
+        (KARATSUBA_SIZE_THRESHOLD + 1, KARATSUBA_SIZE_THRESHOLD + 1),
+        (TOOM3_SIZE_THRESHOLD, TOOM3_SIZE_THRESHOLD),
+        (TOOM3_SIZE_THRESHOLD, TOOM3_SIZE_THRESHOLD * 7 + 5),
+        (NTT_SIZE_THRESHOLD, NTT_SIZE_THRESHOLD + 1),
+        (NTT_SIZE_THRESHOLD * 3, 10),
+    ];
+    for (seed, (l, r)) in sizes.iter().enumerate() {
+        let a = BigInt::pseudorandom(*l, seed as u32);
+        let b = BigInt::pseudorandom(*r, seed as u32 + 100);
+        let mut prod = a.clone();
+        prod.inplace_mul(&b);
+        assert_eq!(prod, BigInt::mul_karatsuba(&a, &b));
+    }
+}
+
+/// Divisions where both the divisor and the quotient have more words than
+/// this threshold use the Newton reciprocal instead of Knuth's algorithm.
+const NEWTON_DIV_SIZE_THRESHOLD: usize = 4096;
+
+impl BigInt {
+    /// Divide `lhs` by `rhs`, and return the quotient and the reminder. The
+    /// divisor must have at least two words, and the most significant words
+    /// of both inputs must not be zero.
+    fn div_knuth(lhs: &[u64], rhs: &[u64]) -> (BigInt, BigInt) {
+        // Algorithm description:
+        // Knuth, TAOCP Vol 2, 4.3.1, Algorithm D. This follows the
+        // implementation in Hacker's Delight (divmnu), with 64-bit words.
+        let n = rhs.len();
+        let m = lhs.len();
+        debug_assert!(n >= 2 && m >= n);
+        debug_assert!(rhs[n - 1] != 0 && lhs[m - 1] != 0);
+
+        // Normalize the inputs, so that the top bit of the divisor is set.
+        let shift = rhs[n - 1].leading_zeros() as usize;
+        let mut vn = BigInt::from_parts(rhs);
+        vn.shift_left(shift);
+        let vn = &vn.parts[..n];
+        let mut un = BigInt::from_parts(lhs);
+        un.shift_left(shift);
+        let un = &mut un.parts[..m + 1];
+
+        let mut quotient = Self::zeros(m - n + 1);
+        let top = vn[n - 1] as u128;
+        for j in (0..=m - n).rev() {
+            // Estimate the next quotient word from the top two words, and
+            // refine the estimate with the next word of the divisor.
+            let num = ((un[j + n] as u128) << 64) | un[j + n - 1] as u128;
+            let mut qhat = num / top;
+            let mut rhat = num % top;
+            while qhat >> 64 != 0
+                || qhat * vn[n - 2] as u128
+                    > ((rhat << 64) | un[j + n - 2] as u128)
+            {
+                qhat -= 1;
+                rhat += top;
+                if rhat >> 64 != 0 {
+                    break;
+                }
+            }
+
+            // Multiply and subtract.
+            let mut carry: u128 = 0;
+            let mut borrow = false;
+            for i in 0..n {
+                let prod = qhat * vn[i] as u128 + carry;
+                carry = prod >> 64;
+                let first = un[i + j].overflowing_sub(prod as u64);
+                let second = first.0.overflowing_sub(borrow as u64);
+                borrow = first.1 || second.1;
+                un[i + j] = second.0;
+            }
+            let first = un[j + n].overflowing_sub(carry as u64);
+            let second = first.0.overflowing_sub(borrow as u64);
+            un[j + n] = second.0;
+            quotient[j] = qhat as u64;
+
+            // The estimate was one too large, so add the divisor back.
+            if first.1 || second.1 {
+                quotient[j] -= 1;
+                let mut carry = false;
+                for i in 0..n {
+                    let first = un[i + j].overflowing_add(vn[i]);
+                    let second = first.0.overflowing_add(carry as u64);
+                    carry = first.1 || second.1;
+                    un[i + j] = second.0;
+                }
+                un[j + n] = un[j + n].wrapping_add(carry as u64);
+            }
+        }
+
+        let mut quotient = BigInt { parts: quotient };
+        quotient.shrink();
+        let mut rem = BigInt::from_parts(&un[..n]);
+        rem.shift_right(shift);
+        (quotient, rem)
+    }
+
+    /// Returns floor(2^(2k) / d), where the most significant bit of `d` is
+    /// the k-th bit.
+    fn reciprocal(d: &BigInt, k: usize) -> BigInt {
+        debug_assert_eq!(d.msb_index(), k);
+        if k <= 64 * NEWTON_DIV_SIZE_THRESHOLD {
+            let num = BigInt::one_hot(2 * k);
+            let num_words = num.msb_index().div_ceil(64);
+            let d_words = k.div_ceil(64);
+            return Self::div_knuth(
+                &num.parts[..num_words],
+                &d.parts[..d_words],
+            )
+            .0;
+        }
+
+        // Compute the reciprocal of the top half of the divisor, and use it
+        // as the initial value of one Newton iteration, that doubles the
+        // number of correct bits: x' = x + x * (2^(2k) - d * x) / 2^(2k).
+        let h = k / 2 + 2;
+        let mut d_top = d.clone();
+        d_top.shift_right(k - h);
+        let mut x = Self::reciprocal(&d_top, h);
+        x.shift_left(k - h);
+
+        let two_k = BigSInt::from(BigInt::one_hot(2 * k));
+        let err = &two_k - &BigSInt::from(d * &x);
+        let mut delta = (&err * &BigSInt::from(x.clone())).get_magnitude();
+        delta.shift_right(2 * k);
+        if err.is_negative() {
+            let borrow = x.inplace_sub(&delta);
+            debug_assert!(!borrow);
+        } else {
+            x.inplace_add(&delta);
+        }
+
+        // Fix the last few bits of the estimate.
+        let d_signed = BigSInt::from(d.clone());
+        let mut rem = &two_k - &BigSInt::from(d * &x);
+        while rem.is_negative() {
+            x = x - 1;
+            rem += &d_signed;
+        }
+        while rem >= d_signed {
+            x = x + 1;
+            rem -= &d_signed;
+        }
+        x
+    }
+
+    /// Divide `lhs` by `rhs` by multiplying with the reciprocal of the
+    /// divisor, and return the quotient and the reminder.
+    fn div_newton(lhs: &BigInt, rhs: &BigInt) -> (BigInt, BigInt) {
+        // Algorithm description:
+        // Menezes, Handbook of Applied Cryptography, 14.42 (Barrett
+        // reduction). The reciprocal is computed with Newton's method.
+
+        // Normalize the inputs, so that the top bit of the divisor is the top
+        // bit of its top word.
+        let k = rhs.msb_index().div_ceil(64);
+        let shift = 64 * k - rhs.msb_index();
+        let mut d = rhs.clone();
+        d.shift_left(shift);
+        d.parts.truncate(k);
+        let mut a = lhs.clone();
+        a.shift_left(shift);
+        let a_words = a.msb_index().div_ceil(64);
+        let mu = Self::reciprocal(&d, 64 * k);
+
+        // Divide the dividend one chunk of k words at a time, starting from
+        // the top. The value that we divide is below d * 2^(64k), so each
+        // quotient chunk has k words.
+        let chunks = a_words.div_ceil(k);
+        let mut quotient = Self::zeros(chunks * k);
+        let mut rem = BigInt::zero();
+        for c in (0..chunks).rev() {
+            let lo = c * k;
+            let hi = ((c + 1) * k).min(a_words);
+            let mut x = rem;
+            x.shift_left(64 * k);
+            x.inplace_add_slice(&a.parts[lo..hi]);
+
+            // The estimate q is at most 2 below the real quotient.
+            let mut q = x.clone();
+            q.shift_right(64 * (k - 1));
+            q.inplace_mul(&mu);
+            q.shift_right(64 * (k + 1));
+            let borrow = x.inplace_sub(&(&q * &d));
+            debug_assert!(!borrow);
+            while x >= d {
+                let borrow = x.inplace_sub(&d);
+                debug_assert!(!borrow);
+                q.inplace_add(&Self::one());
+            }
+
+            let q_words = q.msb_index().div_ceil(64);
+            debug_assert!(q_words <= k);
+            quotient[lo..lo + q_words].copy_from_slice(&q.parts[..q_words]);
+            rem = x;
+        }
+
+        let mut quotient = BigInt { parts: quotient };
+        quotient.shrink();
+        rem.shift_right(shift);
+        (quotient, rem)
+    }
+}
+
+#[test]
+fn test_div_knuth_newton() {
+    use crate::utils::Lfsr;
+    let mut ll = Lfsr::new();
+
+    // Check the identity a = q * b + r, with r < b, on random numbers of
+    // lengths 'l' and 'r'.
+    fn test_sizes(l: usize, r: usize, ll: &mut Lfsr) {
+        let mut a = BigInt::from_iter(ll, l);
+        let mut b = BigInt::from_iter(ll, r);
+        // Make some of the numbers sparse.
+        let shift = (ll.get64() % 128) as usize;
+        if shift < 32 {
+            a.shift_right(shift);
+            b.mask(32 * r);
+            b.set_bit(64 * r - 1, true);
+        }
+        if b.is_zero() {
+            return;
+        }
+        let (q, rem) = a.divrem(&b);
+        assert!(rem < b);
+        assert_eq!(&(&q * &b) + &rem, a);
+
+        // Compare the two algorithms.
+        if a >= b && b.msb_index() > 64 {
+            let aw = a.msb_index().div_ceil(64);
+            let bw = b.msb_index().div_ceil(64);
+            let knuth = BigInt::div_knuth(&a.parts[..aw], &b.parts[..bw]);
+            let newton = BigInt::div_newton(&a, &b);
+            assert_eq!(knuth, newton);
+            assert_eq!(knuth, (q, rem));
+        }
+    }
+
+    for i in 1..12 {
+        for j in 1..12 {
+            test_sizes(i, j, &mut ll);
+        }
+    }
+    test_sizes(100, 30, &mut ll);
+    test_sizes(300, 70, &mut ll);
+    test_sizes(300, 150, &mut ll);
+    test_sizes(1000, 999, &mut ll);
+    test_sizes(2000, 500, &mut ll);
+    test_sizes(5000, 1000, &mut ll);
+
+    // The quotient estimate in Knuth's algorithm is sometimes too large.
+    let b = BigInt::from_parts(&[0, 0x8000_0000_0000_0000, 1 << 63]);
+    let a = BigInt::from_parts(&[0, 0, u64::MAX - 1, 1 << 63]);
+    let (q, rem) = a.divrem(&b);
+    assert_eq!(&(&q * &b) + &rem, a);
+    assert!(rem < b);
+
+    // Check divisors with all of the bits set.
+    for bits in [127, 128, 129, 64 * 70, 64 * 70 + 1] {
+        let a = BigInt::all1s(bits * 3);
+        let b = BigInt::all1s(bits);
+        let (q, rem) = a.divrem(&b);
+        assert!(rem.is_zero());
+        assert_eq!(q, BigInt::one_hot(2 * bits) + BigInt::one_hot(bits) + 1);
+    }
+}
+
+#[test]
+fn test_reciprocal() {
+    for k in [65usize, 100, 128, 64 * 130 + 7, 64 * 4096 + 5] {
+        for seed in 0..2 {
+            let mut d = BigInt::pseudorandom(k.div_ceil(64), seed);
+            d.mask(k);
+            d.set_bit(k - 1, true);
+            let x = BigInt::reciprocal(&d, k);
+            let knuth = BigInt::div_knuth(
+                &BigInt::one_hot(2 * k).parts[..(2 * k + 1).div_ceil(64)],
+                &d.parts[..k.div_ceil(64)],
+            )
+            .0;
+            assert_eq!(x, knuth);
+        }
+    }
+}
+
 use core::ops::Deref;
 
 impl Deref for BigInt {
@@ -1245,3 +2151,258 @@ impl Deref for BigInt {
         &self.parts[..]
     }
 }
+
+impl BigInt {
+    /// Returns the greatest common divisor of this number and `other`. This is
+    /// the binary GCD algorithm (Stein's algorithm), that only uses shifts and
+    /// subtractions.
+    pub fn gcd(&self, other: &Self) -> Self {
+        if self.is_zero() {
+            return other.clone();
+        } else if other.is_zero() {
+            return self.clone();
+        }
+
+        // Extract the common power of two.
+        let mut a = self.clone();
+        let mut b = other.clone();
+        let a_zeros = a.trailing_zeros();
+        let b_zeros = b.trailing_zeros();
+        a.shift_right(a_zeros);
+        b.shift_right(b_zeros);
+
+        // Both numbers are odd, so the difference is even.
+        loop {
+            if a < b {
+                core::mem::swap(&mut a, &mut b);
+            }
+            let borrow = a.inplace_sub(&b);
+            debug_assert!(!borrow);
+            if a.is_zero() {
+                break;
+            }
+            a.shift_right(a.trailing_zeros());
+        }
+
+        b.shift_left(a_zeros.min(b_zeros));
+        b
+    }
+
+    /// Returns the least common multiple of this number and `other`.
+    pub fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+        let mut res = self.clone();
+        res.inplace_div(&self.gcd(other));
+        res.inplace_mul(other);
+        res
+    }
+
+    /// Returns `self^exp` modulo `modulus`. The modulus must not be zero.
+    pub fn modpow(&self, exp: &Self, modulus: &Self) -> Self {
+        let mut base = self.clone().inplace_div(modulus);
+        let mut res = Self::one().inplace_div(modulus);
+
+        // Scan the bits of the exponent, from the lsb to the msb.
+        for i in 0..exp.msb_index() {
+            if exp.get_bit(i) {
+                res.inplace_mul(&base);
+                res = res.inplace_div(modulus);
+            }
+            base.inplace_mul(&base.clone());
+            base = base.inplace_div(modulus);
+        }
+        res
+    }
+
+    /// Returns the modular multiplicative inverse of this number, the number
+    /// x such that `self * x = 1 (mod modulus)`. Returns None if the number
+    /// and the modulus are not coprime, or if the modulus is zero.
+    pub fn modinv(&self, modulus: &Self) -> Option<Self> {
+        if modulus.is_zero() {
+            return None;
+        }
+        // Run the extended Euclidean algorithm, and keep track of the
+        // coefficient of 'self' in the identity: x * self + y * mod = gcd.
+        let mut r0 = BigSInt::from(modulus.clone());
+        let mut r1 = BigSInt::from(self.clone().inplace_div(modulus));
+        let mut t0 = BigSInt::zero();
+        let mut t1 = BigSInt::one();
+        while !r1.is_zero() {
+            let mut q = r0.clone();
+            let r2 = q.inplace_div(&r1);
+            let t2 = &t0 - &(&q * &t1);
+            (r0, r1) = (r1, r2);
+            (t0, t1) = (t1, t2);
+        }
+
+        if r0 != BigSInt::one() {
+            return None;
+        }
+        if t0.is_negative() {
+            t0 += &BigSInt::from(modulus.clone());
+        }
+        Some(t0.get_magnitude())
+    }
+
+    /// Returns the integer square root of this number, and the remainder, such
+    /// that `self = root^2 + rem`.
+    pub fn isqrt(&self) -> (Self, Self) {
+        self.nth_root(2)
+    }
+
+    /// Returns the integer n-th root of this number, and the remainder, such
+    /// that `self = root^n + rem`. The root `n` must not be zero.
+    pub fn nth_root(&self, n: u64) -> (Self, Self) {
+        assert_ne!(n, 0, "the zeroth root is undefined");
+        if self.is_zero() || n == 1 {
+            return (self.clone(), Self::zero());
+        }
+
+        // The number is below 2^n, so the root is one.
+        if n >= self.msb_index() as u64 {
+            return (Self::one(), self.clone() - 1);
+        }
+
+        // Start with a power of two that is above the root, and use Newton's
+        // method, that decreases monotonically to the root:
+        // x' = ((n - 1) * x + self / x^(n - 1)) / n.
+        let bits = (self.msb_index() as u64).div_ceil(n);
+        let mut x = Self::one_hot(bits as usize);
+        let n_big = Self::from_u64(n);
+        let n_1 = Self::from_u64(n - 1);
+        loop {
+            let mut y = self.clone();
+            y.inplace_div(&x.powi(n - 1));
+            y.inplace_add(&(&x * &n_1));
+            y.inplace_div(&n_big);
+            if y >= x {
+                break;
+            }
+            x = y;
+        }
+
+        let mut rem = self.clone();
+        let borrow = rem.inplace_sub(&x.powi(n));
+        debug_assert!(!borrow);
+        (x, rem)
+    }
+}
+
+#[test]
+fn test_gcd_lcm() {
+    use crate::utils::Lfsr;
+
+    fn gcd(a: u128, b: u128) -> u128 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    let mut lfsr = Lfsr::new();
+    for i in 0..2000 {
+        // Use numbers with many common factors.
+        let c = (lfsr.get64() >> (i % 64)) as u128 | 1;
+        let a = (lfsr.get64() >> (i % 64)) as u128 * c;
+        let b = ((lfsr.get64() >> (i % 60)) as u128 * c) << (i % 7);
+        let x = BigInt::from_u128(a);
+        let y = BigInt::from_u128(b);
+        assert_eq!(x.gcd(&y).as_u128(), gcd(a, b));
+        if a < (1 << 64) && b < (1 << 64) {
+            let lcm = if a == 0 || b == 0 {
+                0
+            } else {
+                a / gcd(a, b) * b
+            };
+            assert_eq!(x.lcm(&y).as_u128(), lcm);
+        }
+    }
+    let zero = BigInt::zero();
+    let seven = BigInt::from_u64(7);
+    assert_eq!(zero.gcd(&seven).as_u64(), 7);
+    assert_eq!(seven.gcd(&zero).as_u64(), 7);
+    assert_eq!(zero.gcd(&zero).as_u64(), 0);
+}
+
+#[test]
+fn test_modpow_modinv() {
+    use crate::utils::Lfsr;
+
+    fn modpow(a: u64, mut e: u64, m: u64) -> u64 {
+        let m = m as u128;
+        let mut base = a as u128 % m;
+        let mut res = 1 % m;
+        while e > 0 {
+            if e & 1 == 1 {
+                res = res * base % m;
+            }
+            base = base * base % m;
+            e >>= 1;
+        }
+        res as u64
+    }
+
+    let mut lfsr = Lfsr::new();
+    for _ in 0..500 {
+        let a = lfsr.get64();
+        let e = lfsr.get64() >> (lfsr.get64() % 64);
+        let m = (lfsr.get64() >> (lfsr.get64() % 64)).max(1);
+        let (ba, be, bm) = (
+            BigInt::from_u64(a),
+            BigInt::from_u64(e),
+            BigInt::from_u64(m),
+        );
+        assert_eq!(ba.modpow(&be, &bm).as_u64(), modpow(a, e, m));
+
+        match ba.modinv(&bm) {
+            Some(inv) => {
+                assert_eq!((ba.clone() * inv).inplace_div(&bm).as_u64(), 1 % m)
+            }
+            None => assert_ne!(ba.gcd(&bm).as_u64(), 1),
+        }
+    }
+
+    // Fermat's little theorem, with the Mersenne prime 2^521 - 1.
+    let p = BigInt::all1s(521);
+    let p_1 = BigInt::all1s(521) - 1;
+    let a = BigInt::pseudorandom(8, 1);
+    assert_eq!(a.modpow(&p_1, &p), BigInt::one());
+    let inv = a.modinv(&p).unwrap();
+    assert_eq!((a * inv).inplace_div(&p), BigInt::one());
+    assert!(BigInt::from_u64(6).modinv(&BigInt::from_u64(9)).is_none());
+    assert!(BigInt::from_u64(3).modinv(&BigInt::zero()).is_none());
+    assert!(BigInt::zero().modinv(&BigInt::zero()).is_none());
+}
+
+#[test]
+fn test_isqrt_nth_root() {
+    use crate::utils::Lfsr;
+
+    let mut lfsr = Lfsr::new();
+    for i in 0..300 {
+        let mut x = BigInt::pseudorandom(1 + i % 5, i as u32);
+        x.shift_right((lfsr.get64() % 64) as usize);
+        for n in [2, 3, 5, 7] {
+            let (root, rem) = x.nth_root(n);
+            assert_eq!(&root.powi(n) + &rem, x);
+            assert!((root + 1).powi(n) > x);
+        }
+    }
+
+    let (root, rem) = BigInt::from_u64(1 << 60).isqrt();
+    assert_eq!((root.as_u64(), rem.as_u64()), (1 << 30, 0));
+    let (root, rem) = BigInt::from_u64(99).isqrt();
+    assert_eq!((root.as_u64(), rem.as_u64()), (9, 18));
+    let (root, rem) = BigInt::from_u64(1).nth_root(3);
+    assert_eq!((root.as_u64(), rem.as_u64()), (1, 0));
+    let (root, rem) = BigInt::from_u64(5).nth_root(1 << 40);
+    assert_eq!((root.as_u64(), rem.as_u64()), (1, 4));
+    let (root, rem) = BigInt::all1s(100).nth_root(100);
+    assert_eq!(root.as_u64(), 1);
+    assert_eq!(rem, BigInt::all1s(100) - 1);
+    let (root, rem) = BigInt::one_hot(100).nth_root(100);
+    assert_eq!((root.as_u64(), rem.as_u64()), (2, 0));
+}
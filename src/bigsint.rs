@@ -0,0 +1,375 @@
+//! This module contains the implementation of the signed big-int data
+//! structure, that is built on top of the unsigned BigInt.
+
+extern crate alloc;
+
+use core::cmp::Ordering;
+use core::fmt::Display;
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub,
+    SubAssign,
+};
+
+use alloc::string::String;
+
+use super::bigint::BigInt;
+use super::float::Float;
+use super::Semantics;
+
+/// This is an arbitrary-size signed big number implementation. The number is
+/// stored as a sign and a magnitude, like the floating point numbers. Zero is
+/// never negative.
+///
+/// # Examples
+///
+/// ```
+///    use arpfloat::BigSInt;
+///
+///    let x = BigSInt::from_i64(-1995);
+///    let y = BigSInt::from_i64(90210);
+///
+///    let z = (&x - &y) * &x;
+///    assert_eq!(z.as_decimal(), "183948975");
+///    assert_eq!((-z).as_i64(), -183948975);
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct BigSInt {
+    sign: bool,
+    magnitude: BigInt,
+}
+
+impl BigSInt {
+    /// Create a new number from the sign and the magnitude. The sign is true
+    /// for negative numbers.
+    pub fn new(sign: bool, magnitude: BigInt) -> Self {
+        let sign = sign && !magnitude.is_zero();
+        BigSInt { sign, magnitude }
+    }
+
+    /// Create a new zero big int number.
+    pub fn zero() -> Self {
+        Self::new(false, BigInt::zero())
+    }
+
+    /// Create a new number with the value 1.
+    pub fn one() -> Self {
+        Self::new(false, BigInt::one())
+    }
+
+    /// Create a number from the integer `val`.
+    pub fn from_i64(val: i64) -> Self {
+        Self::new(val < 0, BigInt::from_u64(val.unsigned_abs()))
+    }
+
+    /// Returns the lowest 64 bits of the number, as a two's-complement
+    /// integer.
+    pub fn as_i64(&self) -> i64 {
+        let low = self.magnitude.get_part(0) as i64;
+        if self.sign {
+            low.wrapping_neg()
+        } else {
+            low
+        }
+    }
+
+    /// Returns true if the number is negative.
+    pub fn get_sign(&self) -> bool {
+        self.sign
+    }
+
+    /// Returns true if the number is negative.
+    pub fn is_negative(&self) -> bool {
+        self.sign
+    }
+
+    /// Return true if the number is equal to zero.
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    /// Returns the absolute value of the number.
+    pub fn get_magnitude(&self) -> BigInt {
+        self.magnitude.clone()
+    }
+
+    /// Returns the absolute value of the number.
+    pub fn abs(&self) -> Self {
+        Self::new(false, self.magnitude.clone())
+    }
+
+    /// Add `rhs` to this number.
+    pub fn inplace_add(&mut self, rhs: &Self) {
+        if self.sign == rhs.sign {
+            self.magnitude.inplace_add(&rhs.magnitude);
+            return;
+        }
+
+        // The signs are different, so subtract the smaller magnitude from the
+        // larger one, and take the sign of the larger one.
+        if self.magnitude >= rhs.magnitude {
+            let borrow = self.magnitude.inplace_sub(&rhs.magnitude);
+            debug_assert!(!borrow);
+        } else {
+            let mut magnitude = rhs.magnitude.clone();
+            let borrow = magnitude.inplace_sub(&self.magnitude);
+            debug_assert!(!borrow);
+            self.magnitude = magnitude;
+            self.sign = rhs.sign;
+        }
+        self.sign = self.sign && !self.magnitude.is_zero();
+    }
+
+    /// Subtract `rhs` from this number.
+    pub fn inplace_sub(&mut self, rhs: &Self) {
+        self.inplace_add(&-rhs);
+    }
+
+    /// Multiply this number by `rhs`.
+    pub fn inplace_mul(&mut self, rhs: &Self) {
+        self.magnitude.inplace_mul(&rhs.magnitude);
+        self.sign = (self.sign ^ rhs.sign) && !self.magnitude.is_zero();
+    }
+
+    /// Divide this number by `divisor`, rounding toward zero, and return the
+    /// reminder. The reminder has the sign of the dividend, like the reminder
+    /// of the primitive integer types.
+    pub fn inplace_div(&mut self, divisor: &Self) -> Self {
+        let rem = self.magnitude.inplace_div(&divisor.magnitude);
+        let rem = Self::new(self.sign, rem);
+        self.sign = (self.sign ^ divisor.sign) && !self.magnitude.is_zero();
+        rem
+    }
+
+    /// Replace this number with the reminder of the division by `divisor`.
+    pub fn inplace_rem(&mut self, divisor: &Self) {
+        *self = self.inplace_div(divisor);
+    }
+
+    /// Raise this number to the power of `exp` and return the value.
+    pub fn powi(&self, exp: u64) -> Self {
+        let sign = self.sign && exp % 2 == 1;
+        Self::new(sign, self.magnitude.powi(exp))
+    }
+
+    /// Prints the number as a decimal number.
+    pub fn as_decimal(&self) -> String {
+        let mut buff = String::new();
+        if self.sign {
+            buff.push('-');
+        }
+        buff.push_str(&self.magnitude.as_decimal());
+        buff
+    }
+}
+
+impl From<i64> for BigSInt {
+    fn from(val: i64) -> Self {
+        Self::from_i64(val)
+    }
+}
+
+impl From<BigInt> for BigSInt {
+    fn from(val: BigInt) -> Self {
+        Self::new(false, val)
+    }
+}
+
+impl Display for BigSInt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.as_decimal())
+    }
+}
+
+impl Eq for BigSInt {}
+
+impl PartialEq for BigSInt {
+    fn eq(&self, other: &BigSInt) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+impl PartialOrd for BigSInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for BigSInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.sign, other.sign) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+        }
+    }
+}
+
+impl Neg for BigSInt {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(!self.sign, self.magnitude)
+    }
+}
+
+impl Neg for &BigSInt {
+    type Output = BigSInt;
+    fn neg(self) -> Self::Output {
+        BigSInt::new(!self.sign, self.magnitude.clone())
+    }
+}
+
+macro_rules! declare_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $func_impl_name:ident) => {
+        // Self + Self
+        impl $trait_name for BigSInt {
+            type Output = Self;
+
+            fn $func_name(self, rhs: Self) -> Self::Output {
+                self.$func_name(&rhs)
+            }
+        }
+
+        // Self + &Self -> Self
+        impl $trait_name<&Self> for BigSInt {
+            type Output = Self;
+            fn $func_name(self, rhs: &Self) -> Self::Output {
+                let mut n = self;
+                let _ = n.$func_impl_name(rhs);
+                n
+            }
+        }
+
+        // &Self + &Self -> Self
+        impl $trait_name<Self> for &BigSInt {
+            type Output = BigSInt;
+            fn $func_name(self, rhs: Self) -> Self::Output {
+                let mut n = self.clone();
+                let _ = n.$func_impl_name(rhs);
+                n
+            }
+        }
+
+        // Self + i64 -> Self
+        impl $trait_name<i64> for BigSInt {
+            type Output = Self;
+            fn $func_name(self, rhs: i64) -> Self::Output {
+                let mut n = self;
+                let _ = n.$func_impl_name(&Self::from_i64(rhs));
+                n
+            }
+        }
+    };
+}
+
+declare_operator!(Add, add, inplace_add);
+declare_operator!(Sub, sub, inplace_sub);
+declare_operator!(Mul, mul, inplace_mul);
+declare_operator!(Div, div, inplace_div);
+declare_operator!(Rem, rem, inplace_rem);
+
+macro_rules! declare_assign_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $func_impl_name:ident) => {
+        impl $trait_name for BigSInt {
+            fn $func_name(&mut self, rhs: Self) {
+                let _ = self.$func_impl_name(&rhs);
+            }
+        }
+
+        impl $trait_name<&BigSInt> for BigSInt {
+            fn $func_name(&mut self, rhs: &Self) {
+                let _ = self.$func_impl_name(&rhs);
+            }
+        }
+    };
+}
+
+declare_assign_operator!(AddAssign, add_assign, inplace_add);
+declare_assign_operator!(SubAssign, sub_assign, inplace_sub);
+declare_assign_operator!(MulAssign, mul_assign, inplace_mul);
+declare_assign_operator!(DivAssign, div_assign, inplace_div);
+declare_assign_operator!(RemAssign, rem_assign, inplace_rem);
+
+impl Float {
+    /// Load the signed big int `val` into the float. Notice that the number
+    /// may overflow, or be rounded with the rounding mode of `sem`.
+    pub fn from_bigsint(sem: Semantics, val: &BigSInt) -> Self {
+        let mut a = Self::from_bigint(sem, val.get_magnitude());
+        a.set_sign(val.get_sign());
+        a
+    }
+
+    /// Converts the number to a signed big int. The integral part is rounded
+    /// with the rounding mode of the semantics, so integers are converted
+    /// exactly. NaN and Inf are converted to zero.
+    pub fn to_bigsint(&self) -> BigSInt {
+        if !self.is_normal() {
+            return BigSInt::zero();
+        }
+        let rm = self.get_semantics().get_rounding_mode();
+        let magnitude = self.convert_normal_to_integer(rm);
+        BigSInt::new(self.get_sign(), magnitude)
+    }
+}
+
+#[test]
+fn test_bigsint_operators() {
+    use crate::utils::Lfsr;
+
+    let mut lfsr = Lfsr::new();
+    for _ in 0..5000 {
+        // Pick numbers that don't overflow the i64 operations.
+        let a = (lfsr.get64() as i64) >> (lfsr.get64() % 64);
+        let b = (lfsr.get64() as i32 as i64) >> (lfsr.get64() % 32);
+        let x = BigSInt::from_i64(a);
+        let y = BigSInt::from_i64(b);
+
+        assert_eq!((&x + &y).as_i64(), a.wrapping_add(b));
+        assert_eq!((&x - &y).as_i64(), a.wrapping_sub(b));
+        assert_eq!((&x * &y).as_i64(), a.wrapping_mul(b));
+        assert_eq!(x.cmp(&y), a.cmp(&b));
+        if b != 0 {
+            assert_eq!((&x / &y).as_i64(), a / b);
+            assert_eq!((&x % &y).as_i64(), a % b);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_bigsint_to_string() {
+    use std::format;
+    let x = BigSInt::from_i64(-40) * BigSInt::from_i64(1 << 62).powi(3);
+    assert_eq!(
+        x.as_decimal(),
+        "-3923188584616675477397368389504791510063972152790021570560"
+    );
+    assert_eq!(format!("{}", BigSInt::from_i64(-7) + 7), "0");
+    assert_eq!(format!("{}", BigSInt::from_i64(-7) * BigSInt::zero()), "0");
+    assert_eq!(format!("{}", -BigSInt::zero()), "0");
+    assert!(!(-BigSInt::zero()).is_negative());
+}
+
+#[test]
+fn test_bigsint_float_conversion() {
+    use crate::{RoundingMode, FP128, FP64};
+    let x = BigSInt::from_i64(-3).powi(61);
+    let fp = Float::from_bigsint(FP128, &x);
+    assert_eq!(fp.to_bigsint(), x);
+    assert!(fp.is_negative());
+
+    // Large numbers are rounded.
+    let fp = Float::from_bigsint(FP64, &x);
+    assert_ne!(fp.to_bigsint(), x);
+    assert_eq!(fp.as_f64(), -1.271734748256486e29);
+
+    // Fractions are rounded with the rounding mode of the semantics.
+    let fp = Float::from_f64(-2.5);
+    assert_eq!(fp.to_bigsint().as_i64(), -2);
+    let fp = fp.cast(FP64.with_rm(RoundingMode::Negative));
+    assert_eq!(fp.to_bigsint().as_i64(), -3);
+    assert!(Float::nan(FP64, true).to_bigsint().is_zero());
+}
@@ -5,7 +5,7 @@ use crate::FP128;
 
 use super::bigint::BigInt;
 use super::bigint::LossFraction;
-use super::float::{self, Category};
+use super::float::{self, Category, Status};
 use super::float::{Float, RoundingMode, FP32, FP64};
 use super::utils;
 use super::utils::mask;
@@ -25,6 +25,39 @@ impl Float {
         a
     }
 
+    /// Returns the fraction `num / den`, rounded once with the rounding mode
+    /// `rm`. The denominator must not be zero.
+    pub(crate) fn from_fraction(
+        sem: Semantics,
+        sign: bool,
+        num: &BigInt,
+        den: &BigInt,
+        rm: RoundingMode,
+    ) -> Self {
+        debug_assert!(!den.is_zero());
+        if num.is_zero() {
+            return Self::zero(sem, sign);
+        }
+
+        // Shift the numerator to make sure that the integer quotient has at
+        // least two bits more than the precision. The remainder of the
+        // division only affects the rounding, so fold it into a sticky bit.
+        let precision = sem.get_precision();
+        let shift =
+            (den.msb_index() + precision + 2).saturating_sub(num.msb_index());
+        let mut quotient = num.clone();
+        quotient.shift_left(shift);
+        let reminder = quotient.inplace_div(den);
+        if !reminder.is_zero() && quotient.is_even() {
+            quotient.inplace_add(&BigInt::one());
+        }
+
+        let exp = sem.get_mantissa_len() as i64 - shift as i64;
+        let mut res = Self::new(sem, sign, exp, quotient);
+        res.normalize(rm, LossFraction::ExactlyZero);
+        res
+    }
+
     /// Load the integer `val` into the float. Notice that the number may
     /// overflow or rounded.
     pub fn from_i64(sem: Semantics, val: i64) -> Self {
@@ -50,13 +83,7 @@ impl Float {
                 return i64::MAX;
             }
         }
-        let rm = self.get_semantics().get_rounding_mode();
-        let val = self.convert_normal_to_integer(rm);
-        if self.get_sign() {
-            -(val.as_u64() as i64)
-        } else {
-            val.as_u64() as i64
-        }
+        self.to_bigsint().as_i64()
     }
 
     /// Returns a value that is rounded to the nearest integer that's not larger
@@ -140,7 +167,15 @@ impl Float {
                 &self.get_mantissa(),
                 -i_exp as usize,
             );
-            if self.need_round_away_from_zero(rm, loss) {
+            // Ties are broken by the parity of the integer, and not by the
+            // parity of the mantissa.
+            let round = match rm {
+                RoundingMode::NearestTiesToEven => {
+                    loss.is_mt_half() || (loss.is_exactly_half() && m.is_odd())
+                }
+                _ => self.need_round_away_from_zero(rm, loss),
+            };
+            if !loss.is_exactly_zero() && round {
                 m.inplace_add(&BigInt::one());
             }
             m
@@ -188,6 +223,16 @@ impl Float {
 
     /// Cast to another float using the non-default rounding mode `rm`.
     pub fn cast_with_rm(&self, to: Semantics, rm: RoundingMode) -> Float {
+        self.cast_with_status(to, rm).0
+    }
+
+    /// Cast to another float using the rounding mode `rm`, and returns the
+    /// exceptions that the conversion raised.
+    pub fn cast_with_status(
+        &self,
+        to: Semantics,
+        rm: RoundingMode,
+    ) -> (Float, Status) {
         let mut loss = LossFraction::ExactlyZero;
         let exp_delta =
             self.get_mantissa_len() as i64 - to.get_mantissa_len() as i64;
@@ -210,9 +255,10 @@ impl Float {
         if to.get_exponent_len() != self.get_exponent_len()
             || to.get_mantissa_len() != self.get_mantissa_len()
         {
-            x.normalize(rm, loss);
+            let status = x.normalize(rm, loss);
+            return (x, status);
         }
-        x
+        (x, Status::OK)
     }
     /// Convert from one float format to another.
     pub fn cast(&self, to: Semantics) -> Float {
@@ -323,6 +369,18 @@ fn test_rounding_to_integer() {
     assert_eq!(100, Float::from_f64(99.999).cast(p64).to_i64());
     assert_eq!(0, Float::from_f64(-0.99).cast(p64).to_i64());
     assert_eq!(0, Float::from_f64(-0.5).cast(p64).to_i64());
+    assert_eq!(5, Float::from_f64(5.).cast(p64).to_i64());
+    assert_eq!(
+        -5,
+        Float::from_f64(-5.)
+            .cast(FP64.with_rm(RoundingMode::Negative))
+            .to_i64()
+    );
+
+    // Round to even.
+    assert_eq!(2, Float::from_f64(2.5).to_i64());
+    assert_eq!(4, Float::from_f64(3.5).to_i64());
+    assert_eq!(-4, Float::from_f64(-3.5).to_i64());
 
     // Special values
     let n_inf = f64::NEG_INFINITY;
@@ -452,6 +510,51 @@ fn test_cast_zero_nan_inf() {
     }
 }
 
+#[test]
+fn test_cast_status() {
+    use crate::FP16;
+    let rm = RoundingMode::NearestTiesToEven;
+    let status = |v: f64| Float::from_f64(v).cast_with_status(FP16, rm).1;
+    assert!(status(1.5).is_ok());
+    assert!(status(65504.).is_ok());
+    assert!(status(f64::INFINITY).is_ok());
+    assert!(status(f64::NAN).is_ok());
+    assert_eq!(status(0.1), Status::INEXACT);
+    assert_eq!(status(1e6), Status::OVERFLOW | Status::INEXACT);
+    assert_eq!(status(1e-6), Status::UNDERFLOW | Status::INEXACT);
+    assert_eq!(status(1e-10), Status::UNDERFLOW | Status::INEXACT);
+    // The smallest denormal is exact.
+    assert!(status(2.0f64.powi(-24)).is_ok());
+
+    // Widening a number is always exact.
+    let (x, s) = Float::from_f32(0.1).cast_with_status(FP64, rm);
+    assert!(s.is_ok());
+    assert_eq!(x.as_f64(), 0.1f32 as f64);
+}
+
+#[test]
+fn test_from_fraction() {
+    let rm = RoundingMode::NearestTiesToEven;
+    for (num, den) in [(1, 3), (2, 3), (10, 1), (1, 10), (22, 7), (7, 1024)] {
+        let a = BigInt::from_u64(num);
+        let b = BigInt::from_u64(den);
+        let res = Float::from_fraction(FP64, false, &a, &b, rm);
+        assert_eq!(res.as_f64(), num as f64 / den as f64);
+    }
+
+    // Check the rounding of denormals and of numbers that overflow.
+    let one = BigInt::one();
+    let den = BigInt::from_u64(3).powi(700);
+    let res = Float::from_fraction(FP64, true, &one, &den, rm);
+    assert_eq!(res.as_f64(), -0.0);
+    let den = BigInt::from_u64(3).powi(670);
+    let res = Float::from_fraction(FP64, false, &one, &den, rm);
+    assert_eq!(res.as_f64(), 1.0 / 3.0f64.powi(335) / 3.0f64.powi(335));
+    let num = BigInt::from_u64(3).powi(700);
+    let res = Float::from_fraction(FP64, false, &num, &one, rm);
+    assert!(res.is_inf());
+}
+
 #[test]
 fn test_cast_down_easy() {
     // Check that we can cast the numbers down, matching the hardware casting.
@@ -0,0 +1,875 @@
+//! This module contains the implementation of complex numbers, that are built
+//! on top of the Float type. The special values (infinities, NaNs and signed
+//! zeros) and the branch cuts of the functions follow C99 Annex G.
+
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+use super::float::Float;
+use super::RoundingMode;
+use super::Semantics;
+
+/// This is an arbitrary-precision complex number. The real and the imaginary
+/// parts are floats with the same semantics.
+///
+/// # Examples
+///
+/// ```
+///    use arpfloat::{Complex, Float, FP128};
+///
+///    let z = Complex::new(Float::from_u64(FP128, 3), Float::from_u64(FP128, 4));
+///    assert_eq!(z.abs().as_f64(), 5.);
+///
+///    // Prints: 1.60943... + 0.92729...i
+///    let w = z.log();
+///    println!("{} + {}i", w.get_re(), w.get_im());
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Complex {
+    re: Float,
+    im: Float,
+}
+
+impl Complex {
+    /// Create a new number from the real part `re` and the imaginary part
+    /// `im`. Both parts must have the same semantics.
+    pub fn new(re: Float, im: Float) -> Self {
+        assert_eq!(
+            re.get_semantics(),
+            im.get_semantics(),
+            "the parts must have the same semantics"
+        );
+        Complex { re, im }
+    }
+
+    /// Create a new number with the real value `re`, and a zero imaginary
+    /// part.
+    pub fn from_float(re: Float) -> Self {
+        let im = Float::zero(re.get_semantics(), false);
+        Complex { re, im }
+    }
+
+    /// Create a new zero number.
+    pub fn zero(sem: Semantics) -> Self {
+        Self::from_float(Float::zero(sem, false))
+    }
+
+    /// Create a new number with the value 1.
+    pub fn one(sem: Semantics) -> Self {
+        Self::from_float(Float::one(sem, false))
+    }
+
+    /// Create a new number with the value i.
+    pub fn i(sem: Semantics) -> Self {
+        Self::new(Float::zero(sem, false), Float::one(sem, false))
+    }
+
+    /// Create a new number from the magnitude `r` and the angle `theta` (in
+    /// radians).
+    pub fn from_polar(r: &Float, theta: &Float) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = r.get_semantics();
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let r = r.cast_with_rm(sem, rm);
+        let theta = theta.cast_with_rm(sem, rm);
+        let re = Float::mul_with_rm(&r, &theta.cos(), rm);
+        let im = Float::mul_with_rm(&r, &theta.sin(), rm);
+        Self::new(re.cast(orig_sem), im.cast(orig_sem))
+    }
+
+    /// Returns the magnitude and the angle (in radians) of the number.
+    pub fn to_polar(&self) -> (Float, Float) {
+        (self.abs(), self.arg())
+    }
+
+    /// Returns the real part of the number.
+    pub fn get_re(&self) -> Float {
+        self.re.clone()
+    }
+
+    /// Returns the imaginary part of the number.
+    pub fn get_im(&self) -> Float {
+        self.im.clone()
+    }
+
+    /// Returns the semantics of the parts of the number.
+    pub fn get_semantics(&self) -> Semantics {
+        self.re.get_semantics()
+    }
+
+    /// Returns true if one of the parts is infinite. Like in C99, a number
+    /// with an infinite part is infinite, even if the other part is a NaN.
+    pub fn is_inf(&self) -> bool {
+        self.re.is_inf() || self.im.is_inf()
+    }
+
+    /// Returns true if the number is not infinite, and one of the parts is a
+    /// NaN.
+    pub fn is_nan(&self) -> bool {
+        !self.is_inf() && (self.re.is_nan() || self.im.is_nan())
+    }
+
+    /// Returns true if both parts are zero.
+    pub fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    /// Returns the complex conjugate of the number.
+    pub fn conj(&self) -> Self {
+        Self::new(self.re.clone(), self.im.neg())
+    }
+
+    /// Returns the magnitude of the number, sqrt(re^2 + im^2), without
+    /// overflow or underflow in the intermediate values.
+    pub fn abs(&self) -> Float {
+        hypot(&self.re, &self.im)
+    }
+
+    /// Returns the angle of the number (in radians), in the range [-pi, pi].
+    pub fn arg(&self) -> Float {
+        self.im.atan2(&self.re)
+    }
+}
+
+/// Returns true if `x` is not infinite and not a NaN.
+fn is_finite(x: &Float) -> bool {
+    !x.is_inf() && !x.is_nan()
+}
+
+/// Returns `x` with the sign of `sign`.
+fn copysign(x: Float, sign: &Float) -> Float {
+    let mut x = x;
+    x.set_sign(sign.get_sign());
+    x
+}
+
+/// Returns a semantics that can hold the products of numbers in `sem`
+/// exactly, with `more` additional bits of precision.
+fn exact_products_sem(sem: Semantics, more: usize) -> Semantics {
+    sem.increase_precision(sem.get_precision() + more)
+        .increase_exponent(2)
+}
+
+/// Computes sqrt(x^2 + y^2). The squares are exact in the wider semantics, so
+/// the result is rounded only by the sum and the square root.
+fn hypot(x: &Float, y: &Float) -> Float {
+    use RoundingMode::None as rm;
+    let orig_sem = x.get_semantics();
+    if x.is_inf() || y.is_inf() {
+        return Float::inf(orig_sem, false);
+    } else if x.is_nan() || y.is_nan() {
+        return Float::nan(orig_sem, false);
+    }
+
+    let sem = exact_products_sem(orig_sem, 8);
+    let x = x.cast_with_rm(sem, rm);
+    let y = y.cast_with_rm(sem, rm);
+    let sum = Float::add_with_rm(&x.sqr(), &y.sqr(), rm);
+    sum.sqrt().cast(orig_sem)
+}
+
+impl Complex {
+    /// Computes the exponential function e^z.
+    pub fn exp(&self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = self.get_semantics();
+        let (x, y) = (&self.re, &self.im);
+        let nan = Float::nan(orig_sem, false);
+
+        if x.is_inf() {
+            let neg = x.is_negative();
+            if !is_finite(y) {
+                // exp(-inf + i inf) = +0 + i0, exp(+inf + i inf) = inf + iNaN.
+                return if neg {
+                    Self::zero(orig_sem)
+                } else {
+                    Self::new(x.clone(), nan)
+                };
+            }
+            // exp(-inf + iy) = +0 cis(y), exp(+inf + iy) = +inf cis(y).
+            let scale = |sign: bool| {
+                if neg {
+                    Float::zero(orig_sem, sign)
+                } else {
+                    Float::inf(orig_sem, sign)
+                }
+            };
+            if y.is_zero() {
+                return Self::new(scale(false), y.clone());
+            }
+            let re = scale(y.cos().is_negative());
+            let im = scale(y.sin().is_negative());
+            return Self::new(re, im);
+        }
+
+        if x.is_nan() {
+            // exp(NaN + i0) = NaN + i0.
+            let im = if y.is_zero() { y.clone() } else { nan.clone() };
+            return Self::new(nan, im);
+        }
+        if !is_finite(y) {
+            return Self::new(nan.clone(), nan);
+        }
+        if y.is_zero() {
+            return Self::new(x.exp(), y.clone());
+        }
+
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let ex = x.cast_with_rm(sem, rm).exp();
+        let y = y.cast_with_rm(sem, rm);
+        let re = Float::mul_with_rm(&ex, &y.cos(), rm);
+        let im = Float::mul_with_rm(&ex, &y.sin(), rm);
+        Self::new(re.cast(orig_sem), im.cast(orig_sem))
+    }
+
+    /// Computes the natural logarithm of the number. The branch cut is on the
+    /// negative real axis, and the imaginary part of the result is in the
+    /// range [-pi, pi].
+    pub fn log(&self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = self.get_semantics();
+        let (x, y) = (&self.re, &self.im);
+
+        // The imaginary part is the angle. atan2 handles the signed zeros and
+        // the infinities.
+        let im = y.atan2(x);
+        let re = if x.is_inf() || y.is_inf() {
+            Float::inf(orig_sem, false)
+        } else if x.is_nan() || y.is_nan() {
+            Float::nan(orig_sem, false)
+        } else if x.is_zero() && y.is_zero() {
+            Float::inf(orig_sem, true)
+        } else {
+            // Make sure that |x| >= |y|.
+            let (x, y) = if x.abs() >= y.abs() { (x, y) } else { (y, x) };
+            let sem = exact_products_sem(orig_sem, 16);
+            let x = x.cast_with_rm(sem, rm).abs();
+            let y = y.cast_with_rm(sem, rm);
+            let one = Float::one(sem, false);
+            let half = one.scale(-1, rm);
+            let two = one.scale(1, rm);
+            let sum = Float::add_with_rm(&x.sqr(), &y.sqr(), rm);
+
+            let res = if sum > half && sum < two {
+                // Near the unit circle, log(x^2 + y^2) cancels. Compute
+                // log1p((x - 1)(x + 1) + y^2), where all of the terms are
+                // exact, and the sum is rounded once.
+                let xm1 = Float::sub_with_rm(&x, &one, rm);
+                let xp1 = Float::add_with_rm(&x, &one, rm);
+                let prod = Float::mul_with_rm(&xm1, &xp1, rm);
+                Float::add_with_rm(&prod, &y.sqr(), rm).log1p()
+            } else {
+                sum.log()
+            };
+            res.scale(-1, rm).cast(orig_sem)
+        };
+        Self::new(re, im)
+    }
+
+    /// Computes the principal square root of the number. The branch cut is on
+    /// the negative real axis, and the real part of the result is not
+    /// negative.
+    pub fn sqrt(&self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = self.get_semantics();
+        let (x, y) = (&self.re, &self.im);
+        let nan = Float::nan(orig_sem, false);
+        let inf = Float::inf(orig_sem, false);
+
+        if y.is_inf() {
+            // sqrt(x + i inf) = inf + i inf, even if x is a NaN.
+            return Self::new(inf, y.clone());
+        }
+        if x.is_inf() {
+            let neg = x.is_negative();
+            return match (y.is_nan(), neg) {
+                // sqrt(-inf + iNaN) = NaN + i inf.
+                (true, true) => Self::new(nan, inf),
+                // sqrt(+inf + iNaN) = inf + iNaN.
+                (true, false) => Self::new(inf, nan),
+                // sqrt(-inf + iy) = +0 + i inf.
+                (false, true) => {
+                    Self::new(Float::zero(orig_sem, false), copysign(inf, y))
+                }
+                // sqrt(+inf + iy) = inf + i0.
+                (false, false) => {
+                    Self::new(inf, Float::zero(orig_sem, y.get_sign()))
+                }
+            };
+        }
+        if x.is_nan() || y.is_nan() {
+            return Self::new(nan.clone(), nan);
+        }
+        if x.is_zero() && y.is_zero() {
+            return Self::new(Float::zero(orig_sem, false), y.clone());
+        }
+
+        // Compute t = sqrt((|x| + |z|) / 2), which does not cancel, and
+        // derive the other part from y = 2 * re * im.
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let z = Self::new(x.cast_with_rm(sem, rm), y.cast_with_rm(sem, rm));
+        let sum = Float::add_with_rm(&z.re.abs(), &z.abs(), rm);
+        let t = sum.scale(-1, rm).sqrt();
+        let other = Float::div_with_rm(&z.im, &t.scale(1, rm), rm);
+        let (re, im) = if x.is_negative() {
+            (other.abs(), copysign(t, y))
+        } else {
+            (t, other)
+        };
+        Self::new(re.cast(orig_sem), im.cast(orig_sem))
+    }
+
+    /// Computes the number to the power of `w`, as exp(w * log(z)). The
+    /// branch cut is on the negative real axis.
+    pub fn pow(&self, w: &Self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = self.get_semantics();
+
+        // z^0 = 1, and 0^w = 0 if the real part of w is positive.
+        if w.is_zero() {
+            return Self::one(orig_sem);
+        }
+        if self.is_zero() && w.re > Float::zero(orig_sem, false) {
+            return Self::zero(orig_sem);
+        }
+
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let cast = |z: &Self| {
+            Self::new(z.re.cast_with_rm(sem, rm), z.im.cast_with_rm(sem, rm))
+        };
+        let res = (&cast(w) * &cast(self).log()).exp();
+        Self::new(res.re.cast(orig_sem), res.im.cast(orig_sem))
+    }
+
+    /// Computes the hyperbolic sine of the number.
+    pub fn sinh(&self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = self.get_semantics();
+        let (x, y) = (&self.re, &self.im);
+        let nan = Float::nan(orig_sem, false);
+
+        // See C99 Annex G.6.2.5.
+        if (x.is_zero() || x.is_inf()) && !is_finite(y) {
+            return Self::new(x.clone(), nan);
+        }
+        if y.is_zero() && !is_finite(x) {
+            return Self::new(x.clone(), y.clone());
+        }
+        if x.is_nan() || !is_finite(y) {
+            return Self::new(nan.clone(), nan);
+        }
+
+        // sinh(x + iy) = sinh(x)cos(y) + i cosh(x)sin(y).
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let x = x.cast_with_rm(sem, rm);
+        let y = y.cast_with_rm(sem, rm);
+        let re = Float::mul_with_rm(&x.sinh(), &y.cos(), rm);
+        let im = Float::mul_with_rm(&x.cosh(), &y.sin(), rm);
+        Self::new(re.cast(orig_sem), im.cast(orig_sem))
+    }
+
+    /// Computes the hyperbolic cosine of the number.
+    pub fn cosh(&self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = self.get_semantics();
+        let (x, y) = (&self.re, &self.im);
+        let nan = Float::nan(orig_sem, false);
+        let zero = Float::zero(orig_sem, false);
+
+        // See C99 Annex G.6.2.4.
+        if x.is_zero() && !is_finite(y) {
+            return Self::new(nan, zero);
+        }
+        if y.is_zero() && x.is_inf() {
+            let im = Float::zero(orig_sem, y.get_sign() ^ x.get_sign());
+            return Self::new(x.abs(), im);
+        }
+        if y.is_zero() && x.is_nan() {
+            return Self::new(nan, zero);
+        }
+        if x.is_inf() && !is_finite(y) {
+            return Self::new(x.abs(), nan);
+        }
+        if x.is_nan() || !is_finite(y) {
+            return Self::new(nan.clone(), nan);
+        }
+
+        // cosh(x + iy) = cosh(x)cos(y) + i sinh(x)sin(y).
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let x = x.cast_with_rm(sem, rm);
+        let y = y.cast_with_rm(sem, rm);
+        let re = Float::mul_with_rm(&x.cosh(), &y.cos(), rm);
+        let im = Float::mul_with_rm(&x.sinh(), &y.sin(), rm);
+        Self::new(re.cast(orig_sem), im.cast(orig_sem))
+    }
+
+    /// Computes the sine of the number.
+    pub fn sin(&self) -> Self {
+        // sin(z) = -i sinh(iz).
+        let iz = Self::new(self.im.neg(), self.re.clone());
+        let res = iz.sinh();
+        Self::new(res.im, res.re.neg())
+    }
+
+    /// Computes the cosine of the number.
+    pub fn cos(&self) -> Self {
+        // cos(z) = cosh(iz).
+        Self::new(self.im.neg(), self.re.clone()).cosh()
+    }
+}
+
+impl Complex {
+    /// Multiply two numbers, and recover the infinities that the naive
+    /// formula turns into NaNs. See C99 Annex G.5.1.
+    fn mul_impl(lhs: &Self, rhs: &Self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = lhs.get_semantics();
+        let sem = exact_products_sem(orig_sem, 8);
+        let cast = |x: &Float| x.cast_with_rm(sem, rm);
+        let (mut a, mut b) = (cast(&lhs.re), cast(&lhs.im));
+        let (mut c, mut d) = (cast(&rhs.re), cast(&rhs.im));
+
+        let eval = |a: &Float, b: &Float, c: &Float, d: &Float| {
+            let ac = Float::mul_with_rm(a, c, rm);
+            let bd = Float::mul_with_rm(b, d, rm);
+            let ad = Float::mul_with_rm(a, d, rm);
+            let bc = Float::mul_with_rm(b, c, rm);
+            let re = Float::sub_with_rm(&ac, &bd, rm);
+            let im = Float::add_with_rm(&ad, &bc, rm);
+            (re, im)
+        };
+        let (mut re, mut im) = eval(&a, &b, &c, &d);
+
+        if re.is_nan() && im.is_nan() {
+            // Replace the infinite operands with +-1 and the NaNs with +-0,
+            // and scale the result to infinity.
+            let box_inf = |x: &Float| {
+                let val = if x.is_inf() {
+                    Float::one(sem, false)
+                } else {
+                    Float::zero(sem, false)
+                };
+                copysign(val, x)
+            };
+            let nan_to_zero = |x: &Float| {
+                if x.is_nan() {
+                    copysign(Float::zero(sem, false), x)
+                } else {
+                    x.clone()
+                }
+            };
+            let mut recalc = false;
+            if a.is_inf() || b.is_inf() {
+                (a, b) = (box_inf(&a), box_inf(&b));
+                (c, d) = (nan_to_zero(&c), nan_to_zero(&d));
+                recalc = true;
+            }
+            if c.is_inf() || d.is_inf() {
+                (c, d) = (box_inf(&c), box_inf(&d));
+                (a, b) = (nan_to_zero(&a), nan_to_zero(&b));
+                recalc = true;
+            }
+            if recalc {
+                let inf = Float::inf(sem, false);
+                let (x, y) = eval(&a, &b, &c, &d);
+                re = Float::mul_with_rm(&inf, &x, rm);
+                im = Float::mul_with_rm(&inf, &y, rm);
+            }
+        }
+        Self::new(re.cast(orig_sem), im.cast(orig_sem))
+    }
+
+    /// Divide two numbers, and recover the infinities and the zeros that the
+    /// naive formula turns into NaNs. See C99 Annex G.5.1.
+    fn div_impl(lhs: &Self, rhs: &Self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = lhs.get_semantics();
+        let sem = exact_products_sem(orig_sem, 16);
+        let cast = |x: &Float| x.cast_with_rm(sem, rm);
+        let (a, b) = (cast(&lhs.re), cast(&lhs.im));
+        let (c, d) = (cast(&rhs.re), cast(&rhs.im));
+
+        // (a + ib) / (c + id) = ((ac + bd) + i(bc - ad)) / (c^2 + d^2).
+        let eval = |a: &Float, b: &Float, c: &Float, d: &Float| {
+            let ac = Float::mul_with_rm(a, c, rm);
+            let bd = Float::mul_with_rm(b, d, rm);
+            let bc = Float::mul_with_rm(b, c, rm);
+            let ad = Float::mul_with_rm(a, d, rm);
+            let re = Float::add_with_rm(&ac, &bd, rm);
+            let im = Float::sub_with_rm(&bc, &ad, rm);
+            (re, im)
+        };
+        let denom = Float::add_with_rm(&c.sqr(), &d.sqr(), rm);
+        let (x, y) = eval(&a, &b, &c, &d);
+        let mut re = Float::div_with_rm(&x, &denom, rm);
+        let mut im = Float::div_with_rm(&y, &denom, rm);
+
+        if re.is_nan() && im.is_nan() {
+            let inf = Float::inf(sem, false);
+            let zero = Float::zero(sem, false);
+            let box_inf = |x: &Float| {
+                let val = if x.is_inf() {
+                    Float::one(sem, false)
+                } else {
+                    Float::zero(sem, false)
+                };
+                copysign(val, x)
+            };
+
+            if denom.is_zero() && (!a.is_nan() || !b.is_nan()) {
+                // A finite or infinite number divided by zero is infinite.
+                let scale = copysign(inf, &c);
+                re = Float::mul_with_rm(&scale, &a, rm);
+                im = Float::mul_with_rm(&scale, &b, rm);
+            } else if (a.is_inf() || b.is_inf())
+                && is_finite(&c)
+                && is_finite(&d)
+            {
+                // An infinite number divided by a finite number is infinite.
+                let (x, y) = eval(&box_inf(&a), &box_inf(&b), &c, &d);
+                re = Float::mul_with_rm(&inf, &x, rm);
+                im = Float::mul_with_rm(&inf, &y, rm);
+            } else if (c.is_inf() || d.is_inf())
+                && is_finite(&a)
+                && is_finite(&b)
+            {
+                // A finite number divided by an infinite number is zero.
+                let (x, y) = eval(&a, &b, &box_inf(&c), &box_inf(&d));
+                re = Float::mul_with_rm(&zero, &x, rm);
+                im = Float::mul_with_rm(&zero, &y, rm);
+            }
+        }
+        Self::new(re.cast(orig_sem), im.cast(orig_sem))
+    }
+
+    /// Add `rhs` to this number.
+    pub fn inplace_add(&mut self, rhs: &Self) {
+        self.re += &rhs.re;
+        self.im += &rhs.im;
+    }
+
+    /// Subtract `rhs` from this number.
+    pub fn inplace_sub(&mut self, rhs: &Self) {
+        self.re -= &rhs.re;
+        self.im -= &rhs.im;
+    }
+
+    /// Multiply this number by `rhs`.
+    pub fn inplace_mul(&mut self, rhs: &Self) {
+        *self = Self::mul_impl(self, rhs);
+    }
+
+    /// Divide this number by `rhs`.
+    pub fn inplace_div(&mut self, rhs: &Self) {
+        *self = Self::div_impl(self, rhs);
+    }
+}
+
+impl PartialEq for Complex {
+    fn eq(&self, other: &Self) -> bool {
+        self.re == other.re && self.im == other.im
+    }
+}
+
+impl Neg for Complex {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new(self.re.neg(), self.im.neg())
+    }
+}
+
+impl Neg for &Complex {
+    type Output = Complex;
+    fn neg(self) -> Self::Output {
+        Complex::new(self.re.neg(), self.im.neg())
+    }
+}
+
+macro_rules! declare_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $func_impl_name:ident) => {
+        // Self + Self
+        impl $trait_name for Complex {
+            type Output = Self;
+
+            fn $func_name(self, rhs: Self) -> Self::Output {
+                self.$func_name(&rhs)
+            }
+        }
+
+        // Self + &Self -> Self
+        impl $trait_name<&Self> for Complex {
+            type Output = Self;
+            fn $func_name(self, rhs: &Self) -> Self::Output {
+                let mut n = self;
+                n.$func_impl_name(rhs);
+                n
+            }
+        }
+
+        // &Self + &Self -> Self
+        impl $trait_name<Self> for &Complex {
+            type Output = Complex;
+            fn $func_name(self, rhs: Self) -> Self::Output {
+                let mut n = self.clone();
+                n.$func_impl_name(rhs);
+                n
+            }
+        }
+    };
+}
+
+declare_operator!(Add, add, inplace_add);
+declare_operator!(Sub, sub, inplace_sub);
+declare_operator!(Mul, mul, inplace_mul);
+declare_operator!(Div, div, inplace_div);
+
+macro_rules! declare_assign_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $func_impl_name:ident) => {
+        impl $trait_name for Complex {
+            fn $func_name(&mut self, rhs: Self) {
+                self.$func_impl_name(&rhs);
+            }
+        }
+
+        impl $trait_name<&Complex> for Complex {
+            fn $func_name(&mut self, rhs: &Self) {
+                self.$func_impl_name(rhs);
+            }
+        }
+    };
+}
+
+declare_assign_operator!(AddAssign, add_assign, inplace_add);
+declare_assign_operator!(SubAssign, sub_assign, inplace_sub);
+declare_assign_operator!(MulAssign, mul_assign, inplace_mul);
+declare_assign_operator!(DivAssign, div_assign, inplace_div);
+
+#[cfg(test)]
+fn from_f64s(re: f64, im: f64) -> Complex {
+    Complex::new(Float::from_f64(re), Float::from_f64(im))
+}
+
+#[cfg(test)]
+fn as_f64s(z: &Complex) -> (f64, f64) {
+    (z.get_re().as_f64(), z.get_im().as_f64())
+}
+
+#[test]
+fn test_complex_arithmetic() {
+    use crate::utils::Lfsr;
+    let mut lfsr = Lfsr::new();
+
+    for _ in 0..500 {
+        let vals: [f64; 4] =
+            core::array::from_fn(|_| (lfsr.get64() % 2000) as f64 / 100. - 10.);
+        let [a, b, c, d] = vals;
+        let x = from_f64s(a, b);
+        let y = from_f64s(c, d);
+
+        assert_eq!(as_f64s(&(&x + &y)), (a + c, b + d));
+        assert_eq!(as_f64s(&(&x - &y)), (a - c, b - d));
+        // The parts of the product are correctly rounded.
+        let exact = |p: f64, q: f64, r: f64, s: f64| {
+            let wide = |v: f64| Float::from_f64(v).cast(crate::FP256);
+            let sum = wide(p) * wide(q) + wide(r) * wide(s);
+            sum.cast(crate::FP64).as_f64()
+        };
+        let prod = (exact(a, c, -b, d), exact(a, d, b, c));
+        assert_eq!(as_f64s(&(&x * &y)), prod);
+
+        if c != 0. || d != 0. {
+            let q = &x / &y;
+            let back = &q * &y;
+            let (re, im) = as_f64s(&back);
+            assert!((re - a).abs() < 1e-12 && (im - b).abs() < 1e-12);
+        }
+    }
+
+    let mut z = from_f64s(1., 2.);
+    z += from_f64s(3., 4.);
+    z *= &from_f64s(0., 1.);
+    z -= from_f64s(1., 1.);
+    z /= from_f64s(2., 0.);
+    assert_eq!(as_f64s(&z), (-3.5, 1.5));
+    assert_eq!(as_f64s(&-z.conj()), (3.5, 1.5));
+}
+
+#[test]
+fn test_complex_annex_g_arithmetic() {
+    let inf = f64::INFINITY;
+    let nan = f64::NAN;
+
+    // An infinity times a non-zero number is infinite.
+    let res = from_f64s(inf, nan) * from_f64s(2., 1.);
+    assert!(res.is_inf());
+    let res = from_f64s(1., 1.) * from_f64s(nan, -inf);
+    assert!(res.is_inf());
+    let res = from_f64s(inf, 0.) * from_f64s(0., 1.);
+    assert_eq!(res.get_im().as_f64(), inf);
+
+    // Division by zero, and by infinity.
+    let res = from_f64s(1., 2.) / from_f64s(0., 0.);
+    assert!(res.is_inf());
+    let res = from_f64s(inf, nan) / from_f64s(3., 4.);
+    assert!(res.is_inf());
+    let res = from_f64s(3., 4.) / from_f64s(inf, nan);
+    assert!(res.is_zero());
+    let res = from_f64s(nan, 1.) * from_f64s(1., 1.);
+    assert!(res.is_nan());
+}
+
+#[test]
+fn test_complex_functions() {
+    use crate::utils::Lfsr;
+    let mut lfsr = Lfsr::new();
+
+    fn close(z: &Complex, expected: (f64, f64)) -> bool {
+        let (re, im) = as_f64s(z);
+        let err = |a: f64, b: f64| (a - b).abs() <= 1e-13 * b.abs().max(1.);
+        err(re, expected.0) && err(im, expected.1)
+    }
+
+    for _ in 0..100 {
+        let a = (lfsr.get64() % 2000) as f64 / 250. - 4.;
+        let b = (lfsr.get64() % 2000) as f64 / 250. - 4.;
+        let z = from_f64s(a, b);
+        let r = a.hypot(b);
+        let t = b.atan2(a);
+
+        assert!(close(&Complex::from_float(z.abs()), (r, 0.)));
+        assert!(close(&Complex::from_float(z.arg()), (t, 0.)));
+        let exp = (a.exp() * b.cos(), a.exp() * b.sin());
+        assert!(close(&z.exp(), exp));
+        assert!(close(&z.log(), (r.ln(), t)));
+        let sqrt_r = r.sqrt();
+        let sqrt = (sqrt_r * (t / 2.).cos(), sqrt_r * (t / 2.).sin());
+        assert!(close(&z.sqrt(), sqrt));
+        let sin = (a.sin() * b.cosh(), a.cos() * b.sinh());
+        assert!(close(&z.sin(), sin));
+        let cos = (a.cos() * b.cosh(), -a.sin() * b.sinh());
+        assert!(close(&z.cos(), cos));
+
+        let (mag, angle) = z.to_polar();
+        assert!(close(&Complex::from_polar(&mag, &angle), (a, b)));
+    }
+
+    // (-1)^0.5 = i, and i^i = e^(-pi/2).
+    let i = from_f64s(0., 1.);
+    let half = from_f64s(0.5, 0.);
+    assert!(close(&from_f64s(-1., 0.).pow(&half), (0., 1.)));
+    let expected = (-core::f64::consts::FRAC_PI_2).exp();
+    assert!(close(&i.pow(&i), (expected, 0.)));
+    assert!(close(&from_f64s(0., 0.).pow(&half), (0., 0.)));
+    assert!(close(&from_f64s(2., 3.).pow(&from_f64s(0., 0.)), (1., 0.)));
+}
+
+#[test]
+fn test_complex_log_near_one() {
+    use crate::FP128;
+    // log|z| is tiny near the unit circle, and must not cancel.
+    let eps = Float::from_f64(1e-30).cast(FP128);
+    let z = Complex::new(Float::one(FP128, false), eps);
+    let res = z.log().get_re().as_f64();
+    assert!((res - 5e-61).abs() < 1e-75);
+
+    // exp(log(z)) = z, with high precision.
+    let sem = Semantics::new(32, 300, RoundingMode::NearestTiesToEven);
+    let z = Complex::new(Float::from_u64(sem, 3), Float::from_i64(sem, -7));
+    let back = z.log().exp();
+    let err = (&back - &z).abs();
+    assert!(err < Float::from_f64(1e-85).cast(sem));
+    let sqr = z.sqrt() * z.sqrt();
+    assert!((&sqr - &z).abs() < Float::from_f64(1e-85).cast(sem));
+}
+
+#[test]
+fn test_complex_annex_g_functions() {
+    let inf = f64::INFINITY;
+    let nan = f64::NAN;
+    let pi = core::f64::consts::PI;
+
+    // Check that the result matches, including the sign of zeros. NaNs match
+    // any NaN.
+    fn check(z: Complex, expected: (f64, f64)) {
+        let (re, im) = as_f64s(&z);
+        let same = |a: f64, b: f64| {
+            (a.is_nan() && b.is_nan())
+                || (a == b && a.is_sign_negative() == b.is_sign_negative())
+        };
+        assert!(
+            same(re, expected.0) && same(im, expected.1),
+            "{:?} != {:?}",
+            (re, im),
+            expected
+        );
+    }
+
+    // G.6.3.1, cexp.
+    check(from_f64s(0., -0.).exp(), (1., -0.));
+    check(from_f64s(1., inf).exp(), (nan, nan));
+    check(from_f64s(inf, 0.).exp(), (inf, 0.));
+    check(from_f64s(-inf, 1.).exp(), (0., 0.));
+    check(from_f64s(-inf, 2.).exp(), (-0., 0.));
+    check(from_f64s(inf, 2.).exp(), (-inf, inf));
+    check(from_f64s(-inf, inf).exp(), (0., 0.));
+    check(from_f64s(inf, nan).exp(), (inf, nan));
+    check(from_f64s(nan, -0.).exp(), (nan, -0.));
+    check(from_f64s(nan, 1.).exp(), (nan, nan));
+
+    // G.6.3.2, clog.
+    check(from_f64s(-0., 0.).log(), (-inf, pi));
+    check(from_f64s(0., -0.).log(), (-inf, -0.));
+    check(from_f64s(1., inf).log(), (inf, pi / 2.));
+    check(from_f64s(1., nan).log(), (nan, nan));
+    check(from_f64s(-inf, 1.).log(), (inf, pi));
+    check(from_f64s(-inf, -1.).log(), (inf, -pi));
+    check(from_f64s(inf, 1.).log(), (inf, 0.));
+    check(from_f64s(-inf, inf).log(), (inf, 3. * pi / 4.));
+    check(from_f64s(inf, inf).log(), (inf, pi / 4.));
+    check(from_f64s(inf, nan).log(), (inf, nan));
+    check(from_f64s(nan, inf).log(), (inf, nan));
+    check(from_f64s(1., 0.).log(), (0., 0.));
+    check(from_f64s(-2., -0.).log(), (2f64.ln(), -pi));
+
+    // G.6.4.2, csqrt.
+    check(from_f64s(-0., 0.).sqrt(), (0., 0.));
+    check(from_f64s(0., -0.).sqrt(), (0., -0.));
+    check(from_f64s(nan, inf).sqrt(), (inf, inf));
+    check(from_f64s(1., nan).sqrt(), (nan, nan));
+    check(from_f64s(-inf, 1.).sqrt(), (0., inf));
+    check(from_f64s(-inf, -1.).sqrt(), (0., -inf));
+    check(from_f64s(inf, 1.).sqrt(), (inf, 0.));
+    check(from_f64s(inf, nan).sqrt(), (inf, nan));
+    check(from_f64s(nan, 1.).sqrt(), (nan, nan));
+    check(from_f64s(-4., 0.).sqrt(), (0., 2.));
+    check(from_f64s(-4., -0.).sqrt(), (0., -2.));
+
+    // G.6.2.5, csinh, and G.6.2.4, ccosh.
+    check(from_f64s(0., 0.).sinh(), (0., 0.));
+    check(from_f64s(0., inf).sinh(), (0., nan));
+    check(from_f64s(1., inf).sinh(), (nan, nan));
+    check(from_f64s(inf, 0.).sinh(), (inf, 0.));
+    check(from_f64s(inf, inf).sinh(), (inf, nan));
+    check(from_f64s(nan, 0.).sinh(), (nan, 0.));
+    check(from_f64s(nan, 1.).sinh(), (nan, nan));
+    check(from_f64s(0., 0.).cosh(), (1., 0.));
+    check(from_f64s(0., inf).cosh(), (nan, 0.));
+    check(from_f64s(inf, 0.).cosh(), (inf, 0.));
+    check(from_f64s(-inf, 0.).cosh(), (inf, -0.));
+    check(from_f64s(inf, inf).cosh(), (inf, nan));
+    check(from_f64s(nan, 0.).cosh(), (nan, 0.));
+    check(from_f64s(1., nan).cosh(), (nan, nan));
+
+    // sin and cos are derived from sinh and cosh.
+    check(from_f64s(0., -0.).sin(), (0., -0.));
+    check(from_f64s(-0., 0.).sin(), (-0., 0.));
+    check(from_f64s(0., 0.).cos(), (1., -0.));
+    check(from_f64s(inf, 0.).sin(), (nan, 0.));
+    check(from_f64s(0., inf).sin(), (0., inf));
+}
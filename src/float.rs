@@ -4,6 +4,7 @@ extern crate alloc;
 use super::bigint::BigInt;
 use super::bigint::LossFraction;
 use core::cmp::Ordering;
+use core::ops::{BitOr, BitOrAssign};
 
 /// Defines the supported rounding modes.
 /// See IEEE754-2019 Section 4.3 Rounding-direction attributes.
@@ -17,6 +18,67 @@ pub enum RoundingMode {
     Negative,
 }
 
+/// A set of exceptions that an operation raised.
+/// See IEEE754-2019 Section 7 Default exception handling.
+///
+/// Similar to the rounding mode, which is a part of the semantics of the
+/// number and not a global flag, the status is returned together with the
+/// result of the operation, and not accumulated in a global state.
+///
+/// # Example
+///
+/// ```
+///     use arpfloat::{Float, RoundingMode, Status, FP64};
+///
+///     let one = Float::from_u64(FP64, 1);
+///     let three = Float::from_u64(FP64, 3);
+///     let rm = RoundingMode::NearestTiesToEven;
+///
+///     let (_, status) = Float::div_with_status(&one, &three, rm);
+///     assert_eq!(status, Status::INEXACT);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Status(u8);
+
+impl Status {
+    /// No exception was raised, and the result is exact.
+    pub const OK: Status = Status(0);
+    /// The operation has no useful result, for example Inf-Inf or 0/0.
+    pub const INVALID_OP: Status = Status(1 << 0);
+    /// An exact infinite result was computed from finite operands.
+    pub const DIV_BY_ZERO: Status = Status(1 << 1);
+    /// The rounded result is too large for the range of the exponent.
+    pub const OVERFLOW: Status = Status(1 << 2);
+    /// The result is tiny (below the smallest normal) and inexact. The
+    /// tininess of the result is detected before rounding.
+    pub const UNDERFLOW: Status = Status(1 << 3);
+    /// The result was rounded and is not equal to the exact result.
+    pub const INEXACT: Status = Status(1 << 4);
+
+    /// Returns true if no exception was raised.
+    pub fn is_ok(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if all of the exceptions in `other` were raised.
+    pub fn contains(&self, other: Status) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Status {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Status(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Status {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
 /// Controls the semantics of a floating point number with:
 /// 'precision', that determines the number of bits, 'exponent' that controls
 /// the dynamic range of the number, and rounding mode that controls how
@@ -282,6 +344,17 @@ impl Float {
         self.mantissa.clone()
     }
 
+    /// Returns a reference to the mantissa of the float, without copying it.
+    pub fn mantissa_ref(&self) -> &BigInt {
+        &self.mantissa
+    }
+
+    /// Moves the mantissa out of the float, and leaves a zero mantissa. The
+    /// float must be overwritten after this call.
+    pub(crate) fn take_mantissa(&mut self) -> BigInt {
+        core::mem::take(&mut self.mantissa)
+    }
+
     /// Returns the exponent of the float.
     pub fn get_exp(&self) -> i64 {
         self.exp
@@ -303,6 +376,11 @@ impl Float {
         )
     }
 
+    /// Flips the sign of the float (negates the value) in place.
+    pub fn neg_in_place(&mut self) {
+        self.sign = !self.sign;
+    }
+
     /// Shift the mantissa to the left to ensure that the MSB if the mantissa
     /// is set to the precision. The method updates the exponent to keep the
     /// number correct.
@@ -310,7 +388,7 @@ impl Float {
         let bits =
             self.sem.get_precision() as i64 - self.mantissa.msb_index() as i64;
         if bits > 0 {
-            self.exp += bits;
+            self.exp -= bits;
             self.mantissa.shift_left(bits as usize);
         }
     }
@@ -413,14 +491,14 @@ fn shift_right_fraction() {
 impl Float {
     /// The number overflowed, set the right value based on the rounding mode
     /// and sign.
-    fn overflow(&mut self, rm: RoundingMode) {
+    fn overflow(&mut self, rm: RoundingMode) -> Status {
         let bounds = self.get_exp_bounds();
         let inf = Self::inf(self.sem, self.sign);
         let max = Self::new(
             self.sem,
             self.sign,
             bounds.1,
-            BigInt::all1s(self.get_mantissa_len()),
+            BigInt::all1s(self.sem.get_precision()),
         );
 
         *self = match rm {
@@ -442,7 +520,8 @@ impl Float {
                     max
                 }
             }
-        }
+        };
+        Status::OVERFLOW | Status::INEXACT
     }
 
     /// Verify that the exponent is legal.
@@ -461,9 +540,9 @@ impl Float {
 
     pub(crate) fn shift_significand_right(&mut self, amt: u64) -> LossFraction {
         self.exp += amt as i64;
-        let res = shift_right_with_loss(&self.mantissa, amt as usize);
-        self.mantissa = res.0;
-        res.1
+        let loss = self.mantissa.get_loss_kind_for_bit(amt as usize);
+        self.mantissa.shift_right(amt as usize);
+        loss
     }
 
     /// Returns true if we need to round away from zero (increment the mantissa).
@@ -491,12 +570,18 @@ impl Float {
 
     /// Normalize the number by adjusting the exponent to the legal range, shift
     /// the mantissa to the msb, and round the number if bits are lost. This is
-    /// based on Neil Booth' implementation in APFloat.
-    pub(crate) fn normalize(&mut self, rm: RoundingMode, loss: LossFraction) {
+    /// based on Neil Booth' implementation in APFloat. Returns the exceptions
+    /// that the rounding raised.
+    pub(crate) fn normalize(
+        &mut self,
+        rm: RoundingMode,
+        loss: LossFraction,
+    ) -> Status {
         if !self.is_normal() {
-            return;
+            return Status::OK;
         }
         let mut loss = loss;
+        let mut is_tiny = false;
         let bounds = self.get_exp_bounds();
 
         let nmsb = self.mantissa.msb_index() as i64;
@@ -508,22 +593,23 @@ impl Float {
 
             // Handle overflowing exponents.
             if self.exp + exp_change > bounds.1 {
-                self.overflow(rm);
+                let status = self.overflow(rm);
                 self.check_bounds();
-                return;
+                return status;
             }
 
             // Handle underflowing low exponents. Don't allow to go below the
             // legal exponent range.
             if self.exp + exp_change < bounds.0 {
                 exp_change = bounds.0 - self.exp;
+                is_tiny = true;
             }
 
             if exp_change < 0 {
                 // Handle reducing the exponent.
                 debug_assert!(loss.is_exactly_zero(), "losing information");
                 self.shift_significand_left(-exp_change as u64);
-                return;
+                return Status::OK;
             }
 
             if exp_change > 0 {
@@ -540,9 +626,14 @@ impl Float {
             // Canonicalize to zero.
             if self.mantissa.is_zero() {
                 *self = Self::zero(self.sem, self.sign);
-                return;
             }
-            return;
+            return Status::OK;
+        }
+
+        // The result is inexact, and a tiny inexact result underflows.
+        let mut status = Status::INEXACT;
+        if is_tiny {
+            status |= Status::UNDERFLOW;
         }
 
         // Check if we need to round away from zero.
@@ -562,7 +653,7 @@ impl Float {
                     self.shift_significand_right(1);
                 } else {
                     *self = Self::inf(self.sem, self.sign);
-                    return;
+                    return status | Status::OVERFLOW;
                 }
             }
         }
@@ -571,6 +662,7 @@ impl Float {
         if self.mantissa.is_zero() {
             *self = Self::zero(self.sem, self.sign);
         }
+        status
     } // round.
 }
 
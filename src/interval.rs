@@ -0,0 +1,770 @@
+//! This module contains the implementation of interval arithmetic, that is
+//! built on top of the Float type. The operations round the lower bound down
+//! and the upper bound up, so the result of every operation encloses the
+//! exact result of the operation on any of the numbers in the inputs.
+
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+use super::float::Float;
+use super::RoundingMode;
+use super::Semantics;
+
+use RoundingMode::Negative as down;
+use RoundingMode::Positive as up;
+
+/// This is a closed interval of floats, [lo, hi]. The bounds are rounded
+/// outward, so the interval always contains the exact result of the
+/// computation. The empty interval is represented with NaN bounds.
+///
+/// # Examples
+///
+/// ```
+///    use arpfloat::{Float, Interval, FP64};
+///
+///    let x = Interval::from_float(Float::from_u64(FP64, 2));
+///    let root = x.sqrt();
+///
+///    // The interval contains the result of the FP64 computation.
+///    let val = Float::from_u64(FP64, 2).sqrt();
+///    assert!(root.contains(&val));
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Interval {
+    lo: Float,
+    hi: Float,
+}
+
+impl Interval {
+    /// Create a new interval [lo, hi]. Both bounds must have the same
+    /// semantics, and `lo` must not be greater than `hi`.
+    pub fn new(lo: Float, hi: Float) -> Self {
+        assert_eq!(
+            lo.get_semantics(),
+            hi.get_semantics(),
+            "the bounds must have the same semantics"
+        );
+        assert!(
+            lo <= hi,
+            "the lower bound must not be above the upper bound"
+        );
+        assert!(
+            !lo.is_inf() || lo.is_negative(),
+            "the lower bound must not be +inf"
+        );
+        assert!(
+            !hi.is_inf() || !hi.is_negative(),
+            "the upper bound must not be -inf"
+        );
+        Interval { lo, hi }
+    }
+
+    /// Create an interval that contains the single number `val`.
+    pub fn from_float(val: Float) -> Self {
+        Self::new(val.clone(), val)
+    }
+
+    /// Create the empty interval.
+    pub fn empty(sem: Semantics) -> Self {
+        let nan = Float::nan(sem, false);
+        Interval {
+            lo: nan.clone(),
+            hi: nan,
+        }
+    }
+
+    /// Create the interval that contains all of the numbers.
+    pub fn entire(sem: Semantics) -> Self {
+        Interval {
+            lo: Float::inf(sem, true),
+            hi: Float::inf(sem, false),
+        }
+    }
+
+    /// Returns the lower bound of the interval.
+    pub fn get_lo(&self) -> Float {
+        self.lo.clone()
+    }
+
+    /// Returns the upper bound of the interval.
+    pub fn get_hi(&self) -> Float {
+        self.hi.clone()
+    }
+
+    /// Returns the semantics of the bounds.
+    pub fn get_semantics(&self) -> Semantics {
+        self.lo.get_semantics()
+    }
+
+    /// Returns true if the interval is empty.
+    pub fn is_empty(&self) -> bool {
+        self.lo.is_nan()
+    }
+
+    /// Returns true if the number `val` is in the interval.
+    pub fn contains(&self, val: &Float) -> bool {
+        !self.is_empty() && !val.is_nan() && self.lo <= *val && *val <= self.hi
+    }
+
+    /// Returns true if all of the numbers in `other` are in the interval.
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        if other.is_empty() {
+            return true;
+        }
+        !self.is_empty() && self.lo <= other.lo && other.hi <= self.hi
+    }
+
+    /// Returns the interval of the numbers that are in both intervals.
+    pub fn intersection(&self, other: &Self) -> Self {
+        if self.is_empty() || other.is_empty() {
+            return Self::empty(self.get_semantics());
+        }
+        let lo = self.lo.max(&other.lo);
+        let hi = self.hi.min(&other.hi);
+        if lo > hi {
+            return Self::empty(self.get_semantics());
+        }
+        Self::new(lo, hi)
+    }
+
+    /// Returns the smallest interval that contains both intervals.
+    pub fn hull(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            return other.clone();
+        } else if other.is_empty() {
+            return self.clone();
+        }
+        Self::new(self.lo.min(&other.lo), self.hi.max(&other.hi))
+    }
+
+    /// Returns the width of the interval, rounded up. The width of the empty
+    /// interval is NaN.
+    pub fn width(&self) -> Float {
+        Float::sub_with_rm(&self.hi, &self.lo, up)
+    }
+
+    /// Returns the midpoint of the interval, rounded to nearest.
+    pub fn midpoint(&self) -> Float {
+        use RoundingMode::NearestTiesToEven as nte;
+        if self.is_empty() {
+            return self.lo.clone();
+        } else if self.lo.is_inf() && self.hi.is_inf() {
+            return Float::zero(self.get_semantics(), false);
+        } else if self.lo.is_inf() || self.hi.is_inf() {
+            return if self.lo.is_inf() { &self.lo } else { &self.hi }.clone();
+        }
+        // Halve the bounds before the addition to avoid an overflow.
+        let a = self.lo.scale(-1, nte);
+        let b = self.hi.scale(-1, nte);
+        Float::add_with_rm(&a, &b, nte)
+    }
+}
+
+impl Interval {
+    /// Computes the bound `op(a, b)` of a product or a quotient, rounded with
+    /// `rm`. A zero times an infinity is zero, because the bound is a limit
+    /// of the products of finite numbers. An infinity divided by an infinity
+    /// is a limit in the range [0, inf], with the sign of the quotient, so it
+    /// is replaced with the end of that range in the direction of the
+    /// rounding.
+    fn bound<F>(a: &Float, b: &Float, rm: RoundingMode, op: F) -> Float
+    where
+        F: Fn(&Float, &Float, RoundingMode) -> Float,
+    {
+        let r = op(a, b, rm);
+        if !r.is_nan() {
+            return r;
+        }
+        let sem = a.get_semantics();
+        if a.is_zero() || b.is_zero() {
+            return Float::zero(sem, false);
+        }
+        let sign = a.get_sign() ^ b.get_sign();
+        if sign == (rm == down) {
+            Float::inf(sem, sign)
+        } else {
+            Float::zero(sem, sign)
+        }
+    }
+
+    /// Returns the interval of the four bounds `op(a, b)`, where `a` and `b`
+    /// are the bounds of the intervals.
+    fn combine<F>(&self, rhs: &Self, op: F) -> Self
+    where
+        F: Fn(&Float, &Float, RoundingMode) -> Float,
+    {
+        let pairs = [
+            (&self.lo, &rhs.lo),
+            (&self.lo, &rhs.hi),
+            (&self.hi, &rhs.lo),
+            (&self.hi, &rhs.hi),
+        ];
+        let mut lo = Float::inf(self.get_semantics(), false);
+        let mut hi = Float::inf(self.get_semantics(), true);
+        for (a, b) in pairs {
+            lo = lo.min(&Self::bound(a, b, down, &op));
+            hi = hi.max(&Self::bound(a, b, up, &op));
+        }
+        Self::new(lo, hi)
+    }
+
+    fn inplace_add(&mut self, rhs: &Self) {
+        if self.is_empty() || rhs.is_empty() {
+            *self = Self::empty(self.get_semantics());
+            return;
+        }
+        self.lo = Float::add_with_rm(&self.lo, &rhs.lo, down);
+        self.hi = Float::add_with_rm(&self.hi, &rhs.hi, up);
+    }
+
+    fn inplace_sub(&mut self, rhs: &Self) {
+        if self.is_empty() || rhs.is_empty() {
+            *self = Self::empty(self.get_semantics());
+            return;
+        }
+        self.lo = Float::sub_with_rm(&self.lo, &rhs.hi, down);
+        self.hi = Float::sub_with_rm(&self.hi, &rhs.lo, up);
+    }
+
+    fn inplace_mul(&mut self, rhs: &Self) {
+        if self.is_empty() || rhs.is_empty() {
+            *self = Self::empty(self.get_semantics());
+            return;
+        }
+        *self = self.combine(rhs, Float::mul_with_rm);
+    }
+
+    /// Divides the interval by `rhs`. The quotient is the entire interval if
+    /// zero is inside `rhs`, and empty if `rhs` is exactly zero. If zero is
+    /// one of the bounds of `rhs`, the quotient is unbounded on one side.
+    fn inplace_div(&mut self, rhs: &Self) {
+        let sem = self.get_semantics();
+        if self.is_empty() || rhs.is_empty() {
+            *self = Self::empty(sem);
+            return;
+        }
+        let zero = Float::zero(sem, false);
+        if rhs.lo.is_zero() && rhs.hi.is_zero() {
+            *self = Self::empty(sem);
+        } else if rhs.lo < zero && rhs.hi > zero {
+            *self = Self::entire(sem);
+        } else {
+            // Give the zero bounds the sign of the rest of the divisor, to
+            // get infinities with the right sign.
+            let rhs = Interval {
+                lo: if rhs.lo.is_zero() {
+                    zero.clone()
+                } else {
+                    rhs.lo.clone()
+                },
+                hi: if rhs.hi.is_zero() {
+                    zero.neg()
+                } else {
+                    rhs.hi.clone()
+                },
+            };
+            *self = self.combine(&rhs, Float::div_with_rm);
+        }
+    }
+}
+
+impl Interval {
+    /// Evaluates `f` on `val` with the directed rounding mode `rm`, and
+    /// returns the result in the semantics of `val`. The function `f` is
+    /// correctly rounded with an error bound that is proven, so the result is
+    /// on the side of the exact value that `rm` selects.
+    fn directed<F>(val: &Float, rm: RoundingMode, f: F) -> Float
+    where
+        F: Fn(&Float) -> Float,
+    {
+        let sem = val.get_semantics();
+        f(&val.cast(sem.with_rm(rm))).cast(sem)
+    }
+
+    /// Returns the square root of `val`, rounded with the directed rounding
+    /// mode `rm`. The root is rounded to nearest, and is then moved to the
+    /// correct side of the exact root, which is checked by squaring it
+    /// exactly.
+    fn sqrt_directed(val: &Float, rm: RoundingMode) -> Float {
+        use RoundingMode::NearestTiesToEven as nte;
+        if val.is_zero() || val.is_inf() {
+            return val.clone();
+        }
+        let sem = val.get_semantics();
+        let wide = sem
+            .increase_precision(sem.get_precision() + 2)
+            .increase_exponent(1);
+        let target = val.cast(wide);
+        let sqr = |x: &Float| {
+            let x = x.cast(wide);
+            Float::mul_with_rm(&x, &x, nte)
+        };
+        let mut root = val.sqrt();
+        if rm == down {
+            while sqr(&root) > target {
+                root = next_float(&root, down);
+            }
+        } else {
+            while sqr(&root) < target {
+                root = next_float(&root, up);
+            }
+        }
+        root
+    }
+
+    /// Computes the square root of the interval. The negative part of the
+    /// interval is ignored.
+    pub fn sqrt(&self) -> Self {
+        let sem = self.get_semantics();
+        let zero = Float::zero(sem, false);
+        if self.is_empty() || self.hi < zero {
+            return Self::empty(sem);
+        }
+        let lo = self.lo.max(&zero);
+        let lo = Self::sqrt_directed(&lo, down);
+        let hi = Self::sqrt_directed(&self.hi, up);
+        Self::new(lo, hi)
+    }
+
+    /// Computes the exponential function `e^x` of the interval.
+    pub fn exp(&self) -> Self {
+        if self.is_empty() {
+            return self.clone();
+        }
+        let lo = Self::directed(&self.lo, down, Float::exp_cr);
+        let hi = Self::directed(&self.hi, up, Float::exp_cr);
+        // The result is never negative.
+        let lo = lo.max(&Float::zero(self.get_semantics(), false));
+        Self::new(lo, hi)
+    }
+
+    /// Computes the natural logarithm of the interval. The non-positive part
+    /// of the interval is ignored.
+    pub fn log(&self) -> Self {
+        let sem = self.get_semantics();
+        let zero = Float::zero(sem, false);
+        if self.is_empty() || self.hi <= zero {
+            return Self::empty(sem);
+        }
+        let lo = if self.lo <= zero {
+            Float::inf(sem, true)
+        } else {
+            Self::directed(&self.lo, down, Float::log_cr)
+        };
+        let hi = Self::directed(&self.hi, up, Float::log_cr);
+        Self::new(lo, hi)
+    }
+
+    /// Computes the sine of the interval (in radians).
+    pub fn sin(&self) -> Self {
+        self.trig(true)
+    }
+
+    /// Computes the cosine of the interval (in radians).
+    pub fn cos(&self) -> Self {
+        self.trig(false)
+    }
+
+    /// Computes the sine or the cosine of the interval. The function is
+    /// monotonic between the extrema, so the result is bounded by the values
+    /// at the ends of the interval, unless the interval contains an extremum.
+    fn trig(&self, is_sin: bool) -> Self {
+        let sem = self.get_semantics();
+        if self.is_empty() {
+            return self.clone();
+        }
+        let one = Float::one(sem, false);
+        let unit = Self::new(one.neg(), one.clone());
+        if self.lo.is_inf() || self.hi.is_inf() {
+            return unit;
+        }
+
+        let f = if is_sin { Float::sin_cr } else { Float::cos_cr };
+        let a = Self::directed(&self.lo, down, f);
+        let b = Self::directed(&self.lo, up, f);
+        let c = Self::directed(&self.hi, down, f);
+        let d = Self::directed(&self.hi, up, f);
+        let mut lo = a.min(&c);
+        let mut hi = b.max(&d);
+
+        // The extrema of cos are at k*pi, and the extrema of sin are at
+        // (k+1/2)*pi. The maximum is at even k and the minimum at odd k.
+        let (has_max, has_min) = self.trig_extrema(is_sin);
+        if has_max {
+            hi = one.clone();
+        }
+        if has_min {
+            lo = one.neg();
+        }
+        Self::new(lo.max(&one.neg()), hi.min(&one))
+    }
+
+    /// Returns a pair of flags that tell if the interval may contain a
+    /// maximum or a minimum of the sine or the cosine. The search is
+    /// conservative: it may find extrema that are just outside of the
+    /// interval, but it never misses an extremum in the interval.
+    fn trig_extrema(&self, is_sin: bool) -> (bool, bool) {
+        use RoundingMode::None as rm;
+        let sem = self.get_semantics();
+        // Wide intervals contain both extrema.
+        let two_pi = Float::cached_pi(sem.increase_precision(2)).scale(1, rm);
+        if self.width() >= two_pi.cast(sem) {
+            return (true, true);
+        }
+
+        // Compute t = x/pi - shift, where the extrema are at integer t, in a
+        // precision that makes the error of t much smaller than the margin.
+        let max_exp = self.lo.get_exp().max(self.hi.get_exp()).max(0);
+        let precision = sem.get_precision() + max_exp as usize + 64;
+        let wide = Semantics::new(sem.get_exponent_len() + 2, precision, rm);
+        let pi = Float::cached_pi(wide);
+        let shift = if is_sin {
+            Float::one(wide, false).scale(-1, rm)
+        } else {
+            Float::zero(wide, false)
+        };
+        let margin = Float::one(wide, false).scale(-32, rm);
+        let t_lo = Float::div_with_rm(&self.lo.cast(wide), &pi, rm);
+        let t_hi = Float::div_with_rm(&self.hi.cast(wide), &pi, rm);
+        let t_lo = &(&t_lo - &shift) - &margin;
+        let t_hi = &(&t_hi - &shift) + &margin;
+
+        // The interval is shorter than 2*pi, so it contains at most three
+        // extrema. Check the parity of the integers in [t_lo, t_hi].
+        let first = ceil(&t_lo);
+        let last = floor(&t_hi);
+        let one = Float::one(wide, false);
+        let two = Float::from_u64(wide, 2);
+        let mut has = (false, false);
+        let mut k = first;
+        while k <= last {
+            let is_even = k.rem(&two).is_zero();
+            if is_even {
+                has.0 = true;
+            } else {
+                has.1 = true;
+            }
+            k += &one;
+        }
+        has
+    }
+}
+
+/// Returns the largest integer that's not greater than `val`.
+fn floor(val: &Float) -> Float {
+    let t = val.trunc();
+    if t > *val {
+        return &t - &Float::one(val.get_semantics(), false);
+    }
+    t
+}
+
+/// Returns the smallest integer that's not less than `val`.
+fn ceil(val: &Float) -> Float {
+    let t = val.trunc();
+    if t < *val {
+        return &t + &Float::one(val.get_semantics(), false);
+    }
+    t
+}
+
+/// Returns the representable number that's next to `val` in the direction of
+/// the rounding mode `rm`, which must be `Positive` or `Negative`. The number
+/// is found by adding the smallest positive number with the rounding mode.
+fn next_float(val: &Float, rm: RoundingMode) -> Float {
+    let sem = val.get_semantics();
+    let one = Float::one(sem, rm == down);
+    let tiny = one.scale(-(sem.get_precision() as i64) - (1 << 40), rm);
+    Float::add_with_rm(val, &tiny, rm)
+}
+
+impl Neg for Interval {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        (&self).neg()
+    }
+}
+
+impl Neg for &Interval {
+    type Output = Interval;
+    fn neg(self) -> Self::Output {
+        if self.is_empty() {
+            return self.clone();
+        }
+        Interval::new(self.hi.neg(), self.lo.neg())
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return self.is_empty() && other.is_empty();
+        }
+        self.lo == other.lo && self.hi == other.hi
+    }
+}
+
+macro_rules! declare_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $func_impl_name:ident) => {
+        // Self + Self
+        impl $trait_name for Interval {
+            type Output = Self;
+
+            fn $func_name(self, rhs: Self) -> Self::Output {
+                self.$func_name(&rhs)
+            }
+        }
+
+        // Self + &Self -> Self
+        impl $trait_name<&Self> for Interval {
+            type Output = Self;
+            fn $func_name(self, rhs: &Self) -> Self::Output {
+                let mut n = self;
+                n.$func_impl_name(rhs);
+                n
+            }
+        }
+
+        // &Self + &Self -> Self
+        impl $trait_name<Self> for &Interval {
+            type Output = Interval;
+            fn $func_name(self, rhs: Self) -> Self::Output {
+                let mut n = self.clone();
+                n.$func_impl_name(rhs);
+                n
+            }
+        }
+    };
+}
+
+declare_operator!(Add, add, inplace_add);
+declare_operator!(Sub, sub, inplace_sub);
+declare_operator!(Mul, mul, inplace_mul);
+declare_operator!(Div, div, inplace_div);
+
+macro_rules! declare_assign_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $func_impl_name:ident) => {
+        impl $trait_name for Interval {
+            fn $func_name(&mut self, rhs: Self) {
+                self.$func_impl_name(&rhs);
+            }
+        }
+
+        impl $trait_name<&Interval> for Interval {
+            fn $func_name(&mut self, rhs: &Self) {
+                self.$func_impl_name(rhs);
+            }
+        }
+    };
+}
+
+declare_assign_operator!(AddAssign, add_assign, inplace_add);
+declare_assign_operator!(SubAssign, sub_assign, inplace_sub);
+declare_assign_operator!(MulAssign, mul_assign, inplace_mul);
+declare_assign_operator!(DivAssign, div_assign, inplace_div);
+
+#[cfg(test)]
+fn from_f64s(lo: f64, hi: f64) -> Interval {
+    Interval::new(Float::from_f64(lo), Float::from_f64(hi))
+}
+
+#[test]
+fn test_interval_arithmetic() {
+    use crate::utils::Lfsr;
+    use crate::FP256;
+    let mut lfsr = Lfsr::new();
+
+    // Check that the intervals enclose the exact result, that's computed in
+    // a wide format, and that the bounds are tight.
+    let encloses = |r: &Interval, exact: &Float| {
+        let wide =
+            Interval::new(r.get_lo().cast(FP256), r.get_hi().cast(FP256));
+        let nearest = exact.cast(crate::FP64);
+        assert!(wide.contains(exact));
+        assert!(r.contains(&nearest));
+        assert!(next_float(&r.get_lo(), up) >= nearest);
+        assert!(next_float(&r.get_hi(), down) <= nearest);
+    };
+
+    for _ in 0..500 {
+        let vals: [f64; 2] = core::array::from_fn(|_| {
+            (lfsr.get64() % 20000) as f64 / 997. - 10.
+        });
+        let [a, b] = vals;
+        let x = from_f64s(a, a);
+        let y = from_f64s(b, b);
+        let (a, b) = (Float::from_f64(a), Float::from_f64(b));
+        let (wa, wb) = (a.cast(FP256), b.cast(FP256));
+
+        encloses(&(&x + &y), &(&wa + &wb));
+        encloses(&(&x - &y), &(&wa - &wb));
+        encloses(&(&x * &y), &(&wa * &wb));
+        if !b.is_zero() {
+            encloses(&(&x / &y), &(&wa / &wb));
+        }
+    }
+
+    // Operations on intervals with a non-zero width.
+    let x = from_f64s(-1., 2.);
+    let y = from_f64s(3., 5.);
+    assert_eq!(&x + &y, from_f64s(2., 7.));
+    assert_eq!(&x - &y, from_f64s(-6., -1.));
+    assert_eq!(&x * &y, from_f64s(-5., 10.));
+    assert_eq!(&x * &-&y, from_f64s(-10., 5.));
+    assert_eq!(&y / &from_f64s(2., 4.), from_f64s(0.75, 2.5));
+    assert_eq!(&y / &x, Interval::entire(crate::FP64));
+    assert!((&y / &from_f64s(0., 0.)).is_empty());
+    let inf = f64::INFINITY;
+    assert_eq!(from_f64s(0., 1.) * from_f64s(2., inf), from_f64s(0., inf));
+
+    // Division by intervals that are unbounded, or that end at zero.
+    let pos = from_f64s(1., inf);
+    let neg = from_f64s(-inf, -1.);
+    assert_eq!(&pos / &pos, from_f64s(0., inf));
+    assert_eq!(&neg / &neg, from_f64s(0., inf));
+    assert_eq!(&pos / &neg, from_f64s(-inf, 0.));
+    assert_eq!(from_f64s(1., 2.) / from_f64s(0., 4.), from_f64s(0.25, inf));
+    assert_eq!(
+        from_f64s(1., 2.) / from_f64s(-4., 0.),
+        from_f64s(-inf, -0.25)
+    );
+    assert_eq!(
+        from_f64s(-2., -1.) / from_f64s(0., 4.),
+        from_f64s(-inf, -0.25)
+    );
+    assert_eq!(from_f64s(0., 2.) / from_f64s(0., 4.), from_f64s(0., inf));
+    assert_eq!(
+        from_f64s(-1., 2.) / from_f64s(0., 4.),
+        Interval::entire(crate::FP64)
+    );
+
+    let mut z = from_f64s(1., 2.);
+    z += from_f64s(1., 1.);
+    z *= &from_f64s(-1., 1.);
+    z -= from_f64s(0., 1.);
+    z /= from_f64s(2., 2.);
+    assert_eq!(z, from_f64s(-2., 1.5));
+}
+
+#[test]
+fn test_interval_queries() {
+    let x = from_f64s(-1., 2.);
+    let y = from_f64s(1., 5.);
+    let sem = crate::FP64;
+    assert!(x.contains(&Float::from_f64(2.)));
+    assert!(!x.contains(&Float::from_f64(2.5)));
+    assert!(!x.contains(&Float::nan(sem, false)));
+    assert!(x.contains_interval(&from_f64s(0., 1.)));
+    assert!(!x.contains_interval(&y));
+    assert!(x.contains_interval(&Interval::empty(sem)));
+    assert_eq!(x.intersection(&y), from_f64s(1., 2.));
+    assert!(x.intersection(&from_f64s(3., 4.)).is_empty());
+    assert_eq!(x.hull(&y), from_f64s(-1., 5.));
+    assert_eq!(x.hull(&Interval::empty(sem)), x);
+    assert_eq!(x.width().as_f64(), 3.);
+    assert_eq!(x.midpoint().as_f64(), 0.5);
+    assert!(Interval::empty(sem).width().is_nan());
+
+    // The width is rounded up.
+    let tiny = Float::from_f64(1e-30);
+    let w = Interval::new(Float::from_f64(-1.), tiny.clone()).width();
+    assert!(w.as_f64() > 1.);
+}
+
+#[test]
+fn test_interval_functions() {
+    use crate::utils::Lfsr;
+    use crate::FP256;
+    let mut lfsr = Lfsr::new();
+
+    // Check that the result of a function on a random interval contains the
+    // function at points in the interval, computed in a wide format.
+    for _ in 0..50 {
+        let a = (lfsr.get64() % 20000) as f64 / 500. - 20.;
+        let w = (lfsr.get64() % 1000) as f64 / 500.;
+        let x = from_f64s(a, a + w);
+        let (sin, cos, exp) = (x.sin(), x.cos(), x.exp());
+        let abs = from_f64s(a.abs(), a.abs() + w);
+        let (sqrt, log) = (abs.sqrt(), abs.log());
+        for i in 0..=8 {
+            let p = Float::from_f64(a + w * i as f64 / 8.);
+            let wp = p.cast(FP256);
+            let wide = |r: &Interval| {
+                let lo = r.get_lo().cast(FP256);
+                Interval::new(lo, r.get_hi().cast(FP256))
+            };
+            assert!(wide(&sin).contains(&wp.sin()));
+            assert!(wide(&cos).contains(&wp.cos()));
+            assert!(wide(&exp).contains(&wp.exp()));
+            let q = Float::from_f64(a.abs() + w * i as f64 / 8.).cast(FP256);
+            assert!(wide(&sqrt).contains(&q.sqrt()));
+            if !q.is_zero() {
+                assert!(wide(&log).contains(&q.log()));
+            }
+        }
+    }
+
+    // Validate FP64 results against the proven bounds.
+    let two = from_f64s(2., 2.);
+    assert!(two.sqrt().contains(&Float::from_f64(2f64.sqrt())));
+    assert!(two.exp().contains(&Float::from_f64(2f64.exp())));
+    assert!(two.log().contains(&Float::from_f64(2f64.ln())));
+    assert!(two.sin().contains(&Float::from_f64(2f64.sin())));
+    assert!(two.cos().contains(&Float::from_f64(2f64.cos())));
+    for r in [two.exp(), two.log(), two.sin(), two.cos()] {
+        assert_eq!(next_float(&r.get_lo(), up), r.get_hi());
+    }
+    assert!(two.sqrt().width().as_f64() <= f64::EPSILON * 2.);
+    assert_eq!(from_f64s(4., 9.).sqrt(), from_f64s(2., 3.));
+
+    // Extrema, and the special values.
+    assert_eq!(from_f64s(1., 2.).sin().get_hi().as_f64(), 1.);
+    assert_eq!(from_f64s(3., 3.5).cos().get_lo().as_f64(), -1.);
+    assert_eq!(from_f64s(0., 7.).sin(), from_f64s(-1., 1.));
+    let inf = f64::INFINITY;
+    assert_eq!(from_f64s(-inf, 0.).exp(), from_f64s(0., 1.));
+    assert_eq!(from_f64s(-1., 1.).log().get_lo().as_f64(), -inf);
+    assert_eq!(from_f64s(-4., 4.).sqrt(), from_f64s(0., 2.));
+    assert!(from_f64s(-4., -1.).sqrt().is_empty());
+    assert_eq!(from_f64s(-4., -0.).sqrt(), from_f64s(0., 0.));
+    assert_eq!(from_f64s(-0., -0.).sqrt(), from_f64s(0., 0.));
+    assert!(from_f64s(-4., -0.).log().is_empty());
+    assert!(from_f64s(-4., 0.).log().is_empty());
+}
+
+#[test]
+fn test_interval_high_precision() {
+    use RoundingMode::NearestTiesToEven as nte;
+    let sem = Semantics::new(32, 3000, nte);
+    let wide = Semantics::new(32, 6200, nte);
+    let contains = |r: &Interval, val: &Float| {
+        let lo = r.get_lo().cast(wide);
+        Interval::new(lo, r.get_hi().cast(wide)).contains(val)
+    };
+
+    // Compare the bounds with the constants, which are computed with binary
+    // splitting. The bounds are the two numbers around the exact result. For
+    // x = pi rounded to the format, sin(x) is pi - x up to (pi - x)^3.
+    let one = Interval::from_float(Float::one(sem, false));
+    let two = Interval::from_float(Float::from_u64(sem, 2));
+    let ten = Interval::from_float(Float::from_u64(sem, 10));
+    let pi = Interval::from_float(Float::pi(sem));
+    let pi_err = &Float::pi(wide) - &pi.get_lo().cast(wide);
+    for (r, exact) in [
+        (two.log(), Float::ln2(wide)),
+        (ten.log(), Float::ln10(wide)),
+        (one.exp(), Float::e(wide)),
+        (pi.sin(), pi_err.clone()),
+        (pi.cos(), Float::one(wide, true)),
+    ] {
+        assert!(contains(&r, &exact));
+        assert!(r.get_lo() < r.get_hi());
+        assert_eq!(next_float(&r.get_lo(), up), r.get_hi());
+    }
+}
@@ -52,6 +52,19 @@
 //!    let val = Float::mul_with_rm(&x, &y, NearestTiesToEven);
 //! ```
 //!
+//! The exceptions that an operation raised (such as inexact, overflow or
+//! invalid operation) are returned together with the result, instead of being
+//! accumulated in a global state:
+//!```
+//!    use arpfloat::{Float, Status, FP16};
+//!    use arpfloat::RoundingMode::NearestTiesToEven;
+//!
+//!    let x = Float::from_u64(FP16, 60000);
+//!    let (val, status) = Float::add_with_status(&x, &x, NearestTiesToEven);
+//!    assert!(val.is_inf());
+//!    assert!(status.contains(Status::OVERFLOW | Status::INEXACT));
+//!```
+//!
 //! View the internal representation of floating point numbers:
 //! ```
 //!    use arpfloat::Float;
@@ -65,6 +78,17 @@
 //!    m.dump(); // Prints 11110000000
 //!```
 //!
+//! Numbers are printed with the shortest number of digits that round-trip, and
+//! the formatter flags for precision, width, sign and exponents are supported:
+//!```
+//!    use arpfloat::{Float, FP128};
+//!
+//!    let pi = Float::pi(FP128);
+//!    assert_eq!(format!("{:.10}", pi), "3.1415926536");
+//!    assert_eq!(format!("{:+.3e}", pi), "+3.142e0");
+//!    assert_eq!(format!("{}", Float::from_f64(1e300)), "1e300");
+//!```
+//!
 //! Control the rounding mode for type conversion:
 //!```
 //!    use arpfloat::{FP16, FP32, RoundingMode, Float};
@@ -86,6 +110,16 @@
 //!  println!("e^pi = {}", x); // Prints 23.1406926327792....
 //!```
 //!
+//! The functions `sin_cr`, `cos_cr`, `tan_cr`, `exp_cr`, `log_cr` and `pow_cr`
+//! are correctly rounded with the rounding mode of the semantics. They retry
+//! the evaluation at higher precision until the result is unambiguous:
+//!```
+//!  use arpfloat::{Float, FP64, RoundingMode};
+//!
+//!  let x = Float::from_f64(1e22).cast(FP64.with_rm(RoundingMode::Zero));
+//!  println!("sin(1e22) = {}", x.sin_cr());
+//!```
+//!
 //! Floating point numbers can be converted to
 //! [Continued Fractions](https://en.wikipedia.org/wiki/Continued_fraction) that
 //! approximate the value.
@@ -102,7 +136,7 @@
 //! ```
 //!The program above will print this output:
 //!```console
-//!  ln(2) = .6931471805599453094172321214581765680755001343.....
+//!  ln(2) = 0.6931471805599453094172321214581765680755001343.....
 //!  0/1
 //!  1/1
 //!  2/3
@@ -125,14 +159,25 @@ extern crate std;
 
 mod arithmetic;
 mod bigint;
+mod bigsint;
 mod cast;
+mod complex;
 mod float;
+mod interval;
 mod operations;
+mod parts;
+mod rational;
 mod string;
 mod utils;
 
 pub use self::bigint::BigInt;
+pub use self::bigsint::BigSInt;
+pub use self::complex::Complex;
 pub use self::float::Float;
 pub use self::float::RoundingMode;
 pub use self::float::Semantics;
+pub use self::float::Status;
 pub use self::float::{FP128, FP16, FP256, FP32, FP64};
+pub use self::interval::Interval;
+pub use self::operations::ConstantCache;
+pub use self::rational::{ContinuedFraction, Convergents, Rational};
@@ -0,0 +1,305 @@
+//! This module contains a cache for mathematical constants, such as pi, that
+//! are expensive to compute at high precision. The cache keeps the value with
+//! the highest precision that was computed so far, and derives the narrower
+//! values by casting it.
+
+use crate::{Float, RoundingMode, Semantics};
+
+/// The number of extra bits that the cached values have above the requested
+/// precision. The cached values are truncated, so the exact constant is
+/// between the cached value and the next number. The cast is correct if both
+/// of them round to the same number, which the extra bits make likely. The
+/// constant is computed again with more bits if they don't.
+const GUARD_BITS: usize = 32;
+
+/// The exponent length of the cached values.
+const EXPONENT_LEN: usize = 16;
+
+/// The constants that the cache stores.
+#[derive(Debug, Clone, Copy)]
+enum Constant {
+    Pi,
+    E,
+    Ln2,
+    Ln10,
+}
+
+const NUM_CONSTANTS: usize = 4;
+
+impl Constant {
+    /// Computes the constant in the semantics `sem`.
+    fn compute(&self, sem: Semantics) -> Float {
+        match self {
+            Constant::Pi => Float::pi(sem),
+            Constant::E => Float::e(sem),
+            Constant::Ln2 => Float::ln2(sem),
+            Constant::Ln10 => Float::ln10(sem),
+        }
+    }
+}
+
+/// A cache of mathematical constants. The cache is owned by the caller and
+/// does not allocate memory beyond the stored values, so it can be used
+/// without the standard library. With the `std` feature, the math functions
+/// use a thread-safe global cache. The functions that end with `_with_cache`,
+/// such as [`Float::sin_with_cache`], use the cache that the caller passes.
+///
+/// # Examples
+///
+/// ```
+///    use arpfloat::{ConstantCache, Float, FP128, FP64};
+///
+///    let mut cache = ConstantCache::new();
+///    let pi = cache.pi(FP128);
+///    assert_eq!(pi, Float::pi(FP128));
+///
+///    // Narrower values are derived from the cached value.
+///    assert_eq!(cache.pi(FP64).as_f64(), std::f64::consts::PI);
+///
+///    let x = Float::from_f64(10.);
+///    assert_eq!(x.sin_with_cache(&mut cache), x.sin());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConstantCache {
+    values: [Option<Float>; NUM_CONSTANTS],
+}
+
+impl Default for ConstantCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConstantCache {
+    /// Creates a new empty cache.
+    pub const fn new() -> Self {
+        const EMPTY: Option<Float> = None;
+        ConstantCache {
+            values: [EMPTY; NUM_CONSTANTS],
+        }
+    }
+
+    /// Removes all of the values from the cache.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns pi in the semantics `sem`.
+    pub fn pi(&mut self, sem: Semantics) -> Float {
+        self.get(Constant::Pi, sem)
+    }
+
+    /// Returns e in the semantics `sem`.
+    pub fn e(&mut self, sem: Semantics) -> Float {
+        self.get(Constant::E, sem)
+    }
+
+    /// Returns log(2) in the semantics `sem`.
+    pub fn ln2(&mut self, sem: Semantics) -> Float {
+        self.get(Constant::Ln2, sem)
+    }
+
+    /// Returns log(10) in the semantics `sem`.
+    pub fn ln10(&mut self, sem: Semantics) -> Float {
+        self.get(Constant::Ln10, sem)
+    }
+
+    /// Returns the constant `c` in the semantics `sem`, and computes it if the
+    /// cached value is not accurate enough.
+    fn get(&mut self, c: Constant, sem: Semantics) -> Float {
+        if let Some(val) = self.lookup(c, sem) {
+            return val;
+        }
+        let (val, res) = compute_wide(c, sem, self.precision(c));
+        self.insert(c, val);
+        res
+    }
+
+    /// Returns the constant `c` in the semantics `sem`, if the cached value is
+    /// accurate enough.
+    fn lookup(&self, c: Constant, sem: Semantics) -> Option<Float> {
+        let val = self.values[c as usize].as_ref()?;
+        let precision = val.get_semantics().get_precision();
+        if precision < sem.get_precision() + GUARD_BITS {
+            return None;
+        }
+        round_wide(val, sem)
+    }
+
+    /// Returns the precision of the cached value of `c`, or zero.
+    fn precision(&self, c: Constant) -> usize {
+        let val = self.values[c as usize].as_ref();
+        val.map_or(0, |val| val.get_semantics().get_precision())
+    }
+
+    /// Saves the value `val` of the constant `c`, unless the cache has a
+    /// more accurate value.
+    fn insert(&mut self, c: Constant, val: Float) {
+        let slot = &mut self.values[c as usize];
+        if let Some(prev) = slot {
+            let prev_precision = prev.get_semantics().get_precision();
+            if prev_precision >= val.get_semantics().get_precision() {
+                return;
+            }
+        }
+        *slot = Some(val);
+    }
+}
+
+/// Rounds the truncated positive value `val` of a constant to `sem`. Returns
+/// None if `val` is not accurate enough to tell how the constant rounds.
+fn round_wide(val: &Float, sem: Semantics) -> Option<Float> {
+    let rm = sem.get_rounding_mode();
+    let wide = val.get_semantics();
+    let ulp = val.get_exp() - wide.get_precision() as i64 + 1;
+    let ulp = Float::one(wide, false).scale(ulp, RoundingMode::None);
+    let next = Float::add_with_rm(val, &ulp, RoundingMode::None);
+    let lo = val.cast_with_rm(sem, rm);
+    let hi = next.cast_with_rm(sem, rm);
+    if lo != hi {
+        return None;
+    }
+    Some(lo)
+}
+
+/// Computes the value of the constant `c` that is stored in the cache, with
+/// more bits than `sem` and than the previous value, which had `prev` bits.
+/// Returns the truncated wide value and the value rounded to `sem`.
+fn compute_wide(c: Constant, sem: Semantics, prev: usize) -> (Float, Float) {
+    let mut precision = sem.get_precision() + GUARD_BITS;
+    loop {
+        if precision > prev {
+            let rm = RoundingMode::Zero;
+            let val = c.compute(Semantics::new(EXPONENT_LEN, precision, rm));
+            if let Some(res) = round_wide(&val, sem) {
+                return (val, res);
+            }
+        }
+        precision *= 2;
+    }
+}
+
+#[cfg(feature = "std")]
+mod global {
+    extern crate std;
+
+    use super::{compute_wide, Constant, ConstantCache};
+    use crate::{Float, Semantics};
+    use std::sync::{Mutex, MutexGuard};
+
+    static GLOBAL_CACHE: Mutex<ConstantCache> =
+        Mutex::new(ConstantCache::new());
+
+    fn lock() -> MutexGuard<'static, ConstantCache> {
+        // The cache is always in a valid state, even if a thread panicked.
+        GLOBAL_CACHE.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Returns the constant `c` from the global cache. The value is computed
+    /// without holding the lock, to allow other threads to use the cache.
+    pub(super) fn get(c: Constant, sem: Semantics) -> Float {
+        let prev = {
+            let cache = lock();
+            if let Some(val) = cache.lookup(c, sem) {
+                return val;
+            }
+            cache.precision(c)
+        };
+        let (val, res) = compute_wide(c, sem, prev);
+        lock().insert(c, val);
+        res
+    }
+
+    impl ConstantCache {
+        /// Removes all of the values from the global cache that the math
+        /// functions use.
+        pub fn clear_global() {
+            lock().clear();
+        }
+    }
+}
+
+impl Float {
+    /// Returns the constant `c` in the semantics `sem`. Uses the global cache
+    /// if it's available, and computes the constant on every call otherwise.
+    /// The `_with_cache` functions avoid this without the standard library.
+    fn cached(c: Constant, sem: Semantics) -> Self {
+        #[cfg(feature = "std")]
+        {
+            global::get(c, sem)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            c.compute(sem)
+        }
+    }
+
+    /// Returns pi, using the cache of constants.
+    pub(crate) fn cached_pi(sem: Semantics) -> Self {
+        Self::cached(Constant::Pi, sem)
+    }
+
+    /// Returns log(2), using the cache of constants.
+    pub(crate) fn cached_ln2(sem: Semantics) -> Self {
+        Self::cached(Constant::Ln2, sem)
+    }
+
+    /// Returns log(10), using the cache of constants.
+    pub(crate) fn cached_ln10(sem: Semantics) -> Self {
+        Self::cached(Constant::Ln10, sem)
+    }
+}
+
+#[test]
+fn test_constant_cache() {
+    use crate::{FP128, FP16, FP256, FP32, FP64};
+
+    let mut cache = ConstantCache::new();
+    assert_eq!(cache.pi(FP64), Float::pi(FP64));
+    let sem = Semantics::new(32, 1000, RoundingMode::NearestTiesToEven);
+    let pi = cache.pi(sem);
+    assert_eq!(pi, Float::pi(sem));
+
+    // Narrower values come from the cached value, in any rounding mode.
+    for sem in [FP16, FP32, FP64, FP128, FP256] {
+        for rm in [RoundingMode::Zero, RoundingMode::Positive] {
+            let sem = sem.with_rm(rm);
+            assert_eq!(cache.pi(sem), Float::pi(sem));
+            assert_eq!(cache.e(sem), Float::e(sem));
+            assert_eq!(cache.ln2(sem), Float::ln2(sem));
+            assert_eq!(cache.ln10(sem), Float::ln10(sem));
+        }
+    }
+    let wide = cache.values[Constant::Pi as usize].as_ref().unwrap();
+    assert_eq!(wide.get_semantics().get_precision(), 1000 + GUARD_BITS);
+
+    cache.clear();
+    assert!(cache.values.iter().all(|val| val.is_none()));
+    assert_eq!(Float::cached_pi(FP128), Float::pi(FP128));
+    assert_eq!(Float::cached_ln2(FP64), Float::ln2(FP64));
+    assert_eq!(Float::cached_ln10(FP64), Float::ln10(FP64));
+
+    // The math functions that take the cache.
+    for v in [0.5, 3., 10., -100.] {
+        let x = Float::from_f64(v).cast(FP128);
+        assert_eq!(x.sin_with_cache(&mut cache), x.sin());
+        assert_eq!(x.cos_with_cache(&mut cache), x.cos());
+        assert_eq!(x.tan_with_cache(&mut cache), x.tan());
+        assert_eq!(x.exp2_with_cache(&mut cache), x.exp2());
+        assert_eq!(x.exp10_with_cache(&mut cache), x.exp10());
+        let x = x.abs();
+        assert_eq!(x.log2_with_cache(&mut cache), x.log2());
+        assert_eq!(x.log10_with_cache(&mut cache), x.log10());
+    }
+    assert!(cache.values[Constant::Pi as usize].is_some());
+    assert!(cache.values[Constant::Ln2 as usize].is_some());
+    assert!(cache.values[Constant::Ln10 as usize].is_some());
+
+    // The exact constant is in [val, val + ulp), which may round to two
+    // different numbers.
+    let wide = Semantics::new(EXPONENT_LEN, 100, RoundingMode::Zero);
+    let one = Float::one(wide, false);
+    let up = FP64.with_rm(RoundingMode::Positive);
+    assert!(round_wide(&one, up).is_none());
+    assert_eq!(round_wide(&one, FP64).unwrap().as_f64(), 1.);
+}
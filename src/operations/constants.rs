@@ -1,82 +1,331 @@
 //! This module contains the implementation of methods that compute mathematical
 //! constants.
 //!
+use crate::bigint::BigInt;
+use crate::bigsint::BigSInt;
 use crate::RoundingMode;
 use crate::{Float, Semantics};
 
-impl Float {
-    /// Computes pi.
-    pub fn pi(sem: Semantics) -> Self {
-        // Algorithm description in Pg 246:
-        // Fast Multiple-Precision Evaluation of Elementary Functions
-        // by Richard P. Brent.
+/// Evaluates the terms [a, b) of the Chudnovsky series with binary splitting,
+/// and returns the values P, Q and R.
+fn chudnovsky_split(a: u64, b: u64) -> (BigSInt, BigInt, BigSInt) {
+    if b == a + 1 {
+        let k = a as u128;
+        let p = (6 * k - 5) * (2 * k - 1) * (6 * k - 1);
+        let p = BigSInt::new(true, BigInt::from_u128(p));
+        // 640320^3 / 24.
+        let q =
+            BigInt::from_u64(10939058860032000) * BigInt::from_u128(k * k * k);
+        let r = p.clone() * (545140134 * a as i64 + 13591409);
+        return (p, q, r);
+    }
+    let m = (a + b) / 2;
+    let (pam, qam, ram) = chudnovsky_split(a, m);
+    let (pmb, qmb, rmb) = chudnovsky_split(m, b);
+    let r = BigSInt::from(qmb.clone()) * ram + &pam * &rmb;
+    (pam * pmb, qam * qmb, r)
+}
 
-        // Increase the precision, because the arithmetic operations below
-        // require rounding, so if we want to get the accurate results we need
-        // to operate with increased precision.
-        let orig_sem = sem;
-        let sem = sem.grow_log(4);
+/// Evaluates the terms [a, b) of the series sum(a!/k!) for k in (a, b] with
+/// binary splitting, and returns the values P and Q.
+fn factorial_split(a: u64, b: u64) -> (BigInt, BigInt) {
+    if b == a + 1 {
+        return (BigInt::one(), BigInt::from_u64(b));
+    }
+    let m = (a + b) / 2;
+    let (pam, qam) = factorial_split(a, m);
+    let (pmb, qmb) = factorial_split(m, b);
+    (pam * &qmb + pmb, qam * qmb)
+}
 
-        use RoundingMode::NearestTiesToEven as rm;
+/// Evaluates the terms [a, b) of the series atanh(1/x) with binary splitting,
+/// and returns the values T, B and Q, where the sum is T/(B*Q).
+fn atanh_split(x: u64, a: u64, b: u64) -> (BigInt, BigInt, BigInt) {
+    if b == a + 1 {
+        let q = if a == 0 { x } else { x * x };
+        let q = BigInt::from_u64(q);
+        return (BigInt::one(), BigInt::from_u64(2 * a + 1), q);
+    }
+    let m = (a + b) / 2;
+    let (tam, bam, qam) = atanh_split(x, a, m);
+    let (tmb, bmb, qmb) = atanh_split(x, m, b);
+    let t = &bmb * &qmb * tam + &bam * &tmb;
+    (t, bam * bmb, qam * qmb)
+}
 
-        let one = Self::from_i64(sem, 1);
-        let two = Self::from_i64(sem, 2);
-        let four = Self::from_i64(sem, 4);
+/// Computes atanh(1/x) with binary splitting. The terms that are left out
+/// add up to less than x^-(2*terms + 1) < 2^-(w+log2(x)), so the error is
+/// below two ulps.
+fn atanh_inv(sem: Semantics, x: u64) -> Float {
+    use RoundingMode::None as rm;
+    let terms = sem.get_precision() as u64 / (2 * x.ilog2() as u64) + 1;
+    let (t, b, q) = atanh_split(x, 0, terms);
+    Float::from_fraction(sem, false, &t, &(b * q), rm)
+}
 
-        let mut a = one.clone();
-        let mut b = one.clone() / two.sqrt();
-        let mut t = one.clone() / four;
-        let mut x = one;
+/// Computes sum(c * atanh(1/x)) for the pairs (c, x) in `terms`. Returns the
+/// sum and `e`, where 2^e bounds the absolute error.
+fn atanh_combination(sem: Semantics, terms: &[(i64, u64)]) -> (Float, i64) {
+    use RoundingMode::None as rm;
+    let mut sum = Float::zero(sem, false);
+    let mut weight = 0;
+    for &(c, x) in terms {
+        let a = atanh_inv(sem, x);
+        let a = Float::mul_with_rm(&a, &Float::from_i64(sem, c), rm);
+        sum = Float::add_with_rm(&sum, &a, rm);
+        weight += c.unsigned_abs();
+    }
+    // atanh(1/x) is below 1/2, and its error is below 2^-w, where w is the
+    // precision. The error of each product is below 2*|c|*2^-w, and the error
+    // of each addition is below sum(|c|)*2^-w.
+    let err = (terms.len() as u64 + 2) * weight;
+    let w = sem.get_precision() as i64;
+    (sum, err.next_power_of_two().ilog2() as i64 - w)
+}
 
-        while a != b {
-            let y = a.clone();
-            a = (&a + &b).scale(-1, rm);
-            b = (&b * &y).sqrt();
-            t -= &x * (&a - &y).sqr();
-            x = x.scale(1, rm);
-        }
-        (a.sqr() / t).cast(orig_sem)
+/// Evaluates the terms [a, b) of the series sum(a(k) * p(a)/q(a) * ... *
+/// p(k)/q(k)) with binary splitting. The callback `term` returns p(k), q(k)
+/// and a(k). Returns the values P, Q and T, where the sum is T/Q.
+fn hypergeometric_split(
+    a: u64,
+    b: u64,
+    term: &dyn Fn(u64) -> (BigSInt, BigInt, BigInt),
+) -> (BigSInt, BigInt, BigSInt) {
+    if b == a + 1 {
+        let (p, q, c) = term(a);
+        let t = &p * &BigSInt::from(c);
+        return (p, q, t);
+    }
+    let m = (a + b) / 2;
+    let (pam, qam, tam) = hypergeometric_split(a, m, term);
+    let (pmb, qmb, tmb) = hypergeometric_split(m, b, term);
+    let t = BigSInt::from(qmb.clone()) * tam + &pam * &tmb;
+    (pam * pmb, qam * qmb, t)
+}
+
+/// The Machin-like formula for log(2), as pairs (c, x) of the terms
+/// c * atanh(1/x): ln(2) = 18*atanh(1/26) - 2*atanh(1/4801) + 8*atanh(1/8749).
+const LN2: [(i64, u64); 3] = [(18, 26), (-2, 4801), (8, 8749)];
+
+/// The values of the binary splitting of the Brent-McMillan series.
+struct GammaSplit {
+    p: BigInt,
+    q: BigInt,
+    d: BigInt,
+    c: BigInt,
+    t: BigInt,
+    v: BigInt,
+}
+
+/// Evaluates the terms [a, b) of the series sum(u(k)) and sum(u(k) * H(k)),
+/// where u(k) = n2^k / k!^2, and H(k) is the sum of 1/j for j in [a, k].
+/// The first sum is T/Q, the second sum is V/(Q*D) and the sum of 1/j for
+/// j in [a, b) is C/D.
+fn gamma_split(n2: u64, a: u64, b: u64) -> GammaSplit {
+    if b == a + 1 {
+        let p = BigInt::from_u64(n2);
+        return GammaSplit {
+            q: BigInt::from_u128(a as u128 * a as u128),
+            d: BigInt::from_u64(a),
+            c: BigInt::one(),
+            t: p.clone(),
+            v: p.clone(),
+            p,
+        };
+    }
+    let m = (a + b) / 2;
+    let l = gamma_split(n2, a, m);
+    let r = gamma_split(n2, m, b);
+    let t = &l.t * &r.q + &l.p * &r.t;
+    let v = &l.v * &r.q * &r.d + &l.p * &r.v * &l.d + &l.p * &l.c * &r.d * &r.t;
+    GammaSplit {
+        c: &l.c * &r.d + &r.c * &l.d,
+        p: l.p * r.p,
+        q: l.q * r.q,
+        d: l.d * r.d,
+        t,
+        v,
+    }
+}
+
+/// Returns the integer square root of val * 2^(2k), shifted left by one bit,
+/// with a sticky bit in the lowest bit that is set if the root is inexact.
+fn scaled_isqrt(val: u64, k: usize) -> (BigInt, BigInt) {
+    let mut n = BigInt::from_u64(val);
+    n.shift_left(2 * k);
+    let (mut root, rem) = n.isqrt();
+    root.shift_left(1);
+    let sticky = if rem.is_zero() {
+        BigInt::zero()
+    } else {
+        BigInt::one()
+    };
+    (root, sticky)
+}
+
+impl Float {
+    /// Computes pi.
+    pub fn pi(sem: Semantics) -> Self {
+        // Use the Chudnovsky algorithm, and evaluate the series with binary
+        // splitting. Each term adds about 47 bits.
+        use RoundingMode::None as rm;
+        let orig_sem = sem;
+        let sem = sem.grow_log(8);
+        let terms = sem.get_precision() as u64 / 47 + 2;
+
+        let (_, q, r) = chudnovsky_split(1, terms);
+        let den = BigSInt::from(q.clone()) * 13591409 + r;
+        let num = q * BigInt::from_u64(426880);
+        let ratio =
+            Self::from_fraction(sem, false, &num, &den.get_magnitude(), rm);
+        let c = Self::from_u64(sem, 10005).sqrt();
+        Self::mul_with_rm(&ratio, &c, rm).cast(orig_sem)
     }
 
     /// Computes e.
     pub fn e(sem: Semantics) -> Self {
+        use RoundingMode::None as rm;
         let orig_sem = sem;
-        let sem = sem.increase_precision(1);
-
-        let one = Self::one(sem, false);
-        let mut term = one.clone();
+        let sem = sem.grow_log(8);
 
-        // Use Euler's continued fraction, which is a simple series.
-        let iterations: i64 = (sem.get_exponent_len() * 2) as i64;
-        for i in (1..iterations).rev() {
-            let v = Self::from_i64(sem, i);
-            term = &v + &v / &term;
+        // Sum the series 1/k! with binary splitting, until the terms drop
+        // below the precision.
+        let mut terms: u64 = 1;
+        let mut bits = 0;
+        while bits <= sem.get_precision() {
+            terms += 1;
+            bits += terms.ilog2() as usize;
         }
 
-        (one / term + 2).cast(orig_sem)
+        let (p, q) = factorial_split(0, terms);
+        Self::from_fraction(sem, false, &(p + &q), &q, rm).cast(orig_sem)
     }
 
     /// Compute log(2).
     pub fn ln2(sem: Semantics) -> Self {
+        Self::round_with_ziv(sem, 0, |sem| atanh_combination(sem, &LN2))
+    }
+
+    /// Compute log(10).
+    pub fn ln10(sem: Semantics) -> Self {
+        // ln(10) = 3*ln(2) + ln(10/8), and ln(10/8) = 2*atanh(1/9).
+        let mut terms = LN2.map(|(c, x)| (3 * c, x)).to_vec();
+        terms.push((2, 9));
+        Self::round_with_ziv(sem, 0, |sem| atanh_combination(sem, &terms))
+    }
+
+    /// Computes sqrt(2).
+    pub fn sqrt2(sem: Semantics) -> Self {
+        // sqrt(2) = root / 2^k, where root is the integer square root of
+        // 2^(2k+1).
+        let k = sem.get_precision() + 2;
+        let (root, sticky) = scaled_isqrt(2, k);
+        let den = BigInt::one_hot(k + 1);
+        let rm = sem.get_rounding_mode();
+        Self::from_fraction(sem, false, &(root + sticky), &den, rm)
+    }
+
+    /// Computes the golden ratio phi, which is (1 + sqrt(5)) / 2.
+    pub fn phi(sem: Semantics) -> Self {
+        let k = sem.get_precision() + 2;
+        let (root, sticky) = scaled_isqrt(5, k);
+        let one = BigInt::one_hot(k + 1);
+        let den = BigInt::one_hot(k + 2);
+        let rm = sem.get_rounding_mode();
+        Self::from_fraction(sem, false, &(root + one + sticky), &den, rm)
+    }
+
+    /// Computes Catalan's constant G.
+    pub fn catalan(sem: Semantics) -> Self {
+        Self::round_with_ziv(sem, 0, Self::catalan_approx)
+    }
+
+    /// Computes Catalan's constant with an error below 2^(1-w), where w is
+    /// the precision of `sem`.
+    fn catalan_approx(sem: Semantics) -> (Self, i64) {
         use RoundingMode::None as rm;
-        let sem2 = sem.increase_precision(8);
-
-        // Represent log(2) using the sum 1/k*2^k
-        let one = Self::one(sem2, false);
-        let mut sum = Self::zero(sem2, false);
-        let mut prev = Self::inf(sem2, true);
-        for k in 1..500 {
-            let k2 = Self::from_u64(sem2, 1).scale(k, rm);
-            let k = Self::from_u64(sem2, k as u64);
-            let kk2 = &Float::mul_with_rm(&k, &k2, rm);
-            let term = Float::div_with_rm(&one, kk2, rm);
-            sum = Float::add_with_rm(&sum, &term, rm);
-            if prev == sum {
-                break;
+        // Use the series by Lupas, that adds 2 bits per term:
+        // G = 1/64 * sum (-1)^(n-1) * 2^(8n) * (40n^2 - 24n + 3) * (2n)!^3 *
+        //     n!^2 / (n^3 * (2n - 1) * (4n)!^2)
+        // The terms alternate and shrink, and the n-th term is below
+        // 2^(3-2n), so the terms that are left out add up to less than 2^-w.
+        let terms = sem.get_precision() as u64 / 2 + 2;
+        let (_, q, t) = hypergeometric_split(1, terms, &|n| {
+            let p = if n == 1 {
+                BigSInt::from(32)
+            } else {
+                let p = BigInt::from_u64(n - 1).powi(3) * (64 * n - 96);
+                BigSInt::new(true, p)
+            };
+            let q = (BigInt::from_u64(4 * n - 1) * (4 * n - 3)).powi(2);
+            (p, q, BigInt::from_u64(n) * (40 * n - 24) + 3)
+        });
+        let den = q * BigInt::from_u64(64);
+        let sign = t.is_negative();
+        let g = Self::from_fraction(sem, sign, &t.get_magnitude(), &den, rm);
+        // G < 1, so the rounding error is below 2^-w.
+        (g, 1 - sem.get_precision() as i64)
+    }
+
+    /// Computes Apery's constant zeta(3).
+    pub fn zeta3(sem: Semantics) -> Self {
+        Self::round_with_ziv(sem, 0, Self::zeta3_approx)
+    }
+
+    /// Computes Apery's constant with an error below 2^(2-w), where w is the
+    /// precision of `sem`.
+    fn zeta3_approx(sem: Semantics) -> (Self, i64) {
+        use RoundingMode::None as rm;
+        // Use the series by Amdeberhan and Zeilberger, that adds 10 bits per
+        // term: zeta(3) = 1/64 * sum (-1)^k * k!^10 * (205k^2 + 250k + 77) /
+        //     (2k + 1)!^5
+        // The terms alternate and shrink, and the k-th term is below
+        // 2^(1-10k), so the terms that are left out add up to less than 2^-w.
+        let terms = sem.get_precision() as u64 / 10 + 2;
+        let (_, q, t) = hypergeometric_split(0, terms, &|n| {
+            if n == 0 {
+                let one = BigInt::one();
+                return (BigSInt::from(1), one.clone(), one * 77);
             }
-            prev = sum.clone();
-        }
-        sum.cast(sem)
+            let p = BigSInt::new(true, BigInt::from_u64(n).powi(5));
+            let q = BigInt::from_u64(2 * n + 1).powi(5) * 32;
+            (p, q, BigInt::from_u64(n) * (205 * n + 250) + 77)
+        });
+        let den = q * BigInt::from_u64(64);
+        let sign = t.is_negative();
+        let z = Self::from_fraction(sem, sign, &t.get_magnitude(), &den, rm);
+        // zeta(3) < 2, so the rounding error is below 2^(1-w).
+        (z, 2 - sem.get_precision() as i64)
+    }
+
+    /// Computes the Euler-Mascheroni constant gamma.
+    pub fn euler_gamma(sem: Semantics) -> Self {
+        Self::round_with_ziv(sem, 0, Self::euler_gamma_approx)
+    }
+
+    /// Computes the Euler-Mascheroni constant, and returns it with `e`, where
+    /// 2^e bounds the error.
+    fn euler_gamma_approx(sem: Semantics) -> (Self, i64) {
+        use RoundingMode::None as rm;
+        // Use the Brent-McMillan algorithm, with n = 2^m:
+        // gamma = A / B - ln(n), where B = sum (n^k / k!)^2 and
+        // A = sum (n^k / k!)^2 * H(k), and H(k) is the k-th harmonic number.
+        // The error of the algorithm is below pi*e^(-4n), and the terms after
+        // the first 4n terms add up to about e^(-5n). Both are below 2^-w,
+        // because e^(-4n) < 2^(-5.7n) and n > w/5.
+        let w = sem.get_precision() as i64;
+        let m = (w as u64 / 5 + 2).next_power_of_two().ilog2();
+        let n: u64 = 1 << m;
+        let split = gamma_split(n * n, 1, 4 * n);
+        let den = split.d * (split.q + split.t);
+        let ratio = Self::from_fraction(sem, false, &split.v, &den, rm);
+        let ln_n = LN2.map(|(c, x)| (c * m as i64, x));
+        let (ln_n, ln_err) = atanh_combination(sem, &ln_n);
+        let res = Self::sub_with_rm(&ratio, &ln_n, rm);
+        // Add the errors of the algorithm, of ln(n), of the ratio and of the
+        // subtraction, where the result is below 1.
+        let ratio_err = ratio.get_exp() + 1 - w;
+        (res, ln_err.max(ratio_err).max(-w) + 2)
     }
 }
 
@@ -104,3 +353,102 @@ fn test_ln2() {
     use crate::FP64;
     assert_eq!(Float::ln2(FP64).as_f64(), std::f64::consts::LN_2);
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_constants_published_digits() {
+    let sem = Semantics::new(32, 600, RoundingMode::NearestTiesToEven);
+    let parse = |digits: &str| Float::try_from_str(digits, sem).unwrap();
+
+    let pi = "3.14159265358979323846264338327950288419716939937510582097494459\
+              230781640628620899862803482534211706798214808651328230664709384\
+              460955058223172535940812848111745028410270193852110555964462294\
+              8954930381964";
+    let e = "2.718281828459045235360287471352662497757247093699959574966967627\
+             724076630353547594571382178525166427427466391932003059921817413\
+             596629043572900334295260595630738132328627943490763233829880753\
+             1952510190115";
+    let ln2 =
+        "0.69314718055994530941723212145817656807550013436025525412068000\
+        949339362196969471560586332699641868754200148102057068573368552\
+        023575813055703267075163507596193072757082837143519030703862389\
+        1673471123350";
+    assert_eq!(Float::pi(sem), parse(pi));
+    assert_eq!(Float::e(sem), parse(e));
+    assert_eq!(Float::ln2(sem), parse(ln2));
+
+    // The constants are consistent across precisions.
+    let small = Semantics::new(32, 200, RoundingMode::NearestTiesToEven);
+    assert_eq!(Float::pi(sem).cast(small), Float::pi(small));
+    assert_eq!(Float::e(sem).cast(small), Float::e(small));
+    assert_eq!(Float::ln2(sem).cast(small), Float::ln2(small));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_more_constants() {
+    use crate::FP64;
+    assert_eq!(Float::sqrt2(FP64).as_f64(), std::f64::consts::SQRT_2);
+    assert_eq!(Float::ln10(FP64).as_f64(), std::f64::consts::LN_10);
+    assert_eq!(Float::euler_gamma(FP64).as_f64(), 0.5772156649015329);
+    assert_eq!(Float::phi(FP64).as_f64(), 1.618033988749895);
+
+    let sem = Semantics::new(32, 400, RoundingMode::NearestTiesToEven);
+    let parse = |digits: &str| Float::try_from_str(digits, sem).unwrap();
+
+    let gamma = "0.5772156649015328606065120900824024310421593359399235988057\
+                 672348848677267776646709369470632917467495146314472498070824\
+                 809605040144865428362241739976449235362535003337429373377376";
+    let catalan = "0.915965594177219015054603514932384110774149374281672134266\
+                   498119621763019776254769479356512926115106248574422619196\
+                   199579035898803325859059431594737481158406995332028773319";
+    let ln10 = "2.30258509299404568401799145468436420760110148862877297603332\
+                790096757260967735248023599720508959829834196778404228624863\
+                340952546508280675666628736909878168948290720832555468084379";
+    let sqrt2 = "1.4142135623730950488016887242096980785696718753769480731766\
+                 797379907324784621070388503875343276415727350138462309122970\
+                 249248360558507372126441214970999358314132226659275055927557";
+    let phi = "1.618033988749894848204586834365638117720309179805762862135448\
+               622705260462818902449707207204189391137484754088075386891752\
+               126633862223536931793180060766726354433389086595939582905638";
+    let zeta3 = "1.2020569031595942853997381615114499907649862923404988817922\
+                 715553418382057863130901864558736093352581461991577952607194\
+                 184919959986732832137763968372079001614539417829493600667191";
+    assert_eq!(Float::euler_gamma(sem), parse(gamma));
+    assert_eq!(Float::catalan(sem), parse(catalan));
+    assert_eq!(Float::ln10(sem), parse(ln10));
+    assert_eq!(Float::sqrt2(sem), parse(sqrt2));
+    assert_eq!(Float::phi(sem), parse(phi));
+    assert_eq!(Float::zeta3(sem), parse(zeta3));
+
+    // The results are rounded correctly in every rounding mode.
+    let constants: [fn(Semantics) -> Float; 7] = [
+        Float::ln2,
+        Float::ln10,
+        Float::sqrt2,
+        Float::phi,
+        Float::catalan,
+        Float::zeta3,
+        Float::euler_gamma,
+    ];
+    let wide = Semantics::new(32, 600, RoundingMode::NearestTiesToEven);
+    for rm in [RoundingMode::Zero, RoundingMode::Positive] {
+        let sem = sem.with_rm(rm);
+        for f in constants {
+            assert_eq!(f(sem), f(wide).cast_with_rm(sem, rm));
+        }
+    }
+
+    // Truncating and rounding up give neighboring numbers in any precision.
+    for p in 2..100 {
+        use RoundingMode::None as rm;
+        let down = Semantics::new(16, p, RoundingMode::Zero);
+        for f in constants {
+            let lo = f(down);
+            let ulp = lo.get_exp() + 1 - p as i64;
+            let ulp = Float::one(down, false).scale(ulp, rm);
+            let next = Float::add_with_rm(&lo, &ulp, rm);
+            assert_eq!(f(down.with_rm(RoundingMode::Positive)), next);
+        }
+    }
+}
@@ -1,9 +1,9 @@
 //! This module contains the implementation of log- and exp-related methods.
 //!
 
-use crate::RoundingMode;
+use crate::{ConstantCache, RoundingMode};
 
-use crate::float::Float;
+use crate::float::{Float, Semantics};
 
 impl Float {
     /// Computes the taylor series, centered around 1, and valid in [0..2].
@@ -21,7 +21,8 @@ impl Float {
         let mut top = z;
         let mut sum = Self::zero(sem, false);
         let mut prev = Self::one(sem, true);
-        for i in 0..50 {
+        // z is tiny after the range reduction, so each term adds many bits.
+        for i in 0..sem.get_precision() as u64 {
             if prev == sum {
                 break; // Stop if we are not making progress.
             }
@@ -29,13 +30,14 @@ impl Float {
 
             let bottom = &Self::from_u64(sem, i * 2 + 1);
             let elem = Float::div_with_rm(&top, bottom, rm);
-            sum = Float::add_with_rm(&sum, &elem, rm);
+            sum.add_assign_with_rm(&elem, rm);
 
             // Prepare the next iteration.
             top = Float::mul_with_rm(&top, &z2, rm);
         }
 
-        sum.scale(1, RoundingMode::Zero)
+        sum.scale_in_place(1, RoundingMode::Zero);
+        sum
     }
 
     /// Reduce the range of 'x' with the identity:
@@ -81,6 +83,48 @@ impl Float {
     }
 }
 
+impl Float {
+    /// Computes log(1 + y) without losing the low bits of small values of 'y'.
+    /// This is the taylor series of `log_taylor`, with:
+    /// z = (x - 1)/(x + 1) = y / (2 + y)
+    /// log(1 + y) = 2 (z + z^3/3 + z^5/5 + z^7/7 ... )
+    pub(crate) fn log1p_normal(y: &Self) -> Self {
+        use RoundingMode::None as rm;
+        let sem = y.get_semantics();
+        let one = Self::one(sem, false);
+
+        // There is no cancellation when 'y' is large, and the series converges
+        // slowly, so just use the regular log.
+        if y.abs() > one.scale(-1, rm) {
+            return Float::add_with_rm(&one, y, rm).log();
+        }
+
+        let two = Self::from_u64(sem, 2);
+        let z = Float::div_with_rm(y, &Float::add_with_rm(&two, y, rm), rm);
+        let z2 = z.sqr();
+
+        let mut top = z;
+        let mut sum = Self::zero(sem, false);
+        let mut prev = Self::one(sem, true);
+        for i in 0..sem.get_precision() as u64 {
+            if prev == sum {
+                break; // Stop if we are not making progress.
+            }
+            prev = sum.clone();
+
+            let bottom = &Self::from_u64(sem, i * 2 + 1);
+            let elem = Float::div_with_rm(&top, bottom, rm);
+            sum.add_assign_with_rm(&elem, rm);
+
+            // Prepare the next iteration.
+            top = Float::mul_with_rm(&top, &z2, rm);
+        }
+
+        sum.scale_in_place(1, rm);
+        sum
+    }
+}
+
 #[test]
 fn test_log() {
     use crate::FP128;
@@ -108,7 +152,8 @@ impl Float {
 
         let mut sum = Self::zero(sem, false);
         let mut prev = Self::one(sem, true);
-        for k in 1..50 {
+        // The terms shrink at least by half for x <= 1.
+        for k in 1..sem.get_precision() as u64 + 2 {
             if prev == sum {
                 break; // Stop if we are not making progress.
             }
@@ -119,7 +164,7 @@ impl Float {
 
             // Prepare the next iteration.
             bottom *= BigInt::from_u64(k);
-            top = &top * x;
+            top *= x;
         }
 
         sum
@@ -219,3 +264,442 @@ pub fn test_sigmoid() {
         assert_eq!(o.as_f32(), res.as_f32())
     }
 }
+
+impl Float {
+    /// Returns the integer k if this number is exactly 2^k.
+    fn as_power_of_two(&self) -> Option<i64> {
+        if !self.is_normal() || self.is_negative() {
+            return None;
+        }
+        let m = self.get_mantissa();
+        let zeros = m.trailing_zeros();
+        if m.msb_index() != zeros + 1 {
+            return None;
+        }
+        // The value is m * 2^(exp - M), and m is 2^zeros.
+        Some(zeros as i64 + self.get_exp() - self.get_mantissa_len() as i64)
+    }
+
+    /// Handles the special values of the logarithm functions, following
+    /// IEEE754-2019 Section 9.2.1. Returns None for positive normals.
+    fn log_special_values(&self) -> Option<Self> {
+        let sem = self.get_semantics();
+        if self.is_nan() {
+            Some(self.clone())
+        } else if self.is_zero() {
+            Some(Self::inf(sem, true))
+        } else if self.is_negative() {
+            Some(Self::nan(sem, true))
+        } else if self.is_inf() {
+            Some(self.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Computes the base-2 logarithm of the number. The result is exact for
+    /// powers of two.
+    pub fn log2(&self) -> Self {
+        self.log2_with_ln2(Self::cached_ln2)
+    }
+
+    /// Computes the base-2 logarithm of the number, and takes the value of
+    /// log(2) from `cache`.
+    pub fn log2_with_cache(&self, cache: &mut ConstantCache) -> Self {
+        self.log2_with_ln2(|sem| cache.ln2(sem))
+    }
+
+    /// Computes the base-2 logarithm, where `get_ln2` returns log(2).
+    fn log2_with_ln2<F>(&self, get_ln2: F) -> Self
+    where
+        F: FnOnce(Semantics) -> Self,
+    {
+        use RoundingMode::None as rm;
+        if let Some(res) = self.log_special_values() {
+            return res;
+        }
+
+        let orig_sem = self.get_semantics();
+        if let Some(k) = self.as_power_of_two() {
+            return Self::from_i64(orig_sem, k);
+        }
+
+        let sem = orig_sem.grow_log(10).increase_exponent(10);
+        let ln = self.cast_with_rm(sem, rm).log();
+        Float::div_with_rm(&ln, &get_ln2(sem), rm).cast(orig_sem)
+    }
+
+    /// Computes the base-10 logarithm of the number.
+    pub fn log10(&self) -> Self {
+        self.log10_with_ln10(Self::cached_ln10)
+    }
+
+    /// Computes the base-10 logarithm of the number, and takes the value of
+    /// log(10) from `cache`.
+    pub fn log10_with_cache(&self, cache: &mut ConstantCache) -> Self {
+        self.log10_with_ln10(|sem| cache.ln10(sem))
+    }
+
+    /// Computes the base-10 logarithm, where `get_ln10` returns log(10).
+    fn log10_with_ln10<F>(&self, get_ln10: F) -> Self
+    where
+        F: FnOnce(Semantics) -> Self,
+    {
+        use RoundingMode::None as rm;
+        if let Some(res) = self.log_special_values() {
+            return res;
+        }
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(10).increase_exponent(10);
+        let ln = self.cast_with_rm(sem, rm).log();
+        Float::div_with_rm(&ln, &get_ln10(sem), rm).cast(orig_sem)
+    }
+
+    /// Computes log(1 + x). The result is accurate when x is near zero.
+    pub fn log1p(&self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = self.get_semantics();
+
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+
+        let sem = orig_sem.grow_log(10).increase_exponent(10);
+        let x = self.cast_with_rm(sem, rm);
+        let minus_one = Self::one(sem, true);
+        if x == minus_one {
+            return Self::inf(orig_sem, true);
+        } else if x < minus_one {
+            return Self::nan(orig_sem, true);
+        } else if self.is_inf() {
+            return self.clone();
+        }
+
+        Self::log1p_normal(&x).cast(orig_sem)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_log2_log10_log1p() {
+    use crate::utils;
+    use crate::utils::assert_close;
+    use crate::FP128;
+
+    // Powers of two are exact.
+    for i in -1022..1024 {
+        let x = Float::from_f64(2.0f64.powi(i));
+        assert_eq!(x.log2().as_f64(), i as f64);
+    }
+    for i in 0..52 {
+        let x = Float::from_f64(f64::from_bits(1 << i));
+        assert_eq!(x.log2().as_f64(), (i - 1074) as f64);
+    }
+    let x = Float::one(FP128, false).scale(-16000, RoundingMode::None);
+    assert_eq!(x.log2().as_f64(), -16000.);
+
+    for i in 1..200 {
+        let x = (i as f64) * 1.37;
+        let v = Float::from_f64(x);
+        assert_close(v.log2().as_f64(), x.log2(), 1);
+        assert_close(v.log10().as_f64(), x.log10(), 1);
+        assert_close(v.log1p().as_f64(), x.ln_1p(), 1);
+    }
+
+    for i in -60..60 {
+        let x = 10.0f64.powi(i);
+        assert_eq!(Float::from_f64(x).log10().as_f64(), i as f64);
+    }
+
+    // log1p keeps the bits of the small numbers.
+    for i in 1..100 {
+        let x = 0.9f64.powi(i * 7);
+        for x in [x, -x] {
+            assert_close(Float::from_f64(x).log1p().as_f64(), x.ln_1p(), 1);
+        }
+    }
+
+    // Test the special values.
+    for v in utils::get_special_test_values() {
+        let f = Float::from_f64(v);
+        for (r0, r1) in [
+            (v.log2(), f.log2()),
+            (v.log10(), f.log10()),
+            (v.ln_1p(), f.log1p()),
+        ] {
+            let r1 = r1.as_f64();
+            assert_eq!(r0.is_nan(), r1.is_nan());
+            if !v.is_normal() && !r0.is_nan() {
+                assert_eq!(r0.to_bits(), r1.to_bits());
+            }
+        }
+    }
+}
+
+impl Float {
+    /// Computes the taylor series:
+    /// exp(x) - 1 = x/1! + x^2/2! + x^3/3! ...
+    fn expm1_taylor(x: &Self) -> Self {
+        let sem = x.get_semantics();
+        use crate::bigint::BigInt;
+        let mut top = x.clone();
+        let mut bottom = BigInt::one();
+
+        let mut sum = Self::zero(sem, false);
+        let mut prev = Self::one(sem, true);
+        for k in 2..sem.get_precision() as u64 + 2 {
+            if prev == sum {
+                break; // Stop if we are not making progress.
+            }
+            prev = sum.clone();
+
+            sum += &top / &Self::from_bigint(sem, bottom.clone());
+
+            // Prepare the next iteration.
+            bottom *= BigInt::from_u64(k);
+            top *= x;
+        }
+
+        sum
+    }
+
+    /// Computes `e^self - 1`. The result is accurate when x is near zero.
+    pub fn expm1(&self) -> Self {
+        use RoundingMode::None as rm;
+        let orig_sem = self.get_semantics();
+
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        } else if self.is_inf() {
+            if self.is_negative() {
+                return Self::one(orig_sem, true);
+            }
+            return self.clone();
+        }
+
+        let sem = orig_sem.grow_log(10).increase_exponent(10);
+        let x = self.cast_with_rm(sem, rm);
+        let one = Self::one(sem, false);
+
+        // Use the taylor series near zero, where e^x - 1 cancels.
+        if x.abs() < one.scale(-1, rm) {
+            return Self::expm1_taylor(&x).cast(orig_sem);
+        }
+
+        Float::sub_with_rm(&x.exp(), &one, rm).cast(orig_sem)
+    }
+
+    /// Handles the special values of the exponent functions, and the numbers
+    /// that are so large that the result must overflow or underflow. Returns
+    /// None otherwise.
+    fn exp_special_values(&self) -> Option<Self> {
+        use RoundingMode::None as rm;
+        let sem = self.get_semantics();
+        if self.is_nan() {
+            Some(self.clone())
+        } else if self.is_zero() {
+            Some(Self::one(sem, false))
+        } else if self.is_inf() {
+            if self.is_negative() {
+                return Some(Self::zero(sem, false));
+            }
+            Some(self.clone())
+        } else if self.get_exp() > 40 {
+            // This is beyond the range of any exponent. Let the rounding
+            // decide if the result is Inf/Zero or the largest/smallest number.
+            let scale = if self.is_negative() {
+                -1 << 40
+            } else {
+                1 << 40
+            };
+            Some(Self::one(sem, false).scale(scale, rm))
+        } else {
+            None
+        }
+    }
+
+    /// Computes `2^self`. The result is exact for integers.
+    pub fn exp2(&self) -> Self {
+        self.exp2_with_ln2(Self::cached_ln2)
+    }
+
+    /// Computes `2^self`, and takes the value of log(2) from `cache`.
+    pub fn exp2_with_cache(&self, cache: &mut ConstantCache) -> Self {
+        self.exp2_with_ln2(|sem| cache.ln2(sem))
+    }
+
+    /// Computes `2^self`, where `get_ln2` returns log(2).
+    fn exp2_with_ln2<F>(&self, get_ln2: F) -> Self
+    where
+        F: FnOnce(Semantics) -> Self,
+    {
+        use RoundingMode::None as rm;
+        if let Some(res) = self.exp_special_values() {
+            return res;
+        }
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(10).increase_exponent(10);
+
+        // Split the number to n + f, and compute 2^n * 2^f, where 2^n is just
+        // a change of the exponent.
+        let n = self.trunc();
+        let scale = n.to_i64();
+        if n == *self {
+            let rm_orig = orig_sem.get_rounding_mode();
+            return Self::one(orig_sem, false).scale(scale, rm_orig);
+        }
+
+        let f = Float::sub_with_rm(&self.cast(sem), &n.cast(sem), rm);
+        let ef = Float::mul_with_rm(&f, &get_ln2(sem), rm).exp();
+        ef.scale(scale, rm).cast(orig_sem)
+    }
+
+    /// Computes `10^self`. The result is rounded once for integers.
+    pub fn exp10(&self) -> Self {
+        self.exp10_with_ln10(Self::cached_ln10)
+    }
+
+    /// Computes `10^self`, and takes the value of log(10) from `cache`.
+    pub fn exp10_with_cache(&self, cache: &mut ConstantCache) -> Self {
+        self.exp10_with_ln10(|sem| cache.ln10(sem))
+    }
+
+    /// Computes `10^self`, where `get_ln10` returns log(10).
+    fn exp10_with_ln10<F>(&self, get_ln10: F) -> Self
+    where
+        F: FnOnce(Semantics) -> Self,
+    {
+        use crate::bigint::BigInt;
+        use RoundingMode::None as rm;
+        if let Some(res) = self.exp_special_values() {
+            return res;
+        }
+
+        let orig_sem = self.get_semantics();
+        let rm_orig = orig_sem.get_rounding_mode();
+
+        // Compute the integer powers with big integers. 10^n is larger than
+        // 2^3n, so we know when the result overflows or underflows, without
+        // computing huge numbers.
+        if self.trunc() == *self {
+            let n = self.to_i64();
+            let bounds = self.get_exp_bounds();
+            let max_exp = bounds.1 + 2;
+            let min_exp = bounds.0 - orig_sem.get_precision() as i64 - 2;
+            if n > max_exp / 3 || n < min_exp / 3 {
+                return Self::one(orig_sem, false).scale(n * 3, rm_orig);
+            }
+
+            let ten = BigInt::from_u64(10).powi(n.unsigned_abs());
+            let one = BigInt::one();
+            if n < 0 {
+                return Self::from_fraction(
+                    orig_sem, false, &one, &ten, rm_orig,
+                );
+            }
+            return Self::from_fraction(orig_sem, false, &ten, &one, rm_orig);
+        }
+
+        // The error of x * log(10) is magnified by the size of the exponent,
+        // so add the bits of the exponent to the precision.
+        let sem = orig_sem
+            .grow_log(10)
+            .increase_precision(orig_sem.get_exponent_len())
+            .increase_exponent(10);
+        let x = Float::mul_with_rm(&self.cast(sem), &get_ln10(sem), rm);
+        x.exp().cast(orig_sem)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_expm1_exp2_exp10() {
+    use crate::std::format;
+    use crate::utils;
+    use crate::utils::assert_close;
+    use crate::FP128;
+
+    // Integers are exact.
+    for i in -1022..1024 {
+        let x = Float::from_i64(crate::FP64, i);
+        assert_eq!(x.exp2().as_f64(), 2.0f64.powi(i as i32));
+    }
+    for i in 0..52 {
+        let x = Float::from_i64(crate::FP64, i - 1074);
+        assert_eq!(x.exp2().as_f64(), f64::from_bits(1 << i));
+    }
+    for i in -22..22 {
+        let x = Float::from_i64(crate::FP64, i);
+        assert_eq!(x.exp10().as_f64(), format!("1e{}", i).parse().unwrap());
+    }
+    // Check the rounding of large powers against the parser.
+    for i in [-323, -320, -310, -308, -300, -100, 23, 50, 100, 200, 308] {
+        let x = Float::from_i64(crate::FP64, i);
+        assert_eq!(x.exp10().as_f64(), format!("1e{}", i).parse().unwrap());
+    }
+    assert!(Float::from_i64(FP128, 5000).exp10().is_inf());
+    assert!(Float::from_i64(FP128, 5000)
+        .exp10()
+        .cast(crate::FP64)
+        .is_inf());
+    assert!(Float::from_i64(crate::FP64, 309).exp10().is_inf());
+    assert!(Float::from_i64(crate::FP64, -324).exp10().is_zero());
+
+    // Overflow and underflow follow the rounding mode.
+    let zero = crate::FP64.with_rm(RoundingMode::Zero);
+    let up = crate::FP64.with_rm(RoundingMode::Positive);
+    let exp10 = |v: f64, sem| Float::from_f64(v).cast(sem).exp10().as_f64();
+    let exp2 = |v: f64, sem| Float::from_f64(v).cast(sem).exp2().as_f64();
+    assert_eq!(exp10(400., zero), f64::MAX);
+    assert_eq!(exp2(1100., zero), f64::MAX);
+    assert_eq!(exp10(400.5, zero), f64::MAX);
+    assert_eq!(exp2(1100.5, zero), f64::MAX);
+    assert_eq!(exp10(400., up), f64::INFINITY);
+    assert_eq!(exp10(-400., up), 5e-324);
+    assert_eq!(exp2(-1100., up), 5e-324);
+    assert_eq!(exp10(-400., zero), 0.);
+    assert_eq!(exp2(-1100.5, up), 5e-324);
+
+    for i in -200..200 {
+        let x = (i as f64) * 0.137;
+        let v = Float::from_f64(x);
+        assert_close(v.expm1().as_f64(), x.exp_m1(), 1);
+        assert_close(v.exp2().as_f64(), x.exp2(), 1);
+    }
+
+    // expm1 keeps the bits of the small numbers.
+    for i in 1..100 {
+        let x = 0.9f64.powi(i * 7);
+        for x in [x, -x] {
+            assert_close(Float::from_f64(x).expm1().as_f64(), x.exp_m1(), 1);
+        }
+    }
+
+    // Test the special values.
+    for v in utils::get_special_test_values() {
+        let f = Float::from_f64(v);
+        for (r0, r1) in [(v.exp_m1(), f.expm1()), (v.exp2(), f.exp2())] {
+            let r1 = r1.as_f64();
+            assert_eq!(r0.is_nan(), r1.is_nan());
+            if !v.is_normal() && !r0.is_nan() {
+                assert_eq!(r0.to_bits(), r1.to_bits());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_exp10_known_value() {
+    // Verify the results with:
+    // from mpmath import mp
+    // mp.dps = 100
+    // mp.power(10, 0.3)
+    let x = Float::from_f64(0.3).exp10();
+    assert_eq!(x.as_f64(), 1.9952623149688795);
+    let x = Float::from_f64(-7.5).exp10();
+    assert_eq!(x.as_f64(), 3.162277660168379e-8);
+    let x = Float::from_f64(307.25).exp10();
+    assert_eq!(x.as_f64(), 1.7782794100389228e307);
+}
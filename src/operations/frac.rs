@@ -1,20 +1,53 @@
 //! This module contains the implementation of methods that compute continued
 //! fraction.
 
-use crate::{bigint::BigInt, Float};
+use crate::rational::{ContinuedFraction, Convergents};
+use crate::{bigint::BigInt, BigSInt, Float, Rational};
 
 impl Float {
     /// Convert the number to a Continued Fraction of two integers.
     /// The fraction is computed using 'n' iterations of the form:
     /// a0 + 1/(a1 + 1/(a2 + 1/( ... ))).
-    /// This method discards the sign, and returns (0, 0) for Inf and NaN.
-    pub fn as_fraction(&self, n: usize) -> (BigInt, BigInt) {
+    /// The numerator carries the sign of the number. This method returns
+    /// (0, 0) for Inf and NaN.
+    pub fn as_fraction(&self, n: usize) -> (BigSInt, BigInt) {
         if self.is_zero() {
-            return (BigInt::zero(), BigInt::one()); // Zero.
+            return (BigSInt::zero(), BigInt::one()); // Zero.
         } else if self.is_inf() || self.is_nan() {
-            return (BigInt::zero(), BigInt::zero()); // Invalid.
+            return (BigSInt::zero(), BigInt::zero()); // Invalid.
         }
+        let (p, q) = self.abs().as_positive_fraction(n);
+        (BigSInt::new(self.get_sign(), p), q)
+    }
+
+    /// Returns the fraction p/q that's closest to the number, among the
+    /// fractions with q <= `max_denominator`. The fraction is found with the
+    /// semiconvergents of the exact continued fraction of the number. This
+    /// method returns None for Inf and NaN.
+    pub fn best_rational(&self, max_denominator: &BigInt) -> Option<Rational> {
+        let val = self.to_rational()?;
+        Some(val.best_approximation(max_denominator))
+    }
+
+    /// Returns an iterator over the coefficients of the continued fraction of
+    /// the number. The sequence is finite, because every float is a rational
+    /// number. The sequence is empty for Inf and NaN.
+    pub fn continued_fraction(&self) -> ContinuedFraction {
+        match self.to_rational() {
+            Some(val) => val.continued_fraction(),
+            None => ContinuedFraction::empty(),
+        }
+    }
+
+    /// Returns an iterator over the convergents of the continued fraction of
+    /// the number. The convergents approach the number, so callers can stop
+    /// when the error is small enough. The sequence is empty for Inf and NaN.
+    pub fn convergents(&self) -> Convergents {
+        Convergents::new(self.continued_fraction())
+    }
 
+    /// Implements 'as_fraction' for positive normal numbers.
+    fn as_positive_fraction(&self, n: usize) -> (BigInt, BigInt) {
         // Algorithm from:
         // Elementary Functions: Algorithms and Implementation
         // 9.3.1 A few basic notions on continued fractions - Page 180.
@@ -58,11 +91,13 @@ fn test_frac() {
 
     // Verified with https://oeis.org/A001203.
     let (p, q) = x.as_fraction(1);
-    assert_eq!((3, 1), (p.as_u64(), q.as_u64()));
+    assert_eq!((3, 1), (p.as_i64(), q.as_u64()));
     let (p, q) = x.as_fraction(2);
-    assert_eq!((22, 7), (p.as_u64(), q.as_u64()));
+    assert_eq!((22, 7), (p.as_i64(), q.as_u64()));
     let (p, q) = x.as_fraction(3);
-    assert_eq!((333, 106), (p.as_u64(), q.as_u64()));
+    assert_eq!((333, 106), (p.as_i64(), q.as_u64()));
     let (p, q) = x.as_fraction(4);
-    assert_eq!((355, 113), (p.as_u64(), q.as_u64()));
+    assert_eq!((355, 113), (p.as_i64(), q.as_u64()));
+    let (p, q) = x.neg().as_fraction(4);
+    assert_eq!((-355, 113), (p.as_i64(), q.as_u64()));
 }
@@ -2,7 +2,7 @@
 
 use crate::RoundingMode;
 
-use crate::float::Float;
+use crate::float::{Float, Status};
 
 impl Float {
     /// Return this number raised to the power of 'n'.
@@ -32,14 +32,53 @@ impl Float {
     }
     /// Calculates the square root of the number.
     pub fn sqrt(&self) -> Self {
+        if let Some((res, _)) = self.sqrt_special_values() {
+            return res;
+        }
+        self.sqrt_normal()
+    }
+
+    /// Calculates the square root of the number, and returns the exceptions
+    /// that the operation raised.
+    pub fn sqrt_with_status(&self) -> (Self, Status) {
+        if let Some(res) = self.sqrt_special_values() {
+            return res;
+        }
         let sem = self.get_semantics();
-        if self.is_zero() {
-            return self.clone(); // (+/-) zero
-        } else if self.is_nan() || self.is_negative() {
-            return Self::nan(sem, self.get_sign()); // (-/+)Nan, -Number.
+        let root = self.sqrt_normal();
+
+        // The result is exact if the square of the root is the number. The
+        // square is computed exactly in a format with double the precision.
+        let wide = sem
+            .increase_precision(sem.get_precision() + 2)
+            .increase_exponent(1);
+        let r = root.cast(wide);
+        let square = Self::mul_with_rm(&r, &r, RoundingMode::NearestTiesToEven);
+        if square == self.cast(wide) {
+            return (root, Status::OK);
+        }
+        (root, Status::INEXACT)
+    }
+
+    /// Returns the square root of zero, NaN, infinity and the negative
+    /// numbers, or None for the other numbers.
+    fn sqrt_special_values(&self) -> Option<(Self, Status)> {
+        // (+/-) zero and (-/+)Nan.
+        if self.is_zero() || self.is_nan() {
+            Some((self.clone(), Status::OK))
+        } else if self.is_negative() {
+            let nan = Self::nan(self.get_semantics(), self.get_sign());
+            Some((nan, Status::INVALID_OP)) // -Number, -Inf.
         } else if self.is_inf() {
-            return self.clone(); // Inf+.
+            Some((self.clone(), Status::OK)) // Inf+.
+        } else {
+            None
         }
+    }
+
+    /// Calculates the square root of a positive normal number.
+    fn sqrt_normal(&self) -> Self {
+        let sem = self.get_semantics();
 
         let target = self.clone();
         let two = Self::from_u64(sem, 2);
@@ -151,6 +190,35 @@ fn test_sqrt() {
     check(5.0120298432056786e-8, 0.0002238756316173263);
 }
 
+#[test]
+fn test_sqrt_rem_status() {
+    use crate::FP64;
+    let sqrt_status = |v: f64| Float::from_f64(v).sqrt_with_status().1;
+    assert_eq!(sqrt_status(2.0), Status::INEXACT);
+    assert_eq!(sqrt_status(0.1), Status::INEXACT);
+    assert_eq!(sqrt_status(-1.0), Status::INVALID_OP);
+    assert_eq!(sqrt_status(f64::NEG_INFINITY), Status::INVALID_OP);
+    for v in [0.0, -0.0, 0.25, 16.0, 1e300 * 1e300, f64::NAN] {
+        assert!(sqrt_status(v).is_ok());
+    }
+    for i in 1..64 {
+        let x = Float::from_u64(FP64, i * i);
+        assert!(x.sqrt_with_status().1.is_ok());
+        let x = Float::from_u64(FP64, i * i + 1);
+        assert_eq!(x.sqrt_with_status().1, Status::INEXACT);
+    }
+
+    let rem_status = |a: f64, b: f64| {
+        Float::from_f64(a).rem_with_status(&Float::from_f64(b))
+    };
+    assert_eq!(rem_status(1.0, 0.0).1, Status::INVALID_OP);
+    assert_eq!(rem_status(f64::INFINITY, 2.0).1, Status::INVALID_OP);
+    assert!(rem_status(f64::NAN, 0.0).1.is_ok());
+    let (r, status) = rem_status(7.5, 2.0);
+    assert!(status.is_ok());
+    assert_eq!(r.as_f64(), 1.5);
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_min_max() {
@@ -207,32 +275,49 @@ impl Float {
     /// Similar to 'scalbln'. Adds or subtracts to the exponent of the number,
     /// and scaling it by 2^exp.
     pub fn scale(&self, scale: i64, rm: RoundingMode) -> Self {
+        let mut r = self.clone();
+        r.scale_in_place(scale, rm);
+        r
+    }
+
+    /// Multiplies the number by 2^scale in place, and rounds the result
+    /// with the rounding mode `rm`.
+    pub fn scale_in_place(&mut self, scale: i64, rm: RoundingMode) {
         use crate::bigint::LossFraction;
         if !self.is_normal() {
-            return self.clone();
+            return;
         }
 
-        let mut r = Self::new(
-            self.get_semantics(),
-            self.get_sign(),
-            self.get_exp() + scale,
-            self.get_mantissa(),
-        );
-        r.normalize(rm, LossFraction::ExactlyZero);
-        r
+        let sem = self.get_semantics();
+        let (sign, exp) = (self.get_sign(), self.get_exp());
+        *self = Self::new(sem, sign, exp + scale, self.take_mantissa());
+        self.normalize(rm, LossFraction::ExactlyZero);
     }
 
     /// Returns the remainder from a division of two floats. This is equivalent
     /// to rust 'rem' or c 'fmod'.
     pub fn rem(&self, rhs: &Self) -> Self {
+        self.rem_with_status(rhs).0
+    }
+
+    /// Returns the remainder from a division of two floats, and the exceptions
+    /// that the operation raised. The remainder is always exact.
+    pub fn rem_with_status(&self, rhs: &Self) -> (Self, Status) {
         use core::ops::Sub;
         // Handle NaNs.
-        if self.is_nan() || rhs.is_nan() || self.is_inf() || rhs.is_zero() {
-            return Self::nan(self.get_semantics(), self.get_sign());
+        if self.is_nan() || rhs.is_nan() {
+            return (
+                Self::nan(self.get_semantics(), self.get_sign()),
+                Status::OK,
+            );
+        }
+        if self.is_inf() || rhs.is_zero() {
+            let nan = Self::nan(self.get_semantics(), self.get_sign());
+            return (nan, Status::INVALID_OP);
         }
         // Handle values that are obviously zero or self.
         if self.is_zero() || rhs.is_inf() {
-            return self.clone();
+            return (self.clone(), Status::OK);
         }
 
         // Operate on integers.
@@ -262,7 +347,7 @@ impl Float {
 
         // Set the original sign.
         lhs.set_sign(self.get_sign());
-        lhs
+        (lhs, Status::OK)
     }
 }
 
@@ -0,0 +1,346 @@
+//! This module contains the implementation of hyperbolic functions, and their
+//! inverses.
+//!
+use crate::float::Float;
+use crate::RoundingMode;
+
+impl Float {
+    /// sinh(x) = x + x^3 / 3! + x^5 / 5! + x^7 / 7! ....
+    fn sinh_taylor(x: &Self) -> Self {
+        use crate::bigint::BigInt;
+        let sem = x.get_semantics();
+
+        let mut top = x.clone();
+        let mut bottom = BigInt::one();
+        let mut sum = Self::zero(sem, false);
+        let x2 = x.sqr();
+        let mut prev = Self::one(sem, true);
+        for i in 1..sem.get_precision() as u64 {
+            if prev == sum {
+                break; // Stop if we are not making progress.
+            }
+            prev = sum.clone();
+            // Update sum.
+            sum += &top / &Self::from_bigint(sem, bottom.clone());
+
+            // Prepare the next element.
+            top = &top * &x2;
+            let next_term = BigInt::from_u64((i * 2) * (i * 2 + 1));
+            bottom *= next_term;
+        }
+
+        sum
+    }
+
+    /// Computes the hyperbolic sine of the number.
+    pub fn sinh(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        if self.is_zero() || self.is_nan() || self.is_inf() {
+            return self.clone();
+        }
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let one = Self::one(sem, false);
+        let val = self.cast_with_rm(sem, rm);
+
+        // Use the taylor series near zero, where e^x - e^-x cancels.
+        if val.abs() < one {
+            return Self::sinh_taylor(&val).cast(orig_sem);
+        }
+
+        // sinh(x) = (e^x - e^-x) / 2.
+        let ex = val.exp();
+        let inv_ex = Float::div_with_rm(&one, &ex, rm);
+        let res = Float::sub_with_rm(&ex, &inv_ex, rm).scale(-1, rm);
+        res.cast(orig_sem)
+    }
+
+    /// Computes the hyperbolic cosine of the number.
+    pub fn cosh(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        let orig_sem = self.get_semantics();
+        if self.is_nan() {
+            return self.clone();
+        } else if self.is_inf() {
+            return Self::inf(orig_sem, false);
+        } else if self.is_zero() {
+            return Self::one(orig_sem, false);
+        }
+
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let one = Self::one(sem, false);
+
+        // cosh(x) = (e^x + e^-x) / 2.
+        let ex = self.cast_with_rm(sem, rm).exp();
+        let inv_ex = Float::div_with_rm(&one, &ex, rm);
+        let res = Float::add_with_rm(&ex, &inv_ex, rm).scale(-1, rm);
+        res.cast(orig_sem)
+    }
+
+    /// Computes the hyperbolic tangent of the number.
+    pub fn tanh(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        let orig_sem = self.get_semantics();
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        } else if self.is_inf() {
+            return Self::one(orig_sem, self.get_sign());
+        }
+
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let one = Self::one(sem, false);
+        let mut neg = false;
+        let mut val = self.cast_with_rm(sem, rm);
+
+        // Handle the negatives.
+        if val.is_negative() {
+            val = val.neg();
+            neg ^= true;
+        }
+
+        let res = if val < one {
+            // Avoid the cancellation near zero.
+            let sinh = Self::sinh_taylor(&val);
+            let cosh = (&one + &sinh.sqr()).sqrt();
+            Float::div_with_rm(&sinh, &cosh, rm)
+        } else if val > Self::from_u64(sem, orig_sem.get_precision() as u64) {
+            // 1 - tanh(x) is about 2e^-2x, which is below the precision.
+            one
+        } else {
+            // tanh(x) = 2 * sigmoid(2x) - 1.
+            let sig = val.scale(1, rm).sigmoid().scale(1, rm);
+            Float::sub_with_rm(&sig, &one, rm)
+        };
+
+        let res = if neg { res.neg() } else { res };
+        res.cast(orig_sem)
+    }
+}
+
+impl Float {
+    /// Computes the inverse hyperbolic sine of the number.
+    pub fn asinh(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        if self.is_zero() || self.is_nan() || self.is_inf() {
+            return self.clone();
+        }
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let one = Self::one(sem, false);
+        let mut neg = false;
+        let mut val = self.cast_with_rm(sem, rm);
+
+        // Handle the negatives: asinh(-x) = -asinh(x).
+        if val.is_negative() {
+            val = val.neg();
+            neg ^= true;
+        }
+
+        // asinh(x) = log(x + sqrt(x^2 + 1)). Write the argument as 1 + y,
+        // where y = x + x^2 / (1 + sqrt(x^2 + 1)), to avoid the cancellation
+        // near zero.
+        let x2 = val.sqr();
+        let root = Float::add_with_rm(&x2, &one, rm).sqrt();
+        let y =
+            Float::div_with_rm(&x2, &Float::add_with_rm(&one, &root, rm), rm);
+        let y = Float::add_with_rm(&val, &y, rm);
+        let res = Self::log1p_normal(&y);
+
+        let res = if neg { res.neg() } else { res };
+        res.cast(orig_sem)
+    }
+
+    /// Computes the inverse hyperbolic cosine of the number.
+    pub fn acosh(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let one = Self::one(sem, false);
+
+        if self.is_nan() {
+            return self.clone();
+        }
+
+        // The function is only defined in the range 1..inf.
+        if self.is_negative() || self.is_zero() || self.cast(sem) < one {
+            return Self::nan(orig_sem, self.get_sign());
+        }
+
+        if self.is_inf() {
+            return self.clone();
+        }
+
+        // acosh(x) = log(x + sqrt(x^2 - 1)). Write the argument as 1 + y,
+        // where t = x - 1, and y = t + sqrt(t * (t + 2)), to avoid the
+        // cancellation near one.
+        let t = Float::sub_with_rm(&self.cast_with_rm(sem, rm), &one, rm);
+        let two = Self::from_u64(sem, 2);
+        let root = (&t * &Float::add_with_rm(&t, &two, rm)).sqrt();
+        let y = Float::add_with_rm(&t, &root, rm);
+        if y.is_zero() {
+            return Self::zero(orig_sem, false);
+        }
+        Self::log1p_normal(&y).cast(orig_sem)
+    }
+
+    /// Computes the inverse hyperbolic tangent of the number.
+    pub fn atanh(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let one = Self::one(sem, false);
+        let mut neg = false;
+        let mut val = self.cast_with_rm(sem, rm);
+
+        // Handle the negatives: atanh(-x) = -atanh(x).
+        if val.is_negative() {
+            val = val.neg();
+            neg ^= true;
+        }
+
+        // The function is only defined in the range -1..1, and the poles
+        // are at +-1.
+        if self.is_inf() || val > one {
+            return Self::nan(orig_sem, self.get_sign());
+        } else if val == one {
+            return Self::inf(orig_sem, neg);
+        }
+
+        // atanh(x) = log((1 + x) / (1 - x)) / 2 = log(1 + 2x / (1 - x)) / 2.
+        // The subtraction 1 - x is exact near one.
+        let bottom = Float::sub_with_rm(&one, &val, rm);
+        let y = Float::div_with_rm(&val.scale(1, rm), &bottom, rm);
+        let res = Self::log1p_normal(&y).scale(-1, rm);
+
+        let res = if neg { res.neg() } else { res };
+        res.cast(orig_sem)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hyperbolic() {
+    use crate::utils;
+    use crate::utils::assert_close;
+
+    for i in -300..300 {
+        let f0 = (i as f64) / 37.;
+        let v = Float::from_f64(f0);
+        assert_close(f0.sinh(), v.sinh().as_f64(), 2);
+        assert_close(f0.cosh(), v.cosh().as_f64(), 2);
+        assert_close(f0.tanh(), v.tanh().as_f64(), 2);
+    }
+
+    for x in [1e-300, 1e-20, 3e-9, 0.001, 25.3, 700., 711., 1e300] {
+        for f0 in [x, -x] {
+            let v = Float::from_f64(f0);
+            assert_close(f0.sinh(), v.sinh().as_f64(), 2);
+            assert_close(f0.cosh(), v.cosh().as_f64(), 2);
+            assert_close(f0.tanh(), v.tanh().as_f64(), 2);
+        }
+    }
+
+    // Test the special values. These are exact.
+    for v in utils::get_special_test_values() {
+        if v.is_normal() {
+            continue;
+        }
+        let f = Float::from_f64(v);
+        for (r0, r1) in [
+            (v.sinh(), f.sinh()),
+            (v.cosh(), f.cosh()),
+            (v.tanh(), f.tanh()),
+        ] {
+            assert_eq!(r0.is_nan(), r1.is_nan());
+            assert!(r0.is_nan() || r0.to_bits() == r1.as_f64().to_bits());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_inverse_hyperbolic() {
+    use crate::utils;
+    use crate::utils::assert_close;
+
+    for i in -300..300 {
+        let f0 = (i as f64) / 37.;
+        let v = Float::from_f64(f0);
+        assert_close(f0.asinh(), v.asinh().as_f64(), 2);
+        // The native acosh cancels near one.
+        if !(1. ..2.).contains(&f0) {
+            assert_close(f0.acosh(), v.acosh().as_f64(), 2);
+        }
+        // The native atanh is a few ulps off, so check that tanh inverts the
+        // result instead.
+        let f1 = (i as f64) / 400.;
+        let r1 = Float::from_f64(f1).atanh().tanh().as_f64();
+        assert_close(f1, r1, 1);
+    }
+
+    for x in [1e-300, 1e-20, 3e-9, 0.001, 25.3, 700., 1e300] {
+        for f0 in [x, -x] {
+            let v = Float::from_f64(f0);
+            assert_close(f0.asinh(), v.asinh().as_f64(), 2);
+            assert_close(f0.acosh(), v.acosh().as_f64(), 2);
+        }
+    }
+
+    // Test the special values.
+    for v in utils::get_special_test_values() {
+        let f = Float::from_f64(v);
+        for (r0, r1) in [
+            (v.asinh(), f.asinh()),
+            (v.acosh(), f.acosh()),
+            (v.atanh(), f.atanh()),
+        ] {
+            assert_eq!(r0.is_nan(), r1.is_nan());
+            if !v.is_normal() {
+                assert!(r0.is_nan() || r0.to_bits() == r1.as_f64().to_bits());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_hyperbolic_known_value() {
+    use crate::FP128;
+    use RoundingMode::None as rm;
+
+    // Check the values near the poles and near one, where the naive formulas
+    // cancel. Verify the results with:
+    // from mpmath import mp
+    // mp.dps = 100
+    // mp.atanh(1 - mpf(2)**-100)
+    let one = Float::one(FP128, false);
+    let x = &one - &one.scale(-100, rm);
+    assert_eq!(x.atanh().as_f64(), 35.00393261827724);
+    // mp.acosh(1 + mpf(2)**-100)
+    let x = &one + &one.scale(-100, rm);
+    assert_eq!(x.acosh().as_f64(), 1.2560739669470201e-15);
+    // mp.atanh(-299/300.)
+    let x = Float::from_f64(-299. / 300.);
+    assert_eq!(x.atanh().as_f64(), -3.19763079905773);
+    // mp.atanh(1 - 2**-40)
+    let x = Float::from_f64(1. - 2.0f64.powi(-40));
+    assert_eq!(x.atanh().as_f64(), 14.20951720147865);
+
+    // The functions are the identity near zero.
+    let x = one.scale(-80, rm);
+    assert_eq!(x.sinh(), x);
+    assert_eq!(x.asinh(), x);
+    assert_eq!(x.tanh(), x);
+    assert_eq!(x.atanh(), x);
+}
@@ -4,8 +4,13 @@
 #[cfg(feature = "std")]
 extern crate std;
 
+mod cache;
 mod constants;
 mod exp;
 mod frac;
 mod functions;
+mod hyperbolic;
 mod trig;
+mod ziv;
+
+pub use self::cache::ConstantCache;
@@ -1,7 +1,7 @@
 //! This module contains the implementation of trigonometric functions.
 //!
-use crate::float::Float;
-use crate::RoundingMode;
+use crate::float::{Float, Semantics};
+use crate::{ConstantCache, RoundingMode};
 
 impl Float {
     /// sin(x) = x - x^3 / 3! + x^5 / 5! - x^7/7! ....
@@ -15,17 +15,20 @@ impl Float {
         let mut sum = Self::zero(sem, false);
         let x2 = x.sqr();
         let mut prev = Self::one(sem, true);
-        for i in 1..50 {
+        for i in 1..sem.get_precision() as u64 {
             if prev == sum {
                 break; // Stop if we are not making progress.
             }
             prev = sum.clone();
             // Update sum.
-            let elem = &top / &Self::from_bigint(sem, bottom.clone());
-            sum = if neg { sum - elem } else { sum + elem };
+            let mut elem = &top / &Self::from_bigint(sem, bottom.clone());
+            if neg {
+                elem.neg_in_place();
+            }
+            sum += elem;
 
             // Prepare the next element.
-            top = &top * &x2;
+            top *= &x2;
             let next_term = BigInt::from_u64((i * 2) * (i * 2 + 1));
             bottom *= next_term;
             neg ^= true;
@@ -50,6 +53,20 @@ impl Float {
 
     /// Computes the sine of the number (in radians).
     pub fn sin(&self) -> Self {
+        self.sin_with_pi(Self::cached_pi)
+    }
+
+    /// Computes the sine of the number (in radians), and takes the value
+    /// of pi from `cache`.
+    pub fn sin_with_cache(&self, cache: &mut ConstantCache) -> Self {
+        self.sin_with_pi(|sem| cache.pi(sem))
+    }
+
+    /// Computes the sine of the number, where `get_pi` returns pi.
+    fn sin_with_pi<F>(&self, mut get_pi: F) -> Self
+    where
+        F: FnMut(Semantics) -> Self,
+    {
         use RoundingMode::None as rm;
         // Fast Trigonometric functions for Arbitrary Precision number
         // by Henrik Vestermark.
@@ -73,7 +90,7 @@ impl Float {
 
         // Handle the negatives.
         if val.is_negative() {
-            val = val.neg();
+            val.neg_in_place();
             neg ^= true;
         }
 
@@ -81,7 +98,7 @@ impl Float {
         let is_small = self.get_exp() < 0;
 
         if !is_small {
-            let pi = Self::pi(sem);
+            let pi = get_pi(sem);
             let pi2 = pi.scale(1, rm);
             let pi_half = pi.scale(-1, rm);
 
@@ -108,8 +125,10 @@ impl Float {
         // Calculate the number of needed reduction: 8[2/3 * log(2) * log(p)];
         let k = orig_sem.log_precision() * 4;
 
-        let res = Self::sin_step4_reduction(&val, k);
-        let res = if neg { res.neg() } else { res };
+        let mut res = Self::sin_step4_reduction(&val, k);
+        if neg {
+            res.neg_in_place();
+        }
         res.cast(orig_sem)
     }
 }
@@ -123,11 +142,11 @@ fn test_sin_known_value() {
     // mp.dps = 1000
     // mp.sin(801./10000)
     let res = Float::from_f64(801. / 10000.).sin().to_string();
-    assert_eq!(res, ".08001437374006335");
+    assert_eq!(res, "0.08001437374006336");
     let res = Float::from_f64(90210. / 10000.).sin().to_string();
-    assert_eq!(res, ".3928952872542333");
+    assert_eq!(res, "0.39289528725423334");
     let res = Float::from_f64(95051.).sin().to_string();
-    assert_eq!(res, "-.8559198239971502");
+    assert_eq!(res, "-0.8559198239971503");
 }
 
 #[cfg(feature = "std")]
@@ -175,18 +194,21 @@ impl Float {
         let mut sum = Self::zero(sem, false);
         let x2 = x.sqr();
         let mut prev = Self::one(sem, true);
-        for i in 1..50 {
+        for i in 1..sem.get_precision() as u64 {
             if prev == sum {
                 break; // Stop if we are not making progress.
             }
             prev = sum.clone();
 
             // Update sum.
-            let elem = &top / &Self::from_bigint(sem, bottom.clone());
-            sum = if neg { sum - elem } else { sum + elem };
+            let mut elem = &top / &Self::from_bigint(sem, bottom.clone());
+            if neg {
+                elem.neg_in_place();
+            }
+            sum += elem;
 
             // Prepare the next element.
-            top = &top * &x2;
+            top *= &x2;
             let next_term = BigInt::from_u64((i * 2 - 1) * (i * 2));
             bottom *= next_term;
 
@@ -212,6 +234,20 @@ impl Float {
 
     /// Computes the cosine of the number (in radians).
     pub fn cos(&self) -> Self {
+        self.cos_with_pi(Self::cached_pi)
+    }
+
+    /// Computes the cosine of the number (in radians), and takes the value
+    /// of pi from `cache`.
+    pub fn cos_with_cache(&self, cache: &mut ConstantCache) -> Self {
+        self.cos_with_pi(|sem| cache.pi(sem))
+    }
+
+    /// Computes the cosine of the number, where `get_pi` returns pi.
+    fn cos_with_pi<F>(&self, mut get_pi: F) -> Self
+    where
+        F: FnMut(Semantics) -> Self,
+    {
         use RoundingMode::None as rm;
         // Fast Trigonometric functions for Arbitrary Precision number
         // by Henrik Vestermark.
@@ -239,14 +275,14 @@ impl Float {
 
         // Handle the negatives.
         if val.is_negative() {
-            val = val.neg();
+            val.neg_in_place();
         }
 
         // Range reductions.
         let is_small = self.get_exp() < 0; // X < 1.
 
         if !is_small {
-            let pi = Self::pi(sem);
+            let pi = get_pi(sem);
             let pi2 = pi.scale(1, rm);
             let pi_half = pi.scale(-1, rm);
 
@@ -273,8 +309,10 @@ impl Float {
         // Calculate the number of needed reduction: 2[log(2) * log(p)];
         let k = (sem.log_precision() * 8) / 10;
 
-        let res = Self::cos_step4_reduction(&val, k);
-        let res = if neg { res.neg() } else { res };
+        let mut res = Self::cos_step4_reduction(&val, k);
+        if neg {
+            res.neg_in_place();
+        }
         res.cast(orig_sem)
     }
 }
@@ -289,13 +327,13 @@ fn test_cos_known_value() {
     // mp.dps = 100
     // mp.cos(801./10000)
     let res = Float::from_f64(801. / 10000.).cos().to_string();
-    assert_eq!(res, ".9967937098492272");
+    assert_eq!(res, "0.9967937098492272");
     let res = Float::from_f64(2.3).cos().to_string();
-    assert_eq!(res, "-.6662760212798241");
+    assert_eq!(res, "-0.6662760212798241");
     let res = Float::from_f64(90210. / 10000.).cos().to_string();
-    assert_eq!(res, "-.9195832171442742");
+    assert_eq!(res, "-0.9195832171442743");
     let res = Float::from_f64(95051.).cos().to_string();
-    assert_eq!(res, ".5171085523259959");
+    assert_eq!(res, "0.517108552325996");
 }
 
 #[cfg(feature = "std")]
@@ -337,6 +375,20 @@ fn test_cos() {
 impl Float {
     /// Computes the tangent of the number (in radians).
     pub fn tan(&self) -> Self {
+        self.tan_with_pi(Self::cached_pi)
+    }
+
+    /// Computes the tangent of the number (in radians), and takes the value
+    /// of pi from `cache`.
+    pub fn tan_with_cache(&self, cache: &mut ConstantCache) -> Self {
+        self.tan_with_pi(|sem| cache.pi(sem))
+    }
+
+    /// Computes the tangent of the number, where `get_pi` returns pi.
+    fn tan_with_pi<F>(&self, mut get_pi: F) -> Self
+    where
+        F: FnMut(Semantics) -> Self,
+    {
         use RoundingMode::None as rm;
         // Fast Trigonometric functions for Arbitrary Precision number
         // by Henrik Vestermark.
@@ -360,7 +412,7 @@ impl Float {
 
         // Handle the negatives.
         if val.is_negative() {
-            val = val.neg();
+            val.neg_in_place();
             neg ^= true;
         }
 
@@ -368,7 +420,7 @@ impl Float {
         let is_small = self.get_exp() < 0;
 
         if !is_small {
-            let pi = Self::pi(sem);
+            let pi = get_pi(sem);
             let half_pi = pi.scale(-1, rm);
 
             // Wrap around pi.
@@ -386,11 +438,13 @@ impl Float {
         }
 
         // Tan(x) = sin(x)/sqrt(1-sin(x)^2).
-        let sinx = val.sin();
+        let sinx = val.sin_with_pi(&mut get_pi);
         let one = Float::one(sem, false);
         let bottom = (one - sinx.sqr()).sqrt();
-        let res = sinx / bottom;
-        let res = if neg { res.neg() } else { res };
+        let mut res = sinx / bottom;
+        if neg {
+            res.neg_in_place();
+        }
         res.cast(orig_sem)
     }
 }
@@ -405,11 +459,358 @@ fn test_tan_known_value() {
     // mp.dps = 100
     // mp.tan(801./10000)
     let res = Float::from_f64(801. / 10000.).tan().to_string();
-    assert_eq!(res, ".08027174825588148");
+    assert_eq!(res, "0.08027174825588149");
     let res = Float::from_f64(2.3).tan().to_string();
     assert_eq!(res, "-1.1192136417341325");
     let res = Float::from_f64(90210. / 10000.).tan().to_string();
-    assert_eq!(res, "-.4272536513599634");
+    assert_eq!(res, "-0.42725365135996346");
     let res = Float::from_f64(95051.).tan().to_string();
     assert_eq!(res, "-1.6552033806966715");
 }
+
+impl Float {
+    /// atan(x) = x - x^3 / 3 + x^5 / 5 - x^7 / 7 ....
+    fn atan_taylor(x: &Self) -> Self {
+        let sem = x.get_semantics();
+
+        let mut neg = false;
+        let mut top = x.clone();
+        let mut sum = Self::zero(sem, false);
+        let x2 = x.sqr();
+        let mut prev = Self::one(sem, true);
+        for i in 0..sem.get_precision() as u64 {
+            if prev == sum {
+                break; // Stop if we are not making progress.
+            }
+            prev = sum.clone();
+            // Update sum.
+            let mut elem = &top / &Self::from_u64(sem, i * 2 + 1);
+            if neg {
+                elem.neg_in_place();
+            }
+            sum += elem;
+
+            // Prepare the next element.
+            top *= &x2;
+            neg ^= true;
+        }
+
+        sum
+    }
+
+    /// Reduce atan(x) toward zero, using the identity:
+    /// atan(x) = 2atan(x / (1 + sqrt(1 + x^2)))
+    fn atan_step_reduction(x: &Self, steps: usize) -> Self {
+        use RoundingMode::None as rm;
+        if steps == 0 {
+            return Self::atan_taylor(x);
+        }
+        let one = Float::one(x.get_semantics(), false);
+        let bottom = Float::add_with_rm(&one, &(&one + &x.sqr()).sqrt(), rm);
+        let half_x = Float::div_with_rm(x, &bottom, rm);
+        Float::atan_step_reduction(&half_x, steps - 1).scale(1, rm)
+    }
+
+    /// Computes the arc tangent of the number (in radians).
+    pub fn atan(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let pi_half = Self::cached_pi(sem).scale(-1, rm);
+
+        if self.is_inf() {
+            let res = if self.is_negative() {
+                pi_half.neg()
+            } else {
+                pi_half
+            };
+            return res.cast(orig_sem);
+        }
+
+        assert!(self.is_normal());
+
+        let mut neg = false;
+        let mut val = self.cast_with_rm(sem, rm);
+
+        // Handle the negatives.
+        if val.is_negative() {
+            val.neg_in_place();
+            neg ^= true;
+        }
+
+        // Reduce to 0..1, using the identity atan(x) = pi/2 - atan(1/x).
+        let one = Self::one(sem, false);
+        let is_large = val > one;
+        if is_large {
+            val = Float::div_with_rm(&one, &val, rm);
+        }
+
+        // Calculate the number of needed reductions. Each step halves the
+        // angle and makes the taylor series converge faster.
+        let k = orig_sem.log_precision() * 2;
+
+        let mut res = Self::atan_step_reduction(&val, k);
+        if is_large {
+            res = Float::sub_with_rm(&pi_half, &res, rm);
+        }
+        if neg {
+            res.neg_in_place();
+        }
+        res.cast(orig_sem)
+    }
+
+    /// Computes the arc sine of the number (in radians).
+    pub fn asin(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        }
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let one = Self::one(sem, false);
+        let val = self.cast_with_rm(sem, rm);
+
+        // The function is only defined in the range -1..1.
+        if self.is_inf() || val.abs() > one {
+            return Self::nan(orig_sem, self.get_sign());
+        }
+
+        // asin(x) = atan(x / sqrt((1 - x) * (1 + x))).
+        let bottom = (&(&one - &val) * &(&one + &val)).sqrt();
+        let res = if bottom.is_zero() {
+            // asin(+-1) = +-pi/2.
+            let pi_half = Self::cached_pi(sem).scale(-1, rm);
+            if val.is_negative() {
+                pi_half.neg()
+            } else {
+                pi_half
+            }
+        } else {
+            Float::div_with_rm(&val, &bottom, rm).atan()
+        };
+        res.cast(orig_sem)
+    }
+
+    /// Computes the arc cosine of the number (in radians).
+    pub fn acos(&self) -> Self {
+        use RoundingMode::None as rm;
+
+        if self.is_nan() {
+            return self.clone();
+        }
+
+        let orig_sem = self.get_semantics();
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let one = Self::one(sem, false);
+        let val = self.cast_with_rm(sem, rm);
+
+        // The function is only defined in the range -1..1.
+        if self.is_inf() || val.abs() > one {
+            return Self::nan(orig_sem, self.get_sign());
+        }
+
+        // acos(-1) = pi.
+        if val == one.neg() {
+            return Self::cached_pi(orig_sem);
+        }
+
+        // acos(x) = 2atan(sqrt((1 - x) / (1 + x))).
+        let ratio = Float::div_with_rm(&(&one - &val), &(&one + &val), rm);
+        let res = ratio.sqrt().atan().scale(1, rm);
+        res.cast(orig_sem)
+    }
+
+    /// Computes the arc tangent of y/x (in radians), using the signs of the
+    /// arguments to determine the quadrant of the result. `self` is y.
+    pub fn atan2(&self, x: &Self) -> Self {
+        use RoundingMode::None as rm;
+        let y = self;
+        let orig_sem = y.get_semantics();
+        let sign = y.get_sign();
+
+        // See IEEE754-2019 Section 9.2.1 Special values.
+        if y.is_nan() || x.is_nan() {
+            return Self::nan(orig_sem, sign);
+        }
+
+        let sem = orig_sem.grow_log(12).increase_exponent(4);
+        let pi = Self::cached_pi(sem);
+        let with_sign = |val: Self| {
+            let val = val.cast(orig_sem);
+            if sign {
+                val.neg()
+            } else {
+                val
+            }
+        };
+
+        if y.is_zero() {
+            // atan2(+-0, +x) = +-0, atan2(+-0, -x) = +-pi.
+            if x.is_negative() {
+                return with_sign(pi);
+            }
+            return Self::zero(orig_sem, sign);
+        }
+
+        if y.is_inf() {
+            let res = if x.is_inf() && x.is_negative() {
+                // atan2(+-inf, -inf) = +-3pi/4.
+                Float::mul_with_rm(&pi, &Self::from_u64(sem, 3), rm)
+                    .scale(-2, rm)
+            } else if x.is_inf() {
+                // atan2(+-inf, +inf) = +-pi/4.
+                pi.scale(-2, rm)
+            } else {
+                pi.scale(-1, rm)
+            };
+            return with_sign(res);
+        }
+
+        if x.is_zero() {
+            // atan2(+-y, +-0) = +-pi/2.
+            return with_sign(pi.scale(-1, rm));
+        }
+
+        if x.is_inf() {
+            // atan2(+-y, +inf) = +-0, atan2(+-y, -inf) = +-pi.
+            if x.is_negative() {
+                return with_sign(pi);
+            }
+            return Self::zero(orig_sem, sign);
+        }
+
+        assert!(y.is_normal() && x.is_normal());
+
+        // Compute the angle in the first quadrant, and mirror it into the
+        // quadrant of (x, y).
+        let ratio = Float::div_with_rm(
+            &y.cast_with_rm(sem, rm).abs(),
+            &x.cast_with_rm(sem, rm).abs(),
+            rm,
+        );
+        let mut res = ratio.atan();
+        if x.is_negative() {
+            res = Float::sub_with_rm(&pi, &res, rm);
+        }
+        with_sign(res)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_atan_known_value() {
+    use crate::std::string::ToString;
+
+    // Verify the results with:
+    // from mpmath import mp
+    // mp.dps = 100
+    // mp.atan(801./10000)
+    let res = Float::from_f64(801. / 10000.).atan().to_string();
+    assert_eq!(res, "0.07992934899201143");
+    let res = Float::from_f64(2.3).atan().to_string();
+    assert_eq!(res, "1.1606689862534056");
+    let res = Float::from_f64(95051.).atan().to_string();
+    assert_eq!(res, "1.570785806127045");
+    let res = Float::from_f64(801. / 10000.).asin().to_string();
+    assert_eq!(res, "0.08018590198193964");
+    let res = Float::from_f64(801. / 10000.).acos().to_string();
+    assert_eq!(res, "1.490610424812957");
+    let res = Float::from_f64(0.07).acos().to_string();
+    assert_eq!(res, "1.5007390337068462");
+    let res = Float::from_f64(-3.)
+        .atan2(&Float::from_f64(-7.))
+        .to_string();
+    assert_eq!(res, "-2.7367008673047097");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_atan() {
+    use crate::utils;
+
+    for i in -100..100 {
+        let f0 = i as f64;
+        let r0 = f0.atan();
+        let r1 = Float::from_f64(f0).atan().as_f64();
+        assert_eq!(r0, r1);
+    }
+
+    for i in -300..300 {
+        let f0 = (i as f64) / 100.;
+        let r0 = f0.atan();
+        let r1 = Float::from_f64(f0).atan().as_f64();
+        assert_eq!(r0, r1);
+    }
+
+    // Test non-normal values.
+    for v in utils::get_special_test_values() {
+        if v.is_normal() {
+            continue;
+        }
+        let r0 = v.atan();
+        let r1 = Float::from_f64(v).atan().as_f64();
+        assert_eq!(r0.is_nan(), r1.is_nan());
+        if !r0.is_nan() {
+            assert_eq!(r0, r1);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_asin_acos() {
+    use crate::utils;
+    use crate::utils::assert_close;
+
+    for i in -100..=100 {
+        let f0 = (i as f64) / 100.;
+        assert_close(f0.asin(), Float::from_f64(f0).asin().as_f64(), 1);
+        assert_close(f0.acos(), Float::from_f64(f0).acos().as_f64(), 1);
+    }
+
+    // Test non-normal values, and values out of the range -1..1.
+    for v in utils::get_special_test_values() {
+        assert_close(v.asin(), Float::from_f64(v).asin().as_f64(), 1);
+        assert_close(v.acos(), Float::from_f64(v).acos().as_f64(), 1);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_atan2() {
+    use crate::utils;
+    use crate::utils::assert_close;
+
+    fn check(y: f64, x: f64) {
+        let r0 = y.atan2(x);
+        let r1 = Float::from_f64(y).atan2(&Float::from_f64(x)).as_f64();
+        assert_close(r0, r1, 1);
+    }
+
+    for i in -10..10 {
+        for j in -10..10 {
+            check(i as f64, j as f64);
+            check((i as f64) / 7., (j as f64) / 3.);
+        }
+    }
+
+    // Test the special values in all of the quadrants. The results for
+    // special values are exact.
+    for v0 in utils::get_special_test_values() {
+        for v1 in utils::get_special_test_values() {
+            check(v0, v1);
+            if !v0.is_normal() && !v1.is_normal() {
+                let r0 = v0.atan2(v1);
+                let r1 = Float::from_f64(v0).atan2(&Float::from_f64(v1));
+                assert!(r0.is_nan() || r0.to_bits() == r1.as_f64().to_bits());
+            }
+        }
+    }
+}
@@ -0,0 +1,973 @@
+//! This module contains correctly rounded versions of the elementary
+//! functions. The functions are evaluated with increasing precision, until the
+//! error bound of the approximation is small enough to decide how to round the
+//! result. This is Ziv's strategy, that's also used by MPFR:
+//! "Fast evaluation of elementary mathematical functions with correctly
+//! rounded last bit", by Abraham Ziv.
+//!
+use crate::bigint::{BigInt, LossFraction};
+use crate::float::{Float, Semantics};
+use crate::RoundingMode;
+
+/// The number of extra bits that the first iteration uses.
+const ZIV_INITIAL_GUARD: usize = 32;
+
+impl Float {
+    /// Evaluates a function with increasing precision, until the result is
+    /// rounded to the same value on both ends of the error bound. `eval`
+    /// computes the approximation in the given semantics, and returns it
+    /// together with `e`, where 2^e bounds the absolute error. `loss` is the
+    /// number of bits that we expect to lose in the evaluation.
+    ///
+    /// The exact result of the function must not be on a rounding boundary,
+    /// which is a representable number or a midpoint between two numbers,
+    /// because the bound never excludes the boundary, and the loop would
+    /// never end. The callers handle the exact results before they get here.
+    /// The results of exp, log, sin, cos and tan of the other numbers are
+    /// transcendental, so they are never on a boundary. The same holds for
+    /// the irrational constants, and is believed to hold for Catalan's
+    /// constant and for gamma.
+    pub(crate) fn round_with_ziv<F>(
+        sem: Semantics,
+        loss: usize,
+        eval: F,
+    ) -> Self
+    where
+        F: Fn(Semantics) -> (Self, i64),
+    {
+        use RoundingMode::NearestTiesToEven as nte;
+        let rm = sem.get_rounding_mode();
+        let precision = sem.get_precision();
+        let mut guard = ZIV_INITIAL_GUARD;
+        let min_exp = Self::zero(sem, false).get_exp_bounds().0;
+
+        loop {
+            let w = precision + loss + guard;
+            let wsem = Semantics::new(sem.get_exponent_len() + 4, w, nte);
+            let (y, err) = eval(wsem);
+
+            // The result is beyond the range of the wide format, so it must
+            // overflow or underflow. Let the rounding mode pick the result. A
+            // zero is an underflow only if the error bound is below the
+            // smallest number.
+            if y.is_inf() || (y.is_zero() && err < min_exp - precision as i64) {
+                let scale = if y.is_inf() { 1 << 40 } else { -1 << 40 };
+                return Self::one(sem, y.get_sign()).scale(scale, rm);
+            }
+            if y.is_zero() {
+                guard *= 2;
+                continue;
+            }
+            if y.is_nan() {
+                return Self::nan(sem, y.get_sign());
+            }
+
+            // Round both ends of the interval that contains the exact result.
+            let r = Self::one(wsem, false).scale(err, nte);
+            let lo = Float::sub_with_rm(&y, &r, RoundingMode::Negative);
+            let hi = Float::add_with_rm(&y, &r, RoundingMode::Positive);
+            let a = lo.cast_with_rm(sem, rm);
+            let b = hi.cast_with_rm(sem, rm);
+            if a == b && a.get_sign() == b.get_sign() {
+                return a;
+            }
+            guard *= 2;
+        }
+    }
+
+    /// Rounds a number that is slightly above or below the representable
+    /// number `val`, by less than half of the distance to the neighbor of
+    /// `val`. The number is below `val` in magnitude if `toward_zero` is set.
+    /// This handles the results that are too close to `val` for the Ziv
+    /// loop, such as exp(x) for tiny x.
+    fn round_nudged(val: &Self, toward_zero: bool) -> Self {
+        use RoundingMode::NearestTiesToEven as nte;
+        let sem = val.get_semantics();
+        let p = sem.get_precision();
+        let wsem = Semantics::new(sem.get_exponent_len() + 4, p + 4, nte);
+
+        // Pick a number in the same gap between the rounding boundaries.
+        let v = val.cast(wsem);
+        let delta = Self::one(wsem, v.get_sign() ^ toward_zero)
+            .scale(v.get_exp() - p as i64 - 2, nte);
+        let nudged = Float::add_with_rm(&v, &delta, nte);
+        nudged.cast_with_rm(sem, sem.get_rounding_mode())
+    }
+
+    /// Returns true if |x| < 2^-(p+2), which is small enough for exp, sin,
+    /// cos and tan to be within a quarter ulp of f(0) or of x.
+    fn is_tiny(&self) -> bool {
+        let p = self.get_semantics().get_precision() as i64;
+        self.get_exp() < -p - 2
+    }
+
+    /// Returns the exponent of the number, or zero for numbers below one.
+    fn exp_above_one(&self) -> i64 {
+        if self.is_normal() {
+            self.get_exp().max(0)
+        } else {
+            0
+        }
+    }
+}
+
+impl Float {
+    /// Computes the sine of the number (in radians), correctly rounded with
+    /// the rounding mode of the semantics.
+    pub fn sin_cr(&self) -> Self {
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        } else if self.is_inf() {
+            return Self::nan(self.get_semantics(), self.get_sign());
+        }
+        if self.is_tiny() {
+            // sin(x) = x - x^3/6 + ...
+            return Self::round_nudged(self, true);
+        }
+        Self::round_with_ziv(self.get_semantics(), 0, |sem| {
+            let (r, quadrant, r_err) = self.reduce_half_pi(sem);
+            let (y, err) = Self::sin_cos_quadrant(&r, quadrant);
+            // sin and cos don't magnify the error of the argument.
+            (y, err.max(r_err) + 1)
+        })
+    }
+
+    /// Computes the cosine of the number (in radians), correctly rounded with
+    /// the rounding mode of the semantics.
+    pub fn cos_cr(&self) -> Self {
+        if self.is_nan() {
+            return self.clone();
+        } else if self.is_zero() {
+            return Self::one(self.get_semantics(), false);
+        } else if self.is_inf() {
+            return Self::nan(self.get_semantics(), self.get_sign());
+        }
+        if self.is_tiny() {
+            // cos(x) = 1 - x^2/2 + ...
+            let one = Self::one(self.get_semantics(), false);
+            return Self::round_nudged(&one, true);
+        }
+        Self::round_with_ziv(self.get_semantics(), 0, |sem| {
+            let (r, quadrant, r_err) = self.reduce_half_pi(sem);
+            let (y, err) = Self::sin_cos_quadrant(&r, quadrant + 1);
+            (y, err.max(r_err) + 1)
+        })
+    }
+
+    /// Computes the tangent of the number (in radians), correctly rounded
+    /// with the rounding mode of the semantics.
+    pub fn tan_cr(&self) -> Self {
+        if self.is_zero() || self.is_nan() {
+            return self.clone();
+        } else if self.is_inf() {
+            return Self::nan(self.get_semantics(), self.get_sign());
+        }
+        if self.is_tiny() {
+            // tan(x) = x + x^3/3 + ...
+            return Self::round_nudged(self, false);
+        }
+        Self::round_with_ziv(self.get_semantics(), 0, |sem| {
+            self.tan_approx(sem)
+        })
+    }
+
+    /// Computes the exponential function `e^self`, correctly rounded with the
+    /// rounding mode of the semantics.
+    pub fn exp_cr(&self) -> Self {
+        let sem = self.get_semantics();
+        if self.is_nan() {
+            return self.clone();
+        } else if self.is_zero() {
+            return Self::one(sem, false);
+        } else if self.is_inf() {
+            if self.is_negative() {
+                return Self::zero(sem, false);
+            }
+            return self.clone();
+        } else if self.is_tiny() {
+            // exp(x) = 1 + x + ...
+            let one = Self::one(sem, false);
+            return Self::round_nudged(&one, self.is_negative());
+        }
+        Self::round_with_ziv(sem, 0, |sem| self.exp_approx(sem))
+    }
+
+    /// Computes the natural logarithm of the number, correctly rounded with
+    /// the rounding mode of the semantics.
+    pub fn log_cr(&self) -> Self {
+        let sem = self.get_semantics();
+        if self.is_nan() {
+            return self.clone();
+        } else if self.is_zero() {
+            return Self::inf(sem, true);
+        } else if self.is_negative() {
+            return Self::nan(sem, true);
+        } else if self.is_inf() {
+            return self.clone();
+        } else if *self == Self::one(sem, false) {
+            return Self::zero(sem, false);
+        }
+        Self::round_with_ziv(sem, 0, |sem| self.log_approx(sem))
+    }
+}
+
+/// Returns the smallest e such that n <= 2^e.
+fn ceil_log2(n: u64) -> i64 {
+    n.next_power_of_two().ilog2() as i64
+}
+
+// The approximations below return the result together with `e`, where 2^e
+// bounds the absolute error. The operations truncate, so the relative error
+// of each operation is below u = 2^(1-w), where w is the precision. The
+// bounds are written as multiples of u, and they ignore the terms of order
+// u^2, which the final factor of two covers.
+impl Float {
+    /// Computes e^self in the semantics `sem`. The number must be finite,
+    /// and fit in `sem` without rounding.
+    fn exp_approx(&self, sem: Semantics) -> (Self, i64) {
+        use RoundingMode::None as rm;
+        let w = sem.get_precision() as i64;
+        let x = self.cast_with_rm(sem, rm);
+
+        // The result is beyond the exponent range of the format, so return
+        // zero or infinity and let the caller round it.
+        let (min_exp, max_exp) = x.get_exp_bounds();
+        let range = (max_exp - min_exp + w) as u64;
+        if x.get_exp() >= 64 - range.leading_zeros() as i64 {
+            if x.is_negative() {
+                return (Self::zero(sem, false), min_exp);
+            }
+            return (Self::inf(sem, false), max_exp);
+        }
+
+        // Write x = k * ln(2) + r, where |k| < 2^bk and |r| < 1/2. The
+        // product k * ln(2) is below 2^bk, and ln(2) has an error below
+        // 2^-wp, so the error of r is below 2^(bk+2-wp) = u/8, and below u
+        // after the rounding to `sem`.
+        let bk = x.get_exp().max(0) + 2;
+        let wp = w + bk + 4;
+        let psem = sem.increase_precision((wp - w) as usize);
+        let ln2 = Self::cached_ln2(psem);
+        let xp = x.cast_with_rm(psem, rm);
+        let k = Float::div_with_rm(&xp, &ln2, rm).round();
+        let kln2 = Float::mul_with_rm(&k, &ln2, rm);
+        let r = Float::sub_with_rm(&xp, &kln2, rm).cast_with_rm(sem, rm);
+
+        // Compute e^t, where t = r / 2^s, with the Taylor series. The terms
+        // are below 2^-j, and the j-th term has 2j roundings, so the error of
+        // the terms is below 6u. The partial sums are below 2, so each
+        // addition adds 2u, and the terms that are left out add up to 2u.
+        let s = sem.log_precision() as i64;
+        let t = r.scale(-s, rm);
+        let mut sum = Self::one(sem, false);
+        let mut term = sum.clone();
+        let mut n = 0;
+        for j in 1..sem.get_precision() as u64 {
+            term = Float::mul_with_rm(&term, &t, rm);
+            term = Float::div_with_rm(&term, &Self::from_u64(sem, j), rm);
+            if term.is_zero() || term.get_exp() < -w {
+                break;
+            }
+            sum = Float::add_with_rm(&sum, &term, rm);
+            n += 1;
+        }
+
+        // e^t > 1/2, so the relative error is below b = (18 + 4n)u, with one
+        // u for the squaring. Each squaring doubles the relative error b, and
+        // adds u, which keeps it below 2^(i+1) * b as long as it's below 1/4.
+        let b = ceil_log2(18 + 4 * n) + 1 - w;
+        for _ in 0..s {
+            sum = Float::mul_with_rm(&sum, &sum, rm);
+        }
+        let y = sum.scale(k.to_i64(), rm);
+        if y.is_zero() {
+            return (y, min_exp + 1 - w);
+        }
+        if s + b > -2 {
+            // The bound does not hold, so return an error that is too large
+            // to round the result.
+            return (y.clone(), y.get_exp() + 2);
+        }
+
+        // Add the error of r, and the rounding of the results that are below
+        // the normal range.
+        let err = (y.get_exp() + 1 + s + b + 2).max(min_exp + 1 - w);
+        (y, err + 1)
+    }
+
+    /// Computes the natural logarithm of the positive finite number in the
+    /// semantics `sem`. The number must fit in `sem` without rounding.
+    fn log_approx(&self, sem: Semantics) -> (Self, i64) {
+        use RoundingMode::None as rm;
+        let w = sem.get_precision() as i64;
+        let x = self.cast_with_rm(sem, rm);
+
+        // Write x = m * 2^e, where m is in [3/4, 3/2). Then
+        // log(x) = e * ln(2) + 2 * atanh(z), where z = (m - 1) / (m + 1), and
+        // |z| <= 1/5. Both m - 1 and m + 1 are exact.
+        let one = Self::one(sem, false);
+        let mut e = x.get_exp();
+        let mut m = x.scale(-e, rm);
+        if m >= Self::from_u64(sem, 3).scale(-1, rm) {
+            m = m.scale(-1, rm);
+            e += 1;
+        }
+        let num = Float::sub_with_rm(&m, &one, rm);
+        let den = Float::add_with_rm(&m, &one, rm);
+        let z = Float::div_with_rm(&num, &den, rm);
+        let z2 = Float::mul_with_rm(&z, &z, rm);
+
+        // Sum the series atanh(z) = z + z^3/3 + z^5/5 + ... The terms have
+        // the same sign, and the j-th term has a relative error below
+        // (4j + 2)u. Each addition adds u, and the terms that are left out
+        // add up to u, relative to the sum. The relative error of the sum is
+        // below (5n + 6)u.
+        let mut power = z.clone();
+        let mut sum = z.clone();
+        let mut n = 0;
+        for j in 1..sem.get_precision() as u64 {
+            power = Float::mul_with_rm(&power, &z2, rm);
+            let term =
+                Float::div_with_rm(&power, &Self::from_u64(sem, 2 * j + 1), rm);
+            if term.is_zero() || term.get_exp() < z.get_exp() - w {
+                break;
+            }
+            sum = Float::add_with_rm(&sum, &term, rm);
+            n += 1;
+        }
+        let atanh2 = sum.scale(1, rm);
+        if e == 0 {
+            let err = atanh2.get_exp() + 1 + ceil_log2(5 * n + 6) + 1 - w;
+            return (atanh2, err + 1);
+        }
+
+        // |e * ln(2)| > 0.69 and |2 * atanh(z)| < 0.41, so the result is
+        // above 2/5 of the first and 2/3 of the second. The errors of ln(2),
+        // of the product and of the sum add up to 5u of the result, and the
+        // error of the series adds (15n/2 + 9)u.
+        let ln2 = Self::cached_ln2(sem);
+        let eln2 = Float::mul_with_rm(&Self::from_i64(sem, e), &ln2, rm);
+        let y = Float::add_with_rm(&eln2, &atanh2, rm);
+        let err = y.get_exp() + 1 + ceil_log2(8 * n + 14) + 1 - w;
+        (y, err + 1)
+    }
+
+    /// Writes the number as q * pi/2 + r, where |r| < 4/5. Returns r in the
+    /// semantics `sem`, the quadrant q mod 4, and `e`, where 2^e bounds the
+    /// error of r. The number must be finite and fit in `sem`.
+    fn reduce_half_pi(&self, sem: Semantics) -> (Self, u64, i64) {
+        use RoundingMode::None as rm;
+        let w = sem.get_precision() as i64;
+
+        // Compute pi/2 with wp bits, so its error is below 2^(1-wp). The
+        // quotient q is below 2^(ex+1), and the product q * pi/2 is below
+        // 2^(ex+2), so the error of r is below 2^(ex+4-wp) = 2^(-w-4).
+        let ex = self.get_exp().max(0);
+        let wp = w + ex + 8;
+        let psem = sem.increase_precision((wp - w) as usize);
+        let half_pi = Self::cached_pi(psem).scale(-1, rm);
+        let x = self.cast_with_rm(psem, rm);
+        let q = Float::div_with_rm(&x, &half_pi, rm).round();
+        let qp = Float::mul_with_rm(&q, &half_pi, rm);
+        let r = Float::sub_with_rm(&x, &qp, rm);
+
+        // r is exact when q is zero. Add the rounding of r to `sem`.
+        let cast_err = if r.is_zero() {
+            -w - 4
+        } else {
+            r.get_exp() + 2 - w
+        };
+        let err = if q.is_zero() {
+            cast_err
+        } else {
+            cast_err.max(-w - 4) + 1
+        };
+        let q4 = q.scale(-2, rm).trunc().scale(2, rm);
+        let quadrant = Float::sub_with_rm(&q, &q4, rm).to_i64().rem_euclid(4);
+        (r.cast_with_rm(sem, rm), quadrant as u64, err)
+    }
+
+    /// Computes sin(r + q * pi/2) for |r| < 4/5, which is one of +-sin(r) and
+    /// +-cos(r).
+    fn sin_cos_quadrant(r: &Self, quadrant: u64) -> (Self, i64) {
+        let (y, err) = match quadrant % 4 {
+            0 | 2 => Self::sin_series(r),
+            _ => Self::cos_series(r),
+        };
+        if quadrant % 4 >= 2 {
+            (y.neg(), err)
+        } else {
+            (y, err)
+        }
+    }
+
+    /// Computes sin(r) for |r| < 4/5 with the Taylor series.
+    fn sin_series(r: &Self) -> (Self, i64) {
+        use RoundingMode::None as rm;
+        let sem = r.get_semantics();
+        let w = sem.get_precision() as i64;
+        if r.is_zero() {
+            return (r.clone(), -w - 4);
+        }
+        let r2 = Float::mul_with_rm(r, r, rm);
+
+        // The i-th term is below |r| * 0.64^i / (2i + 1)!, and has 3i
+        // roundings, so the error of the terms is below |r|u. The partial
+        // sums are below |r|, so each addition adds |r|u. The terms
+        // alternate and shrink, so the terms that are left out add up to less
+        // than |r|u.
+        let mut term = r.clone();
+        let mut sum = r.clone();
+        let mut n = 0;
+        for i in 1..sem.get_precision() as u64 {
+            let den = Self::from_u64(sem, 2 * i * (2 * i + 1));
+            term = Float::mul_with_rm(&term, &r2, rm);
+            term = Float::div_with_rm(&term, &den, rm).neg();
+            if term.is_zero() || term.get_exp() < r.get_exp() - w {
+                break;
+            }
+            sum = Float::add_with_rm(&sum, &term, rm);
+            n += 1;
+        }
+        let err = r.get_exp() + 1 + ceil_log2(n + 2) + 1 - w;
+        (sum, err + 1)
+    }
+
+    /// Computes cos(r) for |r| < 4/5 with the Taylor series.
+    fn cos_series(r: &Self) -> (Self, i64) {
+        use RoundingMode::None as rm;
+        let sem = r.get_semantics();
+        let w = sem.get_precision() as i64;
+        let r2 = Float::mul_with_rm(r, r, rm);
+
+        // The i-th term is below 0.64^i / (2i)!, and has 3i roundings, so the
+        // error of the terms is below 2u. The partial sums are below 1, and
+        // the terms that are left out add up to less than u.
+        let mut term = Self::one(sem, false);
+        let mut sum = term.clone();
+        let mut n = 0;
+        for i in 1..sem.get_precision() as u64 {
+            let den = Self::from_u64(sem, (2 * i - 1) * (2 * i));
+            term = Float::mul_with_rm(&term, &r2, rm);
+            term = Float::div_with_rm(&term, &den, rm).neg();
+            if term.is_zero() || term.get_exp() < -w {
+                break;
+            }
+            sum = Float::add_with_rm(&sum, &term, rm);
+            n += 1;
+        }
+        (sum, ceil_log2(n + 3) + 2 - w)
+    }
+
+    /// Computes tan(x) in the semantics `sem`, as the ratio a / b of sin and
+    /// cos. The number must be finite and fit in `sem`.
+    fn tan_approx(&self, sem: Semantics) -> (Self, i64) {
+        use RoundingMode::None as rm;
+        let w = sem.get_precision() as i64;
+        let (r, quadrant, r_err) = self.reduce_half_pi(sem);
+        let (a, a_err) = Self::sin_cos_quadrant(&r, quadrant);
+        let (b, b_err) = Self::sin_cos_quadrant(&r, quadrant + 1);
+        let (a_err, b_err) = (a_err.max(r_err) + 1, b_err.max(r_err) + 1);
+        let y = Float::div_with_rm(&a, &b, rm);
+
+        // The relative error of a and b is below 1/2, unless they are too
+        // close to zero. In that case the error is unbounded, and we return a
+        // bound that is too large to round the result.
+        if a.get_exp() <= a_err || b.get_exp() <= b_err {
+            return (y.clone(), y.get_exp() + 2);
+        }
+
+        // If a and b have the relative errors da and db, then the relative
+        // error of the ratio is below 2 * (da + db) + u.
+        let rel = (a_err - a.get_exp()).max(b_err - b.get_exp()).max(1 - w);
+        (y.clone(), y.get_exp() + 1 + rel + 3)
+    }
+
+    /// Computes |self|^n in the semantics `sem`, as e^(n * log(|x|)). The
+    /// numbers must be finite and fit in `sem`.
+    fn pow_approx(&self, n: &Self, sem: Semantics) -> (Self, i64) {
+        use RoundingMode::None as rm;
+        let w = sem.get_precision() as i64;
+        let (ln, ln_err) = self.abs().log_approx(sem);
+        let n = n.cast_with_rm(sem, rm);
+        let t = Float::mul_with_rm(&n, &ln, rm);
+        let t_err = (n.get_exp() + 1 + ln_err).max(t.get_exp() + 2 - w) + 1;
+
+        // e^(t + d) = e^t * e^d, and |e^d - 1| <= 2|d| for |d| <= 1/2.
+        let (y, y_err) = t.exp_approx(sem);
+        if t_err >= -1 {
+            return (y.clone(), y.get_exp() + 2);
+        }
+        let err = y_err.max(y.get_exp() + 1 + t_err + 1);
+        (y, err + 2)
+    }
+}
+
+impl Float {
+    /// Returns true if the number is an integer.
+    fn is_integer(&self) -> bool {
+        self.is_zero() || (self.is_normal() && self.trunc() == *self)
+    }
+
+    /// Returns true if the number is an odd integer.
+    fn is_odd_integer(&self) -> bool {
+        if !self.is_normal() || !self.is_integer() {
+            return false;
+        }
+        // The value is m * 2^(exp - M), so the lowest set bit is at:
+        let m = self.get_mantissa();
+        let lsb = self.get_exp() - self.get_mantissa_len() as i64;
+        lsb + m.trailing_zeros() as i64 == 0
+    }
+
+    /// Writes the finite non-zero number as odd * 2^s, and returns odd and s.
+    fn split_odd(&self) -> (BigInt, i64) {
+        let mut odd = self.get_mantissa();
+        let zeros = odd.trailing_zeros();
+        odd.shift_right(zeros);
+        let s = self.get_exp() - self.get_mantissa_len() as i64 + zeros as i64;
+        (odd, s)
+    }
+
+    /// Computes `self^n` for the integer n, with a single rounding. Returns
+    /// None if the exact power is not a rounding boundary and is too large to
+    /// compute, so the Ziv loop can round it.
+    fn powi_exact(&self, n: &Self) -> Option<Self> {
+        let sem = self.get_semantics();
+        let rm = sem.get_rounding_mode();
+        let sign = self.is_negative() && n.is_odd_integer();
+        let (odd, s) = self.split_odd();
+
+        // Powers of two are exact for any n.
+        if odd == BigInt::one() {
+            if s == 0 {
+                return Some(Self::one(sem, sign));
+            }
+            let limit = 1 << 40;
+            let exp = if n.get_exp() > 40 {
+                limit
+            } else {
+                s.saturating_mul(n.to_i64()).abs().min(limit)
+            };
+            let exp = if (s < 0) ^ n.is_negative() { -exp } else { exp };
+            return Some(Self::one(sem, sign).scale(exp, rm));
+        }
+
+        if n.get_exp() > 32 {
+            return None;
+        }
+        let n = n.to_i64();
+
+        // Handle the numbers that obviously overflow or underflow, where
+        // log2(|x|) is in the range [ex, ex + 1).
+        let ex = s + odd.msb_index() as i64 - 1;
+        let (low, high) = if n > 0 {
+            (n * ex, n * (ex + 1))
+        } else {
+            (n * (ex + 1), n * ex)
+        };
+        let bounds = self.get_exp_bounds();
+        if low > bounds.1 + 1 {
+            return Some(Self::one(sem, sign).scale(1 << 40, rm));
+        }
+        if high < bounds.0 - sem.get_precision() as i64 - 1 {
+            return Some(Self::one(sem, sign).scale(-1 << 40, rm));
+        }
+
+        // The odd part of the power has at least (bits - 1) * k + 1 bits,
+        // and a rounding boundary has at most precision + 2 bits, so compute
+        // the power only if it can be one. The reciprocals of odd powers are
+        // never boundaries, and we compute them only if they are small.
+        let k = n.unsigned_abs();
+        let bits = odd.msb_index() as u64;
+        let p = sem.get_precision() as u64;
+        if n > 0 && (bits - 1).saturating_mul(k) + 1 > p + 2 {
+            return None;
+        }
+        if n < 0 && bits.saturating_mul(k) > (1 << 16).max(p + 2) {
+            return None;
+        }
+
+        let odd_k = odd.powi(k);
+        if n > 0 {
+            let exp = s * n + sem.get_mantissa_len() as i64;
+            let mut res = Self::new(sem, sign, exp, odd_k);
+            res.normalize(rm, LossFraction::ExactlyZero);
+            return Some(res);
+        }
+
+        // x^-k = 2^(-s*k) / odd^k.
+        let mut num = BigInt::one();
+        let mut den = odd_k;
+        let shift = s * k as i64;
+        if shift < 0 {
+            num.shift_left(-shift as usize);
+        } else {
+            den.shift_left(shift as usize);
+        }
+        Some(Self::from_fraction(sem, sign, &num, &den, rm))
+    }
+
+    /// Computes `self^n` for the positive number `self` and the non-integer
+    /// n = m / 2^j, with a single rounding. The power is rational only if
+    /// `self` is a perfect 2^j-th power, and it's irrational otherwise, so it
+    /// is not a rounding boundary. Returns None if the power is irrational or
+    /// too large to compute.
+    fn pow_root_exact(&self, n: &Self) -> Option<Self> {
+        let sem = self.get_semantics();
+        let (_, nexp) = n.split_odd();
+        let j = nexp.unsigned_abs();
+        let (odd, s) = self.split_odd();
+
+        // The root of the odd part must be an integer, and the root of the
+        // power of two must be a power of two.
+        if j >= 63 || s % (1 << j) != 0 {
+            return None;
+        }
+        let root = if odd == BigInt::one() {
+            odd
+        } else {
+            if 1 << j > odd.msb_index() {
+                return None;
+            }
+            let (root, rem) = odd.nth_root(1 << j);
+            if !rem.is_zero() {
+                return None;
+            }
+            root
+        };
+
+        // The root has fewer bits than the number, so it's exact.
+        let exp = s / (1 << j) + sem.get_mantissa_len() as i64;
+        let mut base = Self::new(sem, false, exp, root);
+        base.normalize(RoundingMode::None, LossFraction::ExactlyZero);
+        let m = n.scale(j as i64, RoundingMode::None);
+        base.powi_exact(&m)
+    }
+
+    /// Returns true if `self^n` is so close to one that the Ziv loop can't
+    /// separate it from one. We use the bound |log(x)| <= 2|x - 1| for x in
+    /// [1/2, 2], so |n * log(x)| < 2^(e(n) + e(x - 1) + 3).
+    fn is_pow_near_one(&self, n: &Self) -> bool {
+        let sem = self.get_semantics();
+        let one = Self::one(sem, false);
+        let d = Float::sub_with_rm(&self.abs(), &one, RoundingMode::None);
+        let half = one.scale(-1, RoundingMode::None);
+        if d.abs() >= half {
+            return false;
+        }
+        let p = sem.get_precision() as i64;
+        n.get_exp() + d.get_exp() + 3 < -p - 2
+    }
+
+    /// Computes `self^n`, correctly rounded with the rounding mode of the
+    /// semantics. The special values follow IEEE754-2019 Section 9.2.1.
+    pub fn pow_cr(&self, n: &Self) -> Self {
+        let sem = self.get_semantics();
+        debug_assert_eq!(sem, n.get_semantics());
+        let one = Self::one(sem, false);
+
+        if n.is_zero() || *self == one {
+            return one;
+        } else if self.is_nan() || n.is_nan() {
+            return Self::nan(sem, self.get_sign());
+        }
+
+        let is_odd = n.is_odd_integer();
+        let sign = self.is_negative() && is_odd;
+
+        if self.is_zero() {
+            // pow(+-0, n) is +-0 or +-Inf for odd integers.
+            if n.is_negative() {
+                return Self::inf(sem, sign);
+            }
+            return Self::zero(sem, sign);
+        } else if n.is_inf() {
+            let abs = self.abs();
+            if abs == one {
+                return one;
+            } else if (abs < one) ^ n.is_negative() {
+                return Self::zero(sem, false);
+            }
+            return Self::inf(sem, false);
+        } else if self.is_inf() {
+            if n.is_negative() {
+                return Self::zero(sem, sign);
+            }
+            return Self::inf(sem, sign);
+        } else if self.is_negative() && !n.is_integer() {
+            return Self::nan(sem, true);
+        }
+
+        if n.is_integer() {
+            if let Some(res) = self.powi_exact(n) {
+                return res;
+            }
+        } else if let Some(res) = self.pow_root_exact(n) {
+            return res;
+        }
+
+        // |x|^n = e^t, where t = n * log(|x|) is tiny.
+        if self.is_pow_near_one(n) {
+            let t_negative = (self.abs() < one) ^ n.is_negative();
+            return Self::round_nudged(&Self::one(sem, sign), t_negative);
+        }
+
+        // Compute |x|^n = e^(n * log(|x|)), and set the sign of the result.
+        // The error of the log is relative to n * log(|x|), and it becomes a
+        // relative error of the result, so we lose the bits of the exponent.
+        let loss = n.exp_above_one() + self.abs().log().exp_above_one();
+        Self::round_with_ziv(sem, loss as usize, |sem| {
+            let (y, err) = self.pow_approx(n, sem);
+            if sign {
+                (y.neg(), err)
+            } else {
+                (y, err)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+const ALL_ROUNDING_MODES: [RoundingMode; 5] = [
+    RoundingMode::NearestTiesToEven,
+    RoundingMode::NearestTiesToAway,
+    RoundingMode::Zero,
+    RoundingMode::Positive,
+    RoundingMode::Negative,
+];
+
+/// Checks that `res` is the correct rounding of `exact`, which is computed in
+/// a much wider format.
+#[cfg(test)]
+fn check_rounding(res: &Float, exact: &Float) {
+    let rm = res.get_semantics().get_rounding_mode();
+    assert_eq!(*res, exact.cast_with_rm(res.get_semantics(), rm));
+}
+
+#[test]
+fn test_cr_functions() {
+    use crate::FP64;
+    // A format that is much wider than the result, to compute the reference.
+    let wide = Semantics::new(16, 200, RoundingMode::NearestTiesToEven);
+
+    for rm in ALL_ROUNDING_MODES {
+        let sem = FP64.with_rm(rm);
+        for i in -12..12 {
+            let x = Float::from_f64(i as f64 * 2.731).cast(sem);
+            let xw = x.cast(wide);
+            check_rounding(&x.sin_cr(), &xw.sin());
+            check_rounding(&x.cos_cr(), &xw.cos());
+            check_rounding(&x.tan_cr(), &xw.tan());
+            check_rounding(&x.exp_cr(), &xw.exp());
+            if !x.is_negative() && !x.is_zero() {
+                check_rounding(&x.log_cr(), &xw.log());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_cr_against_native() {
+    use crate::utils;
+
+    // Native sin, cos and exp are usually correctly rounded to nearest.
+    for i in -300..300 {
+        let x = (i as f64) / 37.;
+        let f = Float::from_f64(x);
+        utils::assert_close(f.sin_cr().as_f64(), x.sin(), 1);
+        utils::assert_close(f.cos_cr().as_f64(), x.cos(), 1);
+        utils::assert_close(f.exp_cr().as_f64(), x.exp(), 1);
+        if x > 0. {
+            utils::assert_close(f.log_cr().as_f64(), x.ln(), 1);
+        }
+    }
+
+    // Test the special values.
+    for v in utils::get_special_test_values() {
+        if v.is_normal() {
+            continue;
+        }
+        let f = Float::from_f64(v);
+        for (r0, r1) in [
+            (v.sin(), f.sin_cr()),
+            (v.cos(), f.cos_cr()),
+            (v.tan(), f.tan_cr()),
+            (v.exp(), f.exp_cr()),
+            (v.ln(), f.log_cr()),
+        ] {
+            let r1 = r1.as_f64();
+            assert_eq!(r0.is_nan(), r1.is_nan());
+            assert!(r0.is_nan() || r0.to_bits() == r1.to_bits());
+        }
+        for w in utils::get_special_test_values() {
+            let r0 = v.powf(w);
+            let r1 = f.pow_cr(&Float::from_f64(w)).as_f64();
+            assert_eq!(r0.is_nan(), r1.is_nan());
+            assert!(r0.is_nan() || r0.to_bits() == r1.to_bits());
+        }
+    }
+}
+
+#[test]
+fn test_cr_known_values() {
+    use crate::FP64;
+    // Verify the results with:
+    // from mpmath import mp
+    // mp.dps = 100
+    // mp.sin(1e22)
+    let x = Float::from_f64(1e22);
+    assert_eq!(x.sin_cr().as_f64(), -0.8522008497671888);
+    assert_eq!(x.cos_cr().as_f64(), 0.523214785395139);
+    let x = Float::from_f64(1e300);
+    assert_eq!(x.sin_cr().as_f64(), -0.8178819121159085);
+    assert_eq!(x.tan_cr().as_f64(), 1.4214488238747245);
+    // A number that is close to a multiple of pi.
+    let x = Float::from_f64(355.);
+    assert_eq!(x.sin_cr().as_f64(), -3.014435335948845e-05);
+    assert_eq!(x.tan_cr().as_f64(), 3.0144353373184265e-05);
+    // mp.exp(-745.1)
+    let x = Float::from_f64(-745.1);
+    assert_eq!(x.exp_cr().as_f64(), 5e-324);
+    let x = Float::from_f64(709.8);
+    assert!(x.exp_cr().is_inf());
+    let x = Float::from_f64(-60000.);
+    assert_eq!(x.exp_cr().as_f64(), 0.);
+    let x = Float::from_f64(1. + f64::EPSILON);
+    assert_eq!(x.log_cr().as_f64(), 2.2204460492503128e-16);
+
+    // Directed rounding of overflowing results.
+    let x = Float::from_f64(1000.).cast(FP64.with_rm(RoundingMode::Zero));
+    assert_eq!(x.exp_cr().as_f64(), f64::MAX);
+    let x = Float::from_f64(-1000.).cast(FP64.with_rm(RoundingMode::Positive));
+    assert_eq!(x.exp_cr().as_f64(), 5e-324);
+}
+
+#[test]
+fn test_pow_cr() {
+    use crate::FP64;
+    fn pow(a: f64, b: f64, rm: RoundingMode) -> f64 {
+        let a = Float::from_f64(a).cast(FP64.with_rm(rm));
+        let b = Float::from_f64(b).cast(FP64.with_rm(rm));
+        a.pow_cr(&b).as_f64()
+    }
+    use RoundingMode::NearestTiesToEven as nte;
+
+    // Exact results, including the cases where the result is a rounding
+    // boundary for the directed rounding modes.
+    for rm in ALL_ROUNDING_MODES {
+        assert_eq!(pow(3., 5., rm), 243.);
+        assert_eq!(pow(-3., 5., rm), -243.);
+        assert_eq!(pow(-3., 4., rm), 81.);
+        assert_eq!(pow(2., -3., rm), 0.125);
+        assert_eq!(pow(4., 0.5, rm), 2.);
+        assert_eq!(pow(0.25, -1.5, rm), 8.);
+        assert_eq!(pow(2., -1074., rm), 5e-324);
+        assert_eq!(pow(10., 22., rm), 1e22);
+    }
+    assert_eq!(pow(10., 23., nte), 1e23);
+    assert_eq!(pow(10., -5., nte), 1e-5);
+    assert_eq!(pow(3., 1000., nte), f64::INFINITY);
+    assert_eq!(pow(3., 1000., RoundingMode::Zero), f64::MAX);
+    assert_eq!(pow(3., -1000., nte), 0.);
+    assert_eq!(pow(-3., -1001., RoundingMode::Negative), -5e-324);
+    assert_eq!(pow(1.5, -100000.5, RoundingMode::Positive), 5e-324);
+    assert_eq!(pow(1.5, 100000.5, RoundingMode::Zero), f64::MAX);
+
+    // Verify the results with:
+    // from mpmath import mp
+    // mp.dps = 100
+    // mp.power(1.24, 1.2)
+    assert_eq!(pow(1.24, 1.2, nte), 1.2945118257398391);
+    assert_eq!(pow(0.11, -8.5, nte), 140657267.4624867);
+    assert_eq!(pow(1. + f64::EPSILON, 1e15, nte), 1.2486270715390861);
+    assert_eq!(pow(-1.5, 31., nte), -287626.58884932613);
+
+    // Exact roots, and powers of two with huge exponents.
+    for rm in ALL_ROUNDING_MODES {
+        assert_eq!(pow(16., 0.75, rm), 8.);
+        assert_eq!(pow(0.0625, -0.25, rm), 2.);
+    }
+    let sqrt2 = std::f64::consts::SQRT_2;
+    assert_eq!(pow(2., 0.5, RoundingMode::Zero), sqrt2.next_down());
+    assert_eq!(pow(2., 0.5, RoundingMode::Positive), sqrt2);
+    assert_eq!(pow(2., 1e20, RoundingMode::Zero), f64::MAX);
+    assert_eq!(pow(0.5, 1e20, RoundingMode::Positive), 5e-324);
+    assert_eq!(pow(-2., 3e15, nte), f64::INFINITY);
+
+    // Results that are too close to one for the Ziv loop.
+    let up = RoundingMode::Positive;
+    assert_eq!(pow(1. + f64::EPSILON, 1e-300, up), 1. + f64::EPSILON);
+    assert_eq!(pow(1. + f64::EPSILON, 1e-300, nte), 1.);
+    assert_eq!(pow(1. + f64::EPSILON, -1e-300, up), 1.);
+    assert_eq!(
+        pow(1. + f64::EPSILON, -1e-300, RoundingMode::Zero),
+        1. - f64::EPSILON / 2.
+    );
+}
+
+#[test]
+fn test_cr_tiny_arguments() {
+    use crate::FP64;
+    use RoundingMode::{Negative, Positive, Zero};
+    let eval = |x: f64, rm: RoundingMode, f: fn(&Float) -> Float| {
+        f(&Float::from_f64(x).cast(FP64.with_rm(rm))).as_f64()
+    };
+    let below_one = 1. - f64::EPSILON / 2.;
+    let x: f64 = 1e-300;
+    let below_x = f64::from_bits(x.to_bits() - 1);
+    let above_x = f64::from_bits(x.to_bits() + 1);
+
+    assert_eq!(eval(x, Positive, Float::exp_cr), 1. + f64::EPSILON);
+    assert_eq!(eval(x, Negative, Float::exp_cr), 1.);
+    assert_eq!(eval(-x, Negative, Float::exp_cr), below_one);
+    assert_eq!(eval(-x, Positive, Float::exp_cr), 1.);
+    assert_eq!(eval(x, Zero, Float::sin_cr), below_x);
+    assert_eq!(eval(-x, Positive, Float::sin_cr), -below_x);
+    assert_eq!(eval(x, RoundingMode::NearestTiesToEven, Float::sin_cr), x);
+    assert_eq!(eval(x, Negative, Float::cos_cr), below_one);
+    assert_eq!(eval(x, Positive, Float::cos_cr), 1.);
+    assert_eq!(eval(x, Positive, Float::tan_cr), above_x);
+    assert_eq!(eval(x, Zero, Float::tan_cr), x);
+    assert_eq!(eval(5e-324, Zero, Float::sin_cr), 0.);
+
+    // Tiny numbers in a format with a huge exponent range.
+    let sem = Semantics::new(40, 53, Positive);
+    let x = Float::one(sem, false).scale(-(1 << 30), RoundingMode::None);
+    assert_eq!(x.exp_cr().as_f64(), 1. + f64::EPSILON);
+    assert_eq!(x.sin_cr(), x);
+}
+
+#[test]
+fn test_cr_high_precision() {
+    use crate::bigint::BigInt;
+    use RoundingMode::{Negative, Positive, Zero};
+    for rm in [Zero, Positive, Negative] {
+        let sem = Semantics::new(32, 3000, rm);
+        let wide = Semantics::new(32, 6200, RoundingMode::NearestTiesToEven);
+
+        // The constants are computed with binary splitting.
+        let two = Float::from_u64(sem, 2);
+        assert_eq!(two.log_cr(), Float::ln2(sem));
+        assert_eq!(Float::from_u64(sem, 10).log_cr(), Float::ln10(sem));
+        check_rounding(&Float::one(sem, false).exp_cr(), &Float::e(wide));
+
+        // sin(x) = pi - x and tan(x) = x - pi, up to (x - pi)^3, where x is
+        // pi rounded to the format.
+        let x = Float::pi(sem);
+        let diff = Float::sub_with_rm(&Float::pi(wide), &x.cast(wide), rm);
+        check_rounding(&x.sin_cr(), &diff);
+        check_rounding(&x.tan_cr(), &diff.neg());
+
+        // An exact power with more bits than the first iteration computes.
+        let sem = Semantics::new(32, 70000, rm);
+        let three = Float::from_u64(sem, 3);
+        let exact = Float::from_bigint(sem, BigInt::from_u64(3).powi(40000));
+        assert_eq!(three.pow_cr(&Float::from_u64(sem, 40000)), exact);
+    }
+
+    // The series of exp has many terms at this precision.
+    let sem = Semantics::new(32, 20000, Positive);
+    assert_eq!(Float::one(sem, false).exp_cr(), Float::e(sem));
+}
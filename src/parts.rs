@@ -0,0 +1,192 @@
+//! This module contains the storage of the words of the big int. Small numbers,
+//! like the mantissas of FP16/FP32/FP64 and the intermediate results of their
+//! operations, are stored inline and don't allocate memory. Larger numbers
+//! spill to the heap.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+/// The number of words that are stored inline. This fits the temporary
+/// product of two 2-word mantissas (of up to FP128), that has 5 words.
+const INLINE_PARTS: usize = 5;
+
+/// A vector of words with an inline buffer of `INLINE_PARTS` words.
+pub(crate) enum Parts {
+    Inline {
+        len: usize,
+        data: [u64; INLINE_PARTS],
+    },
+    Heap(Vec<u64>),
+}
+
+impl Parts {
+    /// Create a new vector of `len` zero words.
+    pub fn zeros(len: usize) -> Self {
+        if len <= INLINE_PARTS {
+            let data = [0; INLINE_PARTS];
+            return Parts::Inline { len, data };
+        }
+        Parts::Heap(alloc::vec![0; len])
+    }
+
+    /// Create a new vector with a copy of the words in `parts`.
+    pub fn from_slice(parts: &[u64]) -> Self {
+        let mut res = Self::zeros(parts.len());
+        res.copy_from_slice(parts);
+        res
+    }
+
+    /// Move the words to the heap, and make room for `additional` words.
+    fn spill(&mut self, additional: usize) -> &mut Vec<u64> {
+        if let Parts::Inline { len, data } = self {
+            let mut vec = Vec::with_capacity(2 * INLINE_PARTS + additional);
+            vec.extend_from_slice(&data[..*len]);
+            *self = Parts::Heap(vec);
+        }
+        match self {
+            Parts::Heap(vec) => vec,
+            Parts::Inline { .. } => unreachable!(),
+        }
+    }
+
+    /// Append the word `val` to the end of the vector.
+    pub fn push(&mut self, val: u64) {
+        if let Parts::Inline { len, data } = self {
+            if *len < INLINE_PARTS {
+                data[*len] = val;
+                *len += 1;
+                return;
+            }
+        }
+        self.spill(1).push(val);
+    }
+
+    /// Remove the last word of the vector.
+    pub fn pop(&mut self) -> Option<u64> {
+        match self {
+            Parts::Inline { len: 0, .. } => None,
+            Parts::Inline { len, data } => {
+                *len -= 1;
+                Some(data[*len])
+            }
+            Parts::Heap(vec) => vec.pop(),
+        }
+    }
+
+    /// Grow or shorten the vector to `size` words. New words are zero.
+    pub fn resize(&mut self, size: usize) {
+        match self {
+            Parts::Inline { len, data } if size <= INLINE_PARTS => {
+                for word in data.iter_mut().take(size).skip(*len) {
+                    *word = 0;
+                }
+                *len = size;
+            }
+            Parts::Inline { .. } => self.spill(size).resize(size, 0),
+            Parts::Heap(vec) => vec.resize(size, 0),
+        }
+    }
+
+    /// Shorten the vector to the first `size` words.
+    pub fn truncate(&mut self, size: usize) {
+        match self {
+            Parts::Inline { len, .. } => *len = size.min(*len),
+            Parts::Heap(vec) => vec.truncate(size),
+        }
+    }
+
+    /// Returns true if the words are stored on the heap.
+    #[cfg(test)]
+    fn is_spilled(&self) -> bool {
+        matches!(self, Parts::Heap(_))
+    }
+}
+
+impl Clone for Parts {
+    fn clone(&self) -> Self {
+        match self {
+            Parts::Inline { len, data } => Parts::Inline {
+                len: *len,
+                data: *data,
+            },
+            // Numbers that shrank after they spilled are copied inline.
+            Parts::Heap(vec) if vec.len() <= INLINE_PARTS => {
+                Self::from_slice(vec)
+            }
+            Parts::Heap(vec) => Parts::Heap(vec.clone()),
+        }
+    }
+}
+
+impl Deref for Parts {
+    type Target = [u64];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Parts::Inline { len, data } => &data[..*len],
+            Parts::Heap(vec) => vec,
+        }
+    }
+}
+
+impl DerefMut for Parts {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Parts::Inline { len, data } => &mut data[..*len],
+            Parts::Heap(vec) => vec,
+        }
+    }
+}
+
+impl core::fmt::Debug for Parts {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<u64> for Parts {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut res = Self::zeros(0);
+        for val in iter {
+            res.push(val);
+        }
+        res
+    }
+}
+
+#[test]
+fn test_parts_spill() {
+    let mut parts = Parts::zeros(2);
+    assert_eq!(&parts[..], &[0, 0]);
+    for i in 0..3 {
+        parts.push(i);
+    }
+    assert!(!parts.is_spilled());
+    parts.push(3);
+    assert!(parts.is_spilled());
+    assert_eq!(&parts[..], &[0, 0, 0, 1, 2, 3]);
+    parts[1] = 7;
+    assert_eq!(parts.pop(), Some(3));
+    parts.truncate(2);
+    assert_eq!(&parts[..], &[0, 7]);
+    assert!(!parts.clone().is_spilled());
+
+    // New words are zero, even if the inline buffer had data.
+    let mut parts: Parts = (1..4).collect();
+    parts.truncate(1);
+    parts.resize(3);
+    assert_eq!(&parts[..], &[1, 0, 0]);
+    parts.resize(7);
+    assert!(parts.is_spilled());
+    assert_eq!(&parts[..], &[1, 0, 0, 0, 0, 0, 0]);
+
+    // Short vectors are stored inline.
+    let parts: Parts = (0..4).collect();
+    assert!(!parts.is_spilled());
+    assert!(Parts::from_slice(&[1; 9]).is_spilled());
+    let mut parts = Parts::zeros(1);
+    assert_eq!(parts.pop(), Some(0));
+    assert_eq!(parts.pop(), None);
+}
@@ -0,0 +1,632 @@
+//! This module contains the implementation of exact rational numbers, that
+//! are built on top of the big int types. Every finite float is a dyadic
+//! rational, so floats are converted to rationals exactly, and rationals are
+//! converted to floats with a single rounding.
+
+use core::cmp::Ordering;
+use core::fmt::Display;
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+use super::bigint::BigInt;
+use super::bigsint::BigSInt;
+use super::float::Float;
+use super::Semantics;
+
+/// This is an exact rational number p/q. The number is always reduced: the
+/// denominator is positive, and the numerator and the denominator have no
+/// common factors. Zero is represented as 0/1.
+///
+/// # Examples
+///
+/// ```
+///    use arpfloat::{BigInt, BigSInt, Float, Rational, FP64};
+///
+///    let x = Rational::new(BigSInt::from_i64(-6), BigInt::from_u64(4));
+///    let y = Rational::from_i64(1) / Rational::from_i64(3);
+///    let z = &x + &y;
+///    assert_eq!(z, Rational::new(BigSInt::from_i64(-7), BigInt::from_u64(6)));
+///
+///    // Floats are converted exactly, and rationals are correctly rounded.
+///    let f = Float::from_f64(0.1);
+///    let r = f.to_rational().unwrap();
+///    assert_eq!(Float::from_rational(FP64, &r).as_f64(), 0.1);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Rational {
+    num: BigSInt,
+    den: BigInt,
+}
+
+impl Rational {
+    /// Create a new number from the numerator `num` and the denominator
+    /// `den`, and reduce it. The denominator must not be zero.
+    pub fn new(num: BigSInt, den: BigInt) -> Self {
+        assert!(!den.is_zero(), "the denominator must not be zero");
+        let mut r = Rational { num, den };
+        r.reduce();
+        r
+    }
+
+    /// Create a new zero number.
+    pub fn zero() -> Self {
+        Self::from_bigsint(BigSInt::zero())
+    }
+
+    /// Create a new number with the value 1.
+    pub fn one() -> Self {
+        Self::from_bigsint(BigSInt::one())
+    }
+
+    /// Create a number from the integer `val`.
+    pub fn from_i64(val: i64) -> Self {
+        Self::from_bigsint(BigSInt::from_i64(val))
+    }
+
+    /// Create a number from the integer `val`.
+    pub fn from_bigsint(val: BigSInt) -> Self {
+        Rational {
+            num: val,
+            den: BigInt::one(),
+        }
+    }
+
+    /// Returns the numerator, that carries the sign of the number.
+    pub fn get_numerator(&self) -> BigSInt {
+        self.num.clone()
+    }
+
+    /// Returns the denominator, that is always positive.
+    pub fn get_denominator(&self) -> BigInt {
+        self.den.clone()
+    }
+
+    /// Returns true if the number is zero.
+    pub fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    /// Returns true if the number is negative.
+    pub fn is_negative(&self) -> bool {
+        self.num.is_negative()
+    }
+
+    /// Returns true if the number is an integer.
+    pub fn is_integer(&self) -> bool {
+        self.den == BigInt::one()
+    }
+
+    /// Returns the absolute value of the number.
+    pub fn abs(&self) -> Self {
+        Rational {
+            num: self.num.abs(),
+            den: self.den.clone(),
+        }
+    }
+
+    /// Returns the reciprocal 1/self. The number must not be zero.
+    pub fn recip(&self) -> Self {
+        assert!(!self.is_zero(), "the reciprocal of zero");
+        let num = BigSInt::new(self.num.get_sign(), self.den.clone());
+        Rational {
+            num,
+            den: self.num.get_magnitude(),
+        }
+    }
+
+    /// Raise this number to the power of `exp` and return the value. The
+    /// number must not be zero if the exponent is negative.
+    pub fn powi(&self, exp: i64) -> Self {
+        let base = if exp < 0 { self.recip() } else { self.clone() };
+        let exp = exp.unsigned_abs();
+        Rational {
+            num: base.num.powi(exp),
+            den: base.den.powi(exp),
+        }
+    }
+
+    /// Divide the numerator and the denominator by their common factors.
+    fn reduce(&mut self) {
+        let mut magnitude = self.num.get_magnitude();
+        let gcd = magnitude.gcd(&self.den);
+        if gcd != BigInt::one() {
+            magnitude.inplace_div(&gcd);
+            self.den.inplace_div(&gcd);
+        }
+        self.num = BigSInt::new(self.num.get_sign(), magnitude);
+    }
+
+    fn inplace_add(&mut self, rhs: &Self) {
+        let a = &self.num * &BigSInt::from(rhs.den.clone());
+        let b = &rhs.num * &BigSInt::from(self.den.clone());
+        *self = Self::new(a + b, &self.den * &rhs.den);
+    }
+
+    fn inplace_sub(&mut self, rhs: &Self) {
+        self.inplace_add(&-rhs);
+    }
+
+    fn inplace_mul(&mut self, rhs: &Self) {
+        *self = Self::new(&self.num * &rhs.num, &self.den * &rhs.den);
+    }
+
+    /// Divide this number by `rhs`. The divisor must not be zero.
+    fn inplace_div(&mut self, rhs: &Self) {
+        self.inplace_mul(&rhs.recip());
+    }
+}
+
+impl Float {
+    /// Load the rational number `val` into the float. The number is rounded
+    /// once, with the rounding mode of `sem`, and may overflow.
+    pub fn from_rational(sem: Semantics, val: &Rational) -> Self {
+        let rm = sem.get_rounding_mode();
+        let num = val.num.get_magnitude();
+        Self::from_fraction(sem, val.num.get_sign(), &num, &val.den, rm)
+    }
+
+    /// Converts the number to an exact rational number. Every finite float
+    /// is a dyadic rational. This method returns None for Inf and NaN.
+    pub fn to_rational(&self) -> Option<Rational> {
+        if self.is_zero() {
+            return Some(Rational::zero());
+        } else if !self.is_normal() {
+            return None;
+        }
+        // The value of the number is mantissa * 2^(exp - mantissa_len).
+        let exp = self.get_exp() - self.get_mantissa_len() as i64;
+        let mut num = self.get_mantissa();
+        let mut den = BigInt::one();
+        if exp > 0 {
+            num.shift_left(exp as usize);
+        } else {
+            den.shift_left(-exp as usize);
+        }
+        Some(Rational::new(BigSInt::new(self.get_sign(), num), den))
+    }
+}
+
+impl Rational {
+    /// Returns an iterator over the coefficients of the continued fraction
+    /// a0 + 1/(a1 + 1/(a2 + 1/( ... ))) of the number. The first coefficient
+    /// is the floor of the number, and the others are positive. The sequence
+    /// is finite, because the number is rational.
+    pub fn continued_fraction(&self) -> ContinuedFraction {
+        ContinuedFraction {
+            num: self.num.clone(),
+            den: self.den.clone(),
+        }
+    }
+
+    /// Returns an iterator over the convergents of the continued fraction of
+    /// the number. Each convergent is the best approximation of the number
+    /// among the fractions with a smaller denominator, and the last
+    /// convergent is the number itself.
+    pub fn convergents(&self) -> Convergents {
+        Convergents::new(self.continued_fraction())
+    }
+
+    /// Returns the fraction p/q that's closest to the number, among the
+    /// fractions with q <= `max_denominator`. Ties are broken in favor of the
+    /// smaller denominator. The bound must not be zero.
+    pub fn best_approximation(&self, max_denominator: &BigInt) -> Self {
+        assert!(!max_denominator.is_zero(), "the bound must not be zero");
+        if self.den <= *max_denominator {
+            return self.clone();
+        }
+
+        // The best approximation is either the last convergent below the
+        // bound, or the largest semiconvergent (h0 + t*h1)/(k0 + t*k1) below
+        // the bound, where h0/k0 is the convergent before the last one. The
+        // sequence starts with the convergents 0/1 and 1/0.
+        let mut prev = (BigSInt::zero(), BigInt::one());
+        let mut last = (BigSInt::one(), BigInt::zero());
+        for c in self.convergents() {
+            if c.den > *max_denominator {
+                break;
+            }
+            prev = last;
+            last = (c.num, c.den);
+        }
+
+        let mut t = max_denominator - &prev.1;
+        t.inplace_div(&last.1);
+        let semi_num = &prev.0 + &(&BigSInt::from(t.clone()) * &last.0);
+        let semi_den = &prev.1 + &(&t * &last.1);
+        let semi = Rational::new(semi_num, semi_den);
+        let last = Rational::new(last.0, last.1);
+        if (&semi - self).abs() < (&last - self).abs() {
+            return semi;
+        }
+        last
+    }
+}
+
+/// An iterator over the coefficients of a continued fraction. See
+/// [`Rational::continued_fraction`].
+#[derive(Debug, Clone)]
+pub struct ContinuedFraction {
+    // The remaining value num/den, or the end of the sequence if den is zero.
+    num: BigSInt,
+    den: BigInt,
+}
+
+impl ContinuedFraction {
+    /// Create an iterator over an empty sequence.
+    pub(crate) fn empty() -> Self {
+        ContinuedFraction {
+            num: BigSInt::zero(),
+            den: BigInt::zero(),
+        }
+    }
+}
+
+impl Iterator for ContinuedFraction {
+    type Item = BigSInt;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.den.is_zero() {
+            return None;
+        }
+        // Compute the floor of the value, and the reminder in [0, den).
+        let den = BigSInt::from(self.den.clone());
+        let mut a = self.num.clone();
+        let mut rem = a.inplace_div(&den);
+        if rem.is_negative() {
+            a -= BigSInt::one();
+            rem += &den;
+        }
+        // The next value is den/rem.
+        self.num = den;
+        self.den = rem.get_magnitude();
+        Some(a)
+    }
+}
+
+/// An iterator over the convergents of a continued fraction. See
+/// [`Rational::convergents`].
+#[derive(Debug, Clone)]
+pub struct Convergents {
+    cf: ContinuedFraction,
+    // The numerators and the denominators of the last two convergents.
+    h: (BigSInt, BigSInt),
+    k: (BigInt, BigInt),
+}
+
+impl Convergents {
+    /// Create an iterator over the convergents of the coefficients `cf`.
+    pub(crate) fn new(cf: ContinuedFraction) -> Self {
+        Convergents {
+            cf,
+            h: (BigSInt::one(), BigSInt::zero()),
+            k: (BigInt::zero(), BigInt::one()),
+        }
+    }
+}
+
+impl Iterator for Convergents {
+    type Item = Rational;
+    fn next(&mut self) -> Option<Self::Item> {
+        let a = self.cf.next()?;
+        let h = &(&a * &self.h.0) + &self.h.1;
+        // Only the first coefficient may be negative, and k.0 is zero then.
+        let k = &(&a.get_magnitude() * &self.k.0) + &self.k.1;
+        self.h.1 = core::mem::replace(&mut self.h.0, h.clone());
+        self.k.1 = core::mem::replace(&mut self.k.0, k.clone());
+        // The convergents are always reduced.
+        Some(Rational { num: h, den: k })
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(val: i64) -> Self {
+        Self::from_i64(val)
+    }
+}
+
+impl From<BigSInt> for Rational {
+    fn from(val: BigSInt) -> Self {
+        Self::from_bigsint(val)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_integer() {
+            return write!(f, "{}", self.num);
+        }
+        write!(f, "{}/{}", self.num, self.den.as_decimal())
+    }
+}
+
+impl Eq for Rational {}
+
+impl PartialEq for Rational {
+    fn eq(&self, other: &Rational) -> bool {
+        // The numbers are reduced, so equal numbers have equal parts.
+        self.num == other.num && self.den == other.den
+    }
+}
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // The denominators are positive, so the cross products have the
+        // order of the numbers.
+        let a = &self.num * &BigSInt::from(other.den.clone());
+        let b = &other.num * &BigSInt::from(self.den.clone());
+        a.cmp(&b)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Rational {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+    fn neg(self) -> Self::Output {
+        Rational {
+            num: -&self.num,
+            den: self.den.clone(),
+        }
+    }
+}
+
+macro_rules! declare_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $func_impl_name:ident) => {
+        // Self + Self
+        impl $trait_name for Rational {
+            type Output = Self;
+
+            fn $func_name(self, rhs: Self) -> Self::Output {
+                self.$func_name(&rhs)
+            }
+        }
+
+        // Self + &Self -> Self
+        impl $trait_name<&Self> for Rational {
+            type Output = Self;
+            fn $func_name(self, rhs: &Self) -> Self::Output {
+                let mut n = self;
+                n.$func_impl_name(rhs);
+                n
+            }
+        }
+
+        // &Self + &Self -> Self
+        impl $trait_name<Self> for &Rational {
+            type Output = Rational;
+            fn $func_name(self, rhs: Self) -> Self::Output {
+                let mut n = self.clone();
+                n.$func_impl_name(rhs);
+                n
+            }
+        }
+    };
+}
+
+declare_operator!(Add, add, inplace_add);
+declare_operator!(Sub, sub, inplace_sub);
+declare_operator!(Mul, mul, inplace_mul);
+declare_operator!(Div, div, inplace_div);
+
+macro_rules! declare_assign_operator {
+    ($trait_name:ident,
+     $func_name:ident,
+     $func_impl_name:ident) => {
+        impl $trait_name for Rational {
+            fn $func_name(&mut self, rhs: Self) {
+                self.$func_impl_name(&rhs);
+            }
+        }
+
+        impl $trait_name<&Rational> for Rational {
+            fn $func_name(&mut self, rhs: &Self) {
+                self.$func_impl_name(rhs);
+            }
+        }
+    };
+}
+
+declare_assign_operator!(AddAssign, add_assign, inplace_add);
+declare_assign_operator!(SubAssign, sub_assign, inplace_sub);
+declare_assign_operator!(MulAssign, mul_assign, inplace_mul);
+declare_assign_operator!(DivAssign, div_assign, inplace_div);
+
+#[cfg(test)]
+fn ratio(num: i64, den: u64) -> Rational {
+    Rational::new(BigSInt::from_i64(num), BigInt::from_u64(den))
+}
+
+#[test]
+fn test_rational_arithmetic() {
+    use crate::utils::Lfsr;
+    let mut lfsr = Lfsr::new();
+
+    // The numbers are reduced.
+    let x = ratio(-6, 4);
+    assert_eq!(x.get_numerator().as_i64(), -3);
+    assert_eq!(x.get_denominator().as_u64(), 2);
+    assert_eq!(ratio(0, 7), Rational::zero());
+    assert!(ratio(8, 4).is_integer());
+
+    // Compare the operations to the fractions of small integers, a/b + c/d.
+    for _ in 0..1000 {
+        let a = (lfsr.get64() % 200) as i64 - 100;
+        let b = lfsr.get64() % 100 + 1;
+        let c = (lfsr.get64() % 200) as i64 - 100;
+        let d = lfsr.get64() % 100 + 1;
+        let x = ratio(a, b);
+        let y = ratio(c, d);
+        let (bi, di) = (b as i64, d as i64);
+        assert_eq!(&x + &y, ratio(a * di + c * bi, b * d));
+        assert_eq!(&x - &y, ratio(a * di - c * bi, b * d));
+        assert_eq!(&x * &y, ratio(a * c, b * d));
+        if c != 0 {
+            let sign = if c < 0 { -1 } else { 1 };
+            let q = ratio(sign * a * di, bi as u64 * c.unsigned_abs());
+            assert_eq!(&x / &y, q);
+        }
+        assert_eq!(x.cmp(&y), (a * di).cmp(&(c * bi)));
+    }
+
+    let mut z = ratio(1, 2);
+    z += ratio(1, 3);
+    z *= &ratio(-6, 5);
+    z -= ratio(1, 1);
+    z /= ratio(2, 1);
+    assert_eq!(z, ratio(-1, 1));
+    assert_eq!(ratio(-2, 3).powi(-3), ratio(-27, 8));
+    assert_eq!(ratio(-2, 3).abs().recip(), ratio(3, 2));
+}
+
+#[test]
+fn test_rational_float_conversion() {
+    use crate::utils::Lfsr;
+    use crate::{RoundingMode, FP128, FP16, FP64};
+    let mut lfsr = Lfsr::new();
+
+    // Floats are converted exactly, so the round trip is the identity.
+    for _ in 0..1000 {
+        let val = f64::from_bits(lfsr.get64());
+        if !val.is_finite() {
+            continue;
+        }
+        let r = Float::from_f64(val).to_rational().unwrap();
+        assert_eq!(Float::from_rational(FP64, &r).as_f64(), val);
+    }
+    assert_eq!(Float::from_f64(-0.375).to_rational(), Some(ratio(-3, 8)));
+    assert_eq!(Float::from_f64(48.).to_rational(), Some(ratio(48, 1)));
+    assert!(Float::inf(FP64, false).to_rational().is_none());
+    assert!(Float::nan(FP64, false).to_rational().is_none());
+
+    // The conversion to floats is correctly rounded in all rounding modes.
+    // The quotient of two small integers is correctly rounded by the
+    // division of the floats.
+    let modes = [
+        RoundingMode::NearestTiesToEven,
+        RoundingMode::NearestTiesToAway,
+        RoundingMode::Zero,
+        RoundingMode::Positive,
+        RoundingMode::Negative,
+    ];
+    for _ in 0..200 {
+        let a = (lfsr.get64() >> 20) as i64 - (1 << 43);
+        let b = lfsr.get64() >> 30;
+        if b == 0 {
+            continue;
+        }
+        for rm in modes {
+            let r = Float::from_rational(FP64.with_rm(rm), &ratio(a, b));
+            let x = Float::from_i64(FP128, a);
+            let y = Float::from_u64(FP128, b);
+            let q = Float::div_with_rm(&x, &y, rm).cast_with_rm(FP64, rm);
+            // The quotient in FP128 is not exact, but it's not on a rounding
+            // boundary of FP64 either, so the second rounding is correct.
+            assert_eq!(r.as_f64(), q.as_f64());
+        }
+    }
+
+    // Overflow and underflow follow the rounding mode.
+    let big = ratio(1 << 40, 1).powi(2);
+    let sem = FP16.with_rm(RoundingMode::Zero);
+    assert!(Float::from_rational(FP16, &big).is_inf());
+    assert!(!Float::from_rational(sem, &big).is_inf());
+    let tiny = ratio(-1, 1 << 40);
+    let sem = FP16.with_rm(RoundingMode::Negative);
+    assert!(Float::from_rational(FP16, &tiny).is_zero());
+    assert!(!Float::from_rational(sem, &tiny).is_zero());
+}
+
+#[test]
+fn test_continued_fraction() {
+    extern crate alloc;
+    use crate::FP128;
+    use alloc::vec::Vec;
+    let pi = Float::pi(FP128);
+
+    // Verified with https://oeis.org/A001203.
+    let cf: Vec<i64> = pi
+        .continued_fraction()
+        .take(5)
+        .map(|a| a.as_i64())
+        .collect();
+    assert_eq!(cf, [3, 7, 15, 1, 292]);
+    let conv: Vec<Rational> = pi.convergents().take(4).collect();
+    let expected =
+        [ratio(3, 1), ratio(22, 7), ratio(333, 106), ratio(355, 113)];
+    assert_eq!(conv, expected);
+
+    // The sequence of a float is finite, and ends with the number itself.
+    let x = Float::from_f64(-0.1);
+    let exact = x.to_rational().unwrap();
+    assert_eq!(x.continued_fraction().next().unwrap().as_i64(), -1);
+    assert_eq!(x.convergents().last(), Some(exact));
+    let cf: Vec<i64> = ratio(-7, 3)
+        .continued_fraction()
+        .map(|a| a.as_i64())
+        .collect();
+    assert_eq!(cf, [-3, 1, 2]);
+    assert_eq!(Float::nan(FP128, false).continued_fraction().count(), 0);
+    assert_eq!(Float::inf(FP128, true).convergents().count(), 0);
+
+    // Stop when the error is below a tolerance.
+    let tolerance = Float::from_f64(1e-9).cast(FP128);
+    let c = pi.convergents().find(|c| {
+        let err = &Float::from_rational(FP128, c) - &pi;
+        err.abs() < tolerance
+    });
+    assert_eq!(c, Some(ratio(103993, 33102)));
+}
+
+#[test]
+fn test_best_rational() {
+    use crate::utils::Lfsr;
+    use crate::FP128;
+    let mut lfsr = Lfsr::new();
+
+    let pi = Float::pi(FP128);
+    let best = |x: &Float, n: u64| x.best_rational(&BigInt::from_u64(n));
+    assert_eq!(best(&pi, 1), Some(ratio(3, 1)));
+    assert_eq!(best(&pi, 100), Some(ratio(311, 99)));
+    assert_eq!(best(&pi, 1000), Some(ratio(355, 113)));
+    assert_eq!(best(&pi.neg(), 1000), Some(ratio(-355, 113)));
+    assert_eq!(best(&Float::from_f64(0.75), 1000), Some(ratio(3, 4)));
+    assert_eq!(best(&Float::inf(FP128, false), 10), None);
+
+    // Compare to a search over all of the denominators.
+    for _ in 0..300 {
+        let val = (lfsr.get64() % (1 << 40)) as f64 / (1u64 << 36) as f64 - 8.;
+        let n = lfsr.get64() % 60 + 1;
+        let x = Float::from_f64(val);
+        let exact = x.to_rational().unwrap();
+        let mut expected = Rational::zero();
+        let mut min_err = None;
+        for q in 1..=n {
+            // Check the two numerators around x*q.
+            let p = (val * q as f64).floor() as i64;
+            for p in [p, p + 1] {
+                let c = ratio(p, q);
+                let err = (&c - &exact).abs();
+                if min_err.as_ref().is_none_or(|e| err < *e) {
+                    min_err = Some(err);
+                    expected = c;
+                }
+            }
+        }
+        assert_eq!(best(&x, n), Some(expected));
+    }
+}
@@ -3,52 +3,16 @@
 extern crate alloc;
 
 use super::bigint::BigInt;
-use super::float::Float;
+use super::float::{Float, Status};
 use super::RoundingMode;
 use super::Semantics;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
-use core::cmp::Ordering;
-use core::fmt::Display;
+use core::fmt::{
+    Binary, Display, LowerExp, LowerHex, Octal, UpperExp, UpperHex,
+};
 
 impl Float {
-    /// Convert the number into a large integer, and a base-10 exponent.
-    fn convert_to_integer(&self) -> (BigInt, i64) {
-        // The natural representation of numbers is 1.mmmmmmm, where the
-        // mantissa is aligned to the MSB. In this method we convert the numbers
-        // into integers, that start at bit zero, so we use exponent that refers
-        //  to bit zero.
-        // See Ryu: Fast Float-to-String Conversion -- Ulf Adams.
-        // https://youtu.be/kw-U6smcLzk?t=681
-        let mut exp = self.get_exp() - self.get_mantissa_len() as i64;
-        let mut mantissa: BigInt = self.get_mantissa();
-
-        match exp.cmp(&0) {
-            Ordering::Less => {
-                // The number is not yet an integer, we need to convert it using
-                // the method:
-                // mmmmm * 5^(e) * 10 ^(-e) == mmmmm * 10 ^ (-e);
-                // where (5^e) * (10^-e) == (2^-e)
-                // And the left hand side is how we represent our binary number
-                // 1.mmmm * 2^-e, and the right-hand-side is how we represent
-                // our decimal number: nnnnnnn * 10^-e.
-                let five = BigInt::from_u64(5);
-                let e5 = five.powi((-exp) as u64);
-                mantissa.inplace_mul(&e5);
-                exp = -exp;
-            }
-            Ordering::Equal | Ordering::Greater => {
-                // The number is already an integer, just align it.
-                // In this case, E - M > 0, so we are aligning the larger
-                // integers, for example [1.mmmm * e^15], in FP16 (where M=10).
-                mantissa.shift_left(exp as usize);
-                exp = 0;
-            }
-        }
-
-        (mantissa, exp)
-    }
-
     /// Returns the highest number of decimal digits that are needed for
     /// representing this type accurately.
     pub fn get_decimal_accuracy(&self) -> usize {
@@ -59,83 +23,380 @@ impl Float {
         2 + (self.get_mantissa_len() * 59) / 196
     }
 
-    /// Reduce a number in the representation mmmmm * e^10, to fewer bits in
-    /// 'm', based on the max possible digits in the mantissa.
-    fn reduce_printed_integer_length(
-        &self,
-        integer: &mut BigInt,
-        exp: &mut i64,
-    ) {
-        let bits = integer.msb_index();
-        if bits <= self.get_mantissa_len() {
-            return;
+    /// Returns the shortest sequence of decimal digits that rounds back to
+    /// this number, and the base-10 exponent k, such that the value is
+    /// 0.d1d2d3... * 10^k. This is the free-format algorithm from "Printing
+    /// Floating-Point Numbers Quickly and Accurately" by Burger and Dybvig,
+    /// which is based on "How to Print Floating-Point Numbers Accurately" by
+    /// Steele and White.
+    pub(crate) fn get_shortest_digits(&self) -> (Vec<u8>, i64) {
+        debug_assert!(self.is_normal());
+        // The value is m * 2^e, where m is an integer.
+        let m = self.get_mantissa();
+        let e = self.get_exp() - self.get_mantissa_len() as i64;
+
+        // Numbers in the range [v - m_minus/s, v + m_plus/s] round to v. The
+        // gap below powers of two is half the size of the gap above them.
+        let precision = self.get_semantics().get_precision();
+        let min_exp = self.get_exp_bounds().0;
+        let is_pow2 = m == BigInt::one_hot(precision - 1);
+        let half_gap_below = is_pow2 && self.get_exp() > min_exp;
+        let mut r = m.clone();
+        let mut s = BigInt::one();
+        let mut m_plus = BigInt::one();
+        let mut m_minus = BigInt::one();
+        // The gaps are 2^e, so scale everything by 2 (or 4) to represent the
+        // midpoints as integers.
+        let scale = if half_gap_below { 2 } else { 1 };
+        r.shift_left(scale);
+        m_plus.shift_left(scale - 1);
+        if e >= 0 {
+            r.shift_left(e as usize);
+            m_plus.shift_left(e as usize);
+            m_minus.shift_left(e as usize);
+        } else {
+            s.shift_left(-e as usize);
+        }
+        s.shift_left(scale);
+
+        // The endpoints of the interval round to v if the mantissa is even,
+        // because the parser rounds ties to even.
+        let inclusive = m.is_even();
+        let ten = BigInt::from_u64(10);
+
+        // Estimate k = ceil(log10(v)) using log10(2) ~ 78913 / 2^18, and fix
+        // the estimation below.
+        let log2 = e + m.msb_index() as i64 - 1;
+        let mut k = (log2 * 78913).div_euclid(1 << 18) + 1;
+        let pow = ten.powi(k.unsigned_abs());
+        if k >= 0 {
+            s.inplace_mul(&pow);
+        } else {
+            r.inplace_mul(&pow);
+            m_plus.inplace_mul(&pow);
+            m_minus.inplace_mul(&pow);
+        }
+
+        // Make sure that the high end of the interval is below 10^k.
+        let above = |r: &BigInt, m_plus: &BigInt, s: &BigInt| {
+            let high = r.clone() + m_plus;
+            if inclusive {
+                high >= *s
+            } else {
+                high > *s
+            }
         };
-        let needed_bits = bits - self.get_mantissa_len();
-        // We convert from bits to base-10 digits: log(2)/log(10) ==> 59/196.
-        // A continuous fraction of 5 iteration gives the ratio.
-        let mut digits_to_remove = ((needed_bits * 59) / 196) as i64;
+        while above(&r, &m_plus, &s) {
+            s.inplace_mul(&ten);
+            k += 1;
+        }
+        loop {
+            let r10 = r.clone() * &ten;
+            let m_plus10 = m_plus.clone() * &ten;
+            if above(&r10, &m_plus10, &s) {
+                break;
+            }
+            r = r10;
+            m_plus = m_plus10;
+            m_minus.inplace_mul(&ten);
+            k -= 1;
+        }
 
-        // Only remove digits after the decimal points.
-        if digits_to_remove > *exp {
-            digits_to_remove = *exp;
+        // Generate the digits until the number is inside the interval.
+        let mut digits = Vec::new();
+        loop {
+            r.inplace_mul(&ten);
+            m_plus.inplace_mul(&ten);
+            m_minus.inplace_mul(&ten);
+            let mut q = r.clone();
+            r = q.inplace_div(&s);
+            let d = q.as_u64() as u8;
+
+            let low = if inclusive { r <= m_minus } else { r < m_minus };
+            let high = above(&r, &m_plus, &s);
+            if !low && !high {
+                digits.push(d);
+                continue;
+            }
+            // Pick the closer of the two candidates, and round up on ties.
+            let round_up = if low && high {
+                let r2 = r.clone() + &r;
+                r2 >= s
+            } else {
+                high
+            };
+            digits.push(if round_up { d + 1 } else { d });
+            break;
         }
-        *exp -= digits_to_remove;
-        let ten = BigInt::from_u64(10);
-        let divisor = ten.powi(digits_to_remove as u64);
-        integer.inplace_div(&divisor);
+        (digits, k)
     }
 
-    fn convert_normal_to_string(&self) -> String {
-        // Convert the integer to base-10 integer, and e, the exponent in
-        // base 10 (scientific notation).
-        let (mut integer, mut e) = self.convert_to_integer();
+    /// Returns the digits of the number rounded to an integer after scaling
+    /// it by 10^pow10. Ties are rounded to even, like Rust does for f64.
+    fn get_scaled_integer(&self, pow10: i64) -> BigInt {
+        let mut num = self.get_mantissa();
+        let mut den = BigInt::one();
+        let e = self.get_exp() - self.get_mantissa_len() as i64;
+        if e >= 0 {
+            num.shift_left(e as usize);
+        } else {
+            den.shift_left(-e as usize);
+        }
+        let scale = BigInt::from_u64(10).powi(pow10.unsigned_abs());
+        if pow10 >= 0 {
+            num.inplace_mul(&scale);
+        } else {
+            den.inplace_mul(&scale);
+        }
 
-        // Try to shorten the number.
-        self.reduce_printed_integer_length(&mut integer, &mut e);
+        let rem = num.inplace_div(&den);
+        let rem2 = rem.clone() + &rem;
+        if rem2 > den || (rem2 == den && num.is_odd()) {
+            num.inplace_add(&BigInt::one());
+        }
+        num
+    }
 
-        // Extract the digits: Div10-Mod10-Div10-Mod10 ....
-        let mut buff = Vec::new();
+    /// Returns the digits of the number rounded to `precision` digits after
+    /// the decimal point, and the exponent k, such that the value is
+    /// 0.d1d2d3... * 10^k.
+    fn get_fixed_digits(&self, precision: usize) -> (Vec<u8>, i64) {
+        let precision = precision as i64;
+        let integer = if self.is_normal() {
+            self.get_scaled_integer(precision)
+        } else {
+            BigInt::zero()
+        };
+        if integer.is_zero() {
+            return (Vec::from([0]), 1 - precision);
+        }
         let digits = integer.to_digits::<10>();
-        for d in digits {
-            buff.push(char::from_digit(d as u32, 10).unwrap())
+        let len = digits.len() as i64;
+        (digits, len - precision)
+    }
+
+    /// Returns the number rounded to `precision + 1` significant digits, and
+    /// the exponent k, such that the value is 0.d1d2d3... * 10^k.
+    fn get_exp_digits(&self, precision: usize) -> (Vec<u8>, i64) {
+        let n = precision as i64 + 1;
+        if !self.is_normal() {
+            return (alloc::vec![0; n as usize], 1);
+        }
+        let low = BigInt::from_u64(10).powi(n as u64 - 1);
+        let high = BigInt::from_u64(10).powi(n as u64);
+
+        // Estimate the exponent k using log10(2) ~ 78913 / 2^18, and fix the
+        // estimation until the integer has exactly n digits.
+        let msb = self.get_mantissa().msb_index() as i64;
+        let log2 = self.get_exp() - self.get_mantissa_len() as i64 + msb - 1;
+        let mut k = (log2 * 78913).div_euclid(1 << 18) + 1;
+        loop {
+            let integer = self.get_scaled_integer(n - k);
+            if integer >= high {
+                k += 1;
+            } else if integer < low {
+                k -= 1;
+            } else {
+                return (integer.to_digits::<10>(), k);
+            }
+        }
+    }
+
+    /// Print the digits 0.d1d2d3... * 10^k without an exponent.
+    fn digits_to_decimal(digits: &[u8], k: i64) -> String {
+        let digits: Vec<char> =
+            digits.iter().map(|d| (b'0' + d) as char).collect();
+        let len = digits.len() as i64;
+
+        let mut buff = String::new();
+        if k <= 0 {
+            buff.push_str("0.");
+            buff.extend(core::iter::repeat_n('0', -k as usize));
+            buff.extend(digits);
+        } else if k < len {
+            buff.extend(&digits[..k as usize]);
+            buff.push('.');
+            buff.extend(&digits[k as usize..]);
+        } else {
+            buff.extend(digits);
+            buff.extend(core::iter::repeat_n('0', (k - len) as usize));
+        }
+        buff
+    }
+
+    /// Print the digits 0.d1d2d3... * 10^k in scientific notation, as
+    /// d1.d2d3...e(k-1).
+    fn digits_to_scientific(digits: &[u8], k: i64, upper: bool) -> String {
+        let mut buff = String::new();
+        for (i, d) in digits.iter().enumerate() {
+            if i == 1 {
+                buff.push('.');
+            }
+            buff.push((b'0' + d) as char);
+        }
+        buff.push(if upper { 'E' } else { 'e' });
+        buff.push_str(&(k - 1).to_string());
+        buff
+    }
+
+    /// Convert the absolute value of the number to a string, in the layout
+    /// `layout`. Without a precision this prints the shortest number of digits
+    /// that parse back to the same value. The layout matches the formatting
+    /// of f32 and f64, except that Display switches to scientific notation for
+    /// numbers below 1e-6 or above 1e21, like JavaScript does.
+    fn convert_to_string(&self, layout: Layout, prec: Option<usize>) -> String {
+        if self.is_nan() {
+            return "NaN".to_string();
+        } else if self.is_inf() {
+            return "inf".to_string();
         }
 
-        debug_assert!(e >= 0);
-        // Add the trailing zeros, and make room to place the point.
-        while buff.len() < e as usize {
-            buff.insert(0, '0');
+        match (layout, prec) {
+            (Layout::Decimal, Some(p)) => {
+                let (digits, k) = self.get_fixed_digits(p);
+                Self::digits_to_decimal(&digits, k)
+            }
+            (Layout::Decimal, None) if self.is_zero() => "0".to_string(),
+            (Layout::Decimal, None) => {
+                let (digits, k) = self.get_shortest_digits();
+                if (-5..=21).contains(&k) {
+                    Self::digits_to_decimal(&digits, k)
+                } else {
+                    Self::digits_to_scientific(&digits, k, false)
+                }
+            }
+            (Layout::Scientific(upper), Some(p)) => {
+                let (digits, k) = self.get_exp_digits(p);
+                Self::digits_to_scientific(&digits, k, upper)
+            }
+            (Layout::Scientific(upper), None) => {
+                let (digits, k) = if self.is_zero() {
+                    (Vec::from([0]), 1)
+                } else {
+                    self.get_shortest_digits()
+                };
+                Self::digits_to_scientific(&digits, k, upper)
+            }
+        }
+    }
+
+    /// Writes the number to the formatter, and handles the sign, width,
+    /// alignment and fill flags of the formatter.
+    fn format_with_layout(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+        layout: Layout,
+    ) -> core::fmt::Result {
+        use core::fmt::{Alignment, Write};
+        let body = self.convert_to_string(layout, f.precision());
+        let sign = if self.is_nan() {
+            ""
+        } else if self.get_sign() {
+            "-"
+        } else if f.sign_plus() {
+            "+"
+        } else {
+            ""
+        };
+
+        let len = sign.len() + body.len();
+        let pad = f.width().unwrap_or(0).saturating_sub(len);
+        if f.sign_aware_zero_pad() {
+            f.write_str(sign)?;
+            for _ in 0..pad {
+                f.write_char('0')?;
+            }
+            return f.write_str(&body);
         }
 
-        buff.insert(buff.len() - e as usize, '.');
-        while !buff.is_empty() && buff[buff.len() - 1] == '0' {
-            buff.pop();
+        let (before, after) = match f.align().unwrap_or(Alignment::Right) {
+            Alignment::Left => (0, pad),
+            Alignment::Right => (pad, 0),
+            Alignment::Center => (pad / 2, pad - pad / 2),
+        };
+        let fill = f.fill();
+        for _ in 0..before {
+            f.write_char(fill)?;
         }
-        String::from_iter(buff)
+        f.write_str(sign)?;
+        f.write_str(&body)?;
+        for _ in 0..after {
+            f.write_char(fill)?;
+        }
+        Ok(())
     }
+}
 
-    /// Convert the number to a string. This is a simple implementation
-    /// that does not take into account rounding during the round-trip of
-    /// parsing-printing of the value, or scientific notation, and the minimal
-    /// representation of numbers. For all of that that check out the paper:
-    /// "How to Print Floating-Point Numbers Accurately" by Steele and White.
-    fn convert_to_string(&self) -> String {
-        let result = if self.get_sign() { "-" } else { "" };
-        let mut result: String = result.to_string();
+impl Float {
+    /// Convert the number to a hexadecimal string, in the format of the C99
+    /// printf conversion "%a", such as "0x1.921fb54442d18p+1". The conversion
+    /// is exact, and `try_from_str` parses the string back to the same number.
+    pub fn to_hex_string(&self) -> String {
+        let sign = if self.get_sign() { "-" } else { "" };
+        if self.is_nan() {
+            return "nan".to_string();
+        } else if self.is_inf() {
+            return alloc::format!("{}inf", sign);
+        } else if self.is_zero() {
+            return alloc::format!("{}0x0p+0", sign);
+        }
 
-        let body: String = match self.get_category() {
-            super::float::Category::Infinity => "Inf".to_string(),
-            super::float::Category::NaN => "NaN".to_string(),
-            super::float::Category::Normal => self.convert_normal_to_string(),
-            super::float::Category::Zero => "0.0".to_string(),
+        // Subnormal numbers don't have the implicit leading one.
+        let mantissa_len = self.get_mantissa_len();
+        let mut frac = self.get_mantissa();
+        let lead = if frac.msb_index() > mantissa_len {
+            frac.flip_bit(mantissa_len);
+            '1'
+        } else {
+            '0'
         };
 
-        result.push_str(&body);
-        result
+        // Align the fraction to a whole number of hex digits.
+        let num_digits = mantissa_len.div_ceil(4);
+        frac.shift_left(num_digits * 4 - mantissa_len);
+        let mut digits = alloc::vec![0; num_digits];
+        if !frac.is_zero() {
+            let d = frac.to_digits::<16>();
+            digits[num_digits - d.len()..].copy_from_slice(&d);
+        }
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        let mut buff = alloc::format!("{}0x{}", sign, lead);
+        if !digits.is_empty() {
+            buff.push('.');
+            for d in digits {
+                buff.push(char::from_digit(d as u32, 16).unwrap());
+            }
+        }
+        buff.push_str(&alloc::format!("p{:+}", self.get_exp()));
+        buff
     }
 }
+
+/// The notation that the formatting traits print the numbers with.
+#[derive(Clone, Copy)]
+enum Layout {
+    Decimal,
+    Scientific(bool),
+}
+
 impl Display for Float {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.convert_to_string())
+        self.format_with_layout(f, Layout::Decimal)
+    }
+}
+
+impl LowerExp for Float {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.format_with_layout(f, Layout::Scientific(false))
+    }
+}
+
+impl UpperExp for Float {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.format_with_layout(f, Layout::Scientific(true))
     }
 }
 
@@ -170,14 +431,43 @@ impl Display for Semantics {
     }
 }
 
-#[cfg(feature = "std")]
+impl Display for Status {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_ok() {
+            return f.write_str("OK");
+        }
+        let names = [
+            (Status::INVALID_OP, "InvalidOp"),
+            (Status::DIV_BY_ZERO, "DivByZero"),
+            (Status::OVERFLOW, "Overflow"),
+            (Status::UNDERFLOW, "Underflow"),
+            (Status::INEXACT, "Inexact"),
+        ];
+        let mut sep = "";
+        for (flag, name) in names {
+            if self.contains(flag) {
+                write!(f, "{}{}", sep, name)?;
+                sep = "|";
+            }
+        }
+        Ok(())
+    }
+}
+
 mod from {
+    extern crate alloc;
+
+    use alloc::vec::Vec;
     use core::fmt::{Debug, Display};
-    use std::error::Error;
+    use core::str::FromStr;
 
+    use crate::bigint::LossFraction;
     use crate::{BigInt, Float, Semantics, FP64};
 
     impl Float {
+        /// Parses the decimal number in `value`, such as "-1.25e-3", "nan"
+        /// or "inf". The result is correctly rounded with the rounding mode
+        /// of `sem`.
         pub fn try_from_str(
             value: &str,
             sem: Semantics,
@@ -187,9 +477,8 @@ mod from {
             }
 
             let chars = value.as_bytes();
-            let (sign, skip) = if chars[0] == '-' as u8 || chars[0] == '+' as u8
-            {
-                (chars[0] == '-' as u8, 1)
+            let (sign, skip) = if chars[0] == b'-' || chars[0] == b'+' {
+                (chars[0] == b'-', 1)
             } else {
                 (false, 0)
             };
@@ -199,72 +488,122 @@ mod from {
                 return Ok(Self::nan(sem, sign));
             }
 
-            if value.eq_ignore_ascii_case("inf") {
+            if value.eq_ignore_ascii_case("inf")
+                || value.eq_ignore_ascii_case("infinity")
+            {
                 return Ok(Self::inf(sem, sign));
             }
 
-            let l_r = value.split_once('.');
-            // handle cases where we have no `.` and as such no mantissa
-            if l_r.is_none() {
-                let ((num, _), exp_num) = parse_with_exp(value)?;
-                let mut num = Float::from_bigint(sem, num);
-                if let Some(exp) = exp_num {
-                    if exp >= 0 {
-                        num *= Float::from_bigint(
-                            sem,
-                            BigInt::from_u64(10).powi(exp as u64),
-                        );
-                    } else {
-                        num /= Float::from_bigint(
-                            sem,
-                            BigInt::from_u64(10).powi((-exp) as u64),
-                        );
-                    }
-                }
-                num.set_sign(sign);
-                return Ok(num);
-            }
-            let (left, right) = l_r.unwrap();
-            // try parsing decimal value of 0 (as it has a different type than generic numbers)
-            if right
-                .chars()
-                .map(|chr| chr as u32 & !('0' as u32))
-                .sum::<u32>()
-                == 0
+            if let Some(hex) = value
+                .strip_prefix("0x")
+                .or_else(|| value.strip_prefix("0X"))
             {
-                return parse_whole_num(left, sign, sem)
-                    .map(|num| Ok(num))
-                    .unwrap_or(Err(ParseError(
-                        ParseErrorKind::ParsingNumberFailed,
-                    )));
-            }
-            let left_num = parse_big_int(left).map(|num| Ok(num)).unwrap_or(
-                Err(ParseError(ParseErrorKind::ParsingNumberFailed)),
-            )?;
-            // parse the mantissa and an optional exponent part
-            let ((right_num, right_num_digits), exp_num) =
-                parse_with_exp(right)?;
-            // now construct the number from the info we parsed previously
-            let exp = BigInt::from_u64(10).powi(right_num_digits as u64);
-            let num = left_num * &exp + &right_num;
-
-            let mut ret =
-                Float::from_bigint(sem, num) / (Float::from_bigint(sem, exp));
-            if let Some(exp_num) = exp_num {
-                if exp_num >= 0 {
-                    ret *= Float::from_bigint(
-                        sem,
-                        BigInt::from_u64(10).powi(exp_num as u64),
-                    )
-                } else {
-                    ret /= Float::from_bigint(
-                        sem,
-                        BigInt::from_u64(10).powi((-exp_num) as u64),
-                    )
-                }
+                return Self::parse_hex(hex, sign, sem);
+            }
+
+            // Split the number into the digits and the exponent.
+            let (digits, exp) = match value.find(['e', 'E']) {
+                Some(idx) => (&value[..idx], parse_exp(&value[idx + 1..])?),
+                None => (value, 0),
+            };
+            let (left, right) = digits.split_once('.').unwrap_or((digits, ""));
+            if left.is_empty() && right.is_empty() {
+                return Err(ParseError(ParseErrorKind::ParsingNumberFailed));
+            }
+
+            // The value is the integer 'left.right' times 10^exp.
+            let mut num = parse_big_int(left)
+                .ok_or(ParseError(ParseErrorKind::ParsingNumberFailed))?;
+            let frac = parse_big_int(right)
+                .ok_or(ParseError(ParseErrorKind::ParsingNumberFailed))?;
+            num.inplace_mul(&BigInt::from_u64(10).powi(right.len() as u64));
+            num.inplace_add(&frac);
+            let exp = exp.saturating_sub(right.len() as i64);
+            Ok(Self::from_decimal(sem, sign, &num, exp))
+        }
+
+        /// Parses the hexadecimal number in `value` (without the "0x"
+        /// prefix), such as "1.921fb54442d18p+1". The result is exact if it
+        /// fits in `sem`, and correctly rounded otherwise.
+        fn parse_hex(
+            value: &str,
+            sign: bool,
+            sem: Semantics,
+        ) -> Result<Self, ParseError> {
+            let err = || ParseError(ParseErrorKind::ParsingNumberFailed);
+            let (digits, exp) = match value.find(['p', 'P']) {
+                Some(idx) => (&value[..idx], parse_exp(&value[idx + 1..])?),
+                None => (value, 0),
+            };
+            let (left, right) = digits.split_once('.').unwrap_or((digits, ""));
+            if left.is_empty() && right.is_empty() {
+                return Err(err());
+            }
+
+            // The value is the integer 'left.right' times 2^exp.
+            let mut num = BigInt::zero();
+            for digit in left.chars().chain(right.chars()) {
+                let d = digit.to_digit(16).ok_or_else(err)?;
+                num.shift_left(4);
+                num.inplace_add(&BigInt::from_u64(d as u64));
+            }
+            let exp = exp.saturating_sub(4 * right.len() as i64);
+            if num.is_zero() {
+                return Ok(Self::zero(sem, sign));
+            }
+
+            // Handle the numbers that obviously overflow or underflow.
+            let rm = sem.get_rounding_mode();
+            let exp = exp.clamp(-1 << 40, 1 << 40);
+            let log2 = num.msb_index() as i64 + exp;
+            let (min_exp, max_exp) = Self::zero(sem, sign).get_exp_bounds();
+            if log2 > max_exp + 4 {
+                return Ok(Self::one(sem, sign).scale(1 << 40, rm));
+            }
+            if log2 < min_exp - sem.get_precision() as i64 - 4 {
+                return Ok(Self::one(sem, sign).scale(-1 << 40, rm));
+            }
+
+            let exp = exp + sem.get_mantissa_len() as i64;
+            let mut res = Self::new(sem, sign, exp, num);
+            res.normalize(rm, LossFraction::ExactlyZero);
+            Ok(res)
+        }
+
+        /// Returns the number `num * 10^exp`, rounded once with the rounding
+        /// mode of `sem`.
+        fn from_decimal(
+            sem: Semantics,
+            sign: bool,
+            num: &BigInt,
+            exp: i64,
+        ) -> Self {
+            let rm = sem.get_rounding_mode();
+            if num.is_zero() {
+                return Self::zero(sem, sign);
+            }
+
+            // Handle the numbers that obviously overflow or underflow. The
+            // exponent of the number is log2(num) + exp * log2(10), where
+            // log2(10) ~ 108853 / 2^15.
+            let exp = exp.clamp(-1 << 40, 1 << 40);
+            let log2 = num.msb_index() as i64 + ((exp * 108853) >> 15);
+            let (min_exp, max_exp) = Self::zero(sem, sign).get_exp_bounds();
+            if log2 > max_exp + 4 {
+                return Self::one(sem, sign).scale(1 << 40, rm);
+            }
+            if log2 < min_exp - sem.get_precision() as i64 - 4 {
+                return Self::one(sem, sign).scale(-1 << 40, rm);
+            }
+
+            // Compute the exact fraction and round it once.
+            let pow10 = BigInt::from_u64(10).powi(exp.unsigned_abs());
+            if exp >= 0 {
+                let num = num.clone() * &pow10;
+                Self::from_fraction(sem, sign, &num, &BigInt::one(), rm)
+            } else {
+                Self::from_fraction(sem, sign, num, &pow10, rm)
             }
-            ret.set_sign(sign);
-            Ok(ret)
         }
     }
 
@@ -278,75 +617,79 @@ mod from {
         }
     }
 
-    fn parse_with_exp(
-        value: &str,
-    ) -> Result<((BigInt, usize), Option<i64>), ParseError> {
-        let idx = value.find(|c| c == 'e' || c == 'E');
-        let (num_raw, exp) = if let Some(idx) = idx {
-            let (l, r) = value.split_at(idx);
-            (l, Some(&r[1..]))
-        } else {
-            (value, None)
+    /// Parses the exponent of the number, and saturates it if it's too big.
+    fn parse_exp(value: &str) -> Result<i64, ParseError> {
+        let err = ParseError(ParseErrorKind::ExponentParseFailed);
+        let (sign, digits) = match value.as_bytes().first() {
+            Some(b'-') => (true, &value[1..]),
+            Some(b'+') => (false, &value[1..]),
+            _ => (false, value),
         };
-
-        let num = parse_big_int(num_raw)
-            .map(|num| Ok((num, num_raw.len())))
-            .unwrap_or(Err(ParseError(ParseErrorKind::ParsingNumberFailed)))?;
-
-        if let Some(exp) = exp {
-            match exp.parse::<i64>() {
-                Ok(exp) => {
-                    return Ok((num, Some(exp)));
-                }
-                Err(_) => {
-                    return Err(ParseError(ParseErrorKind::ExponentParseFailed))
-                }
+        if digits.is_empty() {
+            return Err(err);
+        }
+        let mut exp: i64 = 0;
+        for digit in digits.bytes() {
+            if !digit.is_ascii_digit() {
+                return Err(err);
             }
+            exp = exp.saturating_mul(10).saturating_add((digit - b'0') as i64);
         }
-        Ok((num, None))
+        Ok(if sign { -exp } else { exp })
     }
 
     fn parse_big_int(value: &str) -> Option<BigInt> {
-        let chars = value.as_bytes();
-        let ten = BigInt::from_u64(10);
-        let mut num = BigInt::from_u64(0);
-        for digit in chars.iter() {
-            if *digit > '9' as u8 || *digit < '0' as u8 {
-                return None;
-            }
-            let part = [*digit as u64 - '0' as u64];
-            num.inplace_mul(&ten);
-            num.inplace_add_slice(&part);
+        if value.is_empty() {
+            return Some(BigInt::zero());
+        }
+        if !value.bytes().all(|digit| digit.is_ascii_digit()) {
+            return None;
         }
-        Some(num)
+        BigInt::from_str_radix(value, 10).ok()
     }
 
-    fn parse_whole_num(
-        value: &str,
-        sign: bool,
-        sem: Semantics,
-    ) -> Option<Float> {
-        let chars = value.as_bytes();
-        if value.len() == 1 && chars[0] == '0' as u8 {
-            return Some(Float::zero(sem, sign));
+    impl BigInt {
+        /// Parses the number in `value`, written in base `radix`, such as
+        /// "ff" in base 16. The digits above 9 are letters, in lowercase or
+        /// uppercase. The radix must be in the range 2..=36.
+        pub fn from_str_radix(
+            value: &str,
+            radix: u32,
+        ) -> Result<Self, ParseError> {
+            assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+            let value = value.strip_prefix('+').unwrap_or(value);
+            if value.is_empty() {
+                return Err(ParseError(ParseErrorKind::InputEmpty));
+            }
+            let digits: Option<Vec<u8>> = value
+                .chars()
+                .map(|c| c.to_digit(radix).map(|d| d as u8))
+                .collect();
+            let digits =
+                digits.ok_or(ParseError(ParseErrorKind::InvalidDigit))?;
+            Ok(Self::from_radix_digits(&digits, radix as u8))
         }
-        let num = parse_big_int(value)?;
+    }
 
-        let mut ret = Float::from_bigint(sem, num);
-        ret.set_sign(sign);
+    impl FromStr for BigInt {
+        type Err = ParseError;
 
-        return Some(ret);
+        fn from_str(value: &str) -> Result<Self, Self::Err> {
+            Self::from_str_radix(value, 10)
+        }
     }
 
     enum ParseErrorKind {
         InputEmpty,
         ParsingNumberFailed,
         ExponentParseFailed,
+        InvalidDigit,
     }
 
     pub struct ParseError(ParseErrorKind);
 
-    impl Error for ParseError {}
+    #[cfg(feature = "std")]
+    impl std::error::Error for ParseError {}
 
     impl Display for ParseError {
         fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -360,6 +703,9 @@ mod from {
                 ParseErrorKind::InputEmpty => {
                     f.write_str("The input provided was empty")
                 }
+                ParseErrorKind::InvalidDigit => {
+                    f.write_str("Invalid digit found in the number")
+                }
             }
         }
     }
@@ -386,28 +732,36 @@ fn test_convert_to_string() {
         format!("{}", Float::from_f64(val).cast(FP64))
     }
 
-    assert_eq!("-0.0", to_str_w_fp16(-0.));
-    assert_eq!(".3", to_str_w_fp16(0.3));
+    assert_eq!("-0", to_str_w_fp16(-0.));
+    assert_eq!("0.3", to_str_w_fp16(0.3));
     assert_eq!("4.5", to_str_w_fp16(4.5));
-    assert_eq!("256.", to_str_w_fp16(256.));
-    assert_eq!("Inf", to_str_w_fp16(65534.));
-    assert_eq!("-Inf", to_str_w_fp16(-65534.));
-    assert_eq!(".0999", to_str_w_fp16(0.1));
-    assert_eq!(".1", to_str_w_fp64(0.1));
-    assert_eq!(".29999999999999998", to_str_w_fp64(0.3));
-    assert_eq!("2251799813685248.", to_str_w_fp64((1u64 << 51) as f64));
-    assert_eq!("1995.1994999999999", to_str_w_fp64(1995.1995));
+    assert_eq!("256", to_str_w_fp16(256.));
+    assert_eq!("inf", to_str_w_fp16(65534.));
+    assert_eq!("-inf", to_str_w_fp16(-65534.));
+    assert_eq!("0.1", to_str_w_fp16(0.1));
+    assert_eq!("0.0999", to_str_w_fp16(0.0999));
+    assert_eq!("65500", to_str_w_fp16(65504.));
+    assert_eq!("0.1", to_str_w_fp64(0.1));
+    assert_eq!("0.3", to_str_w_fp64(0.3));
+    assert_eq!("0.30000000000000004", to_str_w_fp64(0.1 + 0.2));
+    assert_eq!("2251799813685248", to_str_w_fp64((1u64 << 51) as f64));
+    assert_eq!("100000000000000000000", to_str_w_fp64(1e20));
+    assert_eq!("1e22", to_str_w_fp64(1e22));
+    assert_eq!("-1.5e300", to_str_w_fp64(-1.5e300));
+    assert_eq!("1e-7", to_str_w_fp64(1e-7));
+    assert_eq!("0.000001", to_str_w_fp64(1e-6));
+    assert_eq!("1995.1995", to_str_w_fp64(1995.1995));
 }
 
 #[test]
 fn test_from_string() {
-    assert_eq!("-3.", Float::try_from("-3.0").unwrap().to_string());
-    assert_eq!("30.", Float::try_from("30").unwrap().to_string());
+    assert_eq!("-3", Float::try_from("-3.0").unwrap().to_string());
+    assert_eq!("30", Float::try_from("30").unwrap().to_string());
     assert_eq!("430.56", Float::try_from("430.56").unwrap().to_string());
     assert_eq!("5.2", Float::try_from("5.2").unwrap().to_string());
-    assert_eq!("Inf", Float::try_from("inf").unwrap().to_string());
+    assert_eq!("inf", Float::try_from("inf").unwrap().to_string());
     assert_eq!("NaN", Float::try_from("nan").unwrap().to_string());
-    assert_eq!("32.", Float::try_from("3.2e1").unwrap().to_string());
+    assert_eq!("32", Float::try_from("3.2e1").unwrap().to_string());
     assert_eq!("4.4", Float::try_from("44.e-1").unwrap().to_string());
     assert_eq!("5.4", Float::try_from("54e-1").unwrap().to_string());
     assert_eq!("-5.485", Float::try_from("-54.85e-1").unwrap().to_string());
@@ -417,6 +771,188 @@ fn test_from_string() {
     assert!(Float::try_from("").is_err());
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_correctly_rounded() {
+    use crate::utils;
+    use std::format;
+    use std::string::String;
+
+    fn check(s: &str) {
+        let expected: f64 = s.parse().unwrap();
+        let parsed = Float::try_from(s).unwrap().as_f64();
+        assert_eq!(expected.to_bits(), parsed.to_bits(), "{}", s);
+    }
+
+    // Halfway cases, subnormals and the edges of the range.
+    for s in [
+        "9007199254740993",
+        "9007199254740995",
+        "0.1",
+        "1e23",
+        "8.98846567431158e307",
+        "1.7976931348623157e308",
+        "1.7976931348623158e308",
+        "1.7976931348623159e308",
+        "2.2250738585072011e-308",
+        "2.2250738585072012e-308",
+        "4.9406564584124654e-324",
+        "2.4703282292062327e-324",
+        "2.4703282292062328e-324",
+        "1e-400",
+        "-1e400",
+        "1e99999999999999999999",
+        "0.000000000000000000000000000000000000000001e42",
+        "7.038531e-26",
+        "123456789012345678901234567890e-10",
+        ".5",
+        "5.",
+        "+0.0",
+        "-0",
+    ] {
+        check(s);
+    }
+
+    // The number 2^-1075, which is exactly halfway between zero and the
+    // smallest subnormal. It rounds to zero, and anything above it does not.
+    let half = BigInt::from_u64(5).powi(1075).as_decimal();
+    check(&format!("{}e-1075", half));
+    check(&format!("{}1e-1076", half));
+
+    // Random numbers, printed with more or fewer digits than needed.
+    let mut lfsr = utils::Lfsr::new();
+    for _ in 0..1000 {
+        let v = f64::from_bits(lfsr.get64());
+        if v.is_nan() {
+            continue;
+        }
+        check(&format!("{:e}", v));
+        check(&format!("{:.25e}", v));
+        check(&format!("{:.5e}", v));
+        let digits = lfsr.get64() % 40 + 1;
+        let mut s = String::new();
+        for _ in 0..digits {
+            s.push(char::from(b'0' + (lfsr.get64() % 10) as u8));
+        }
+        check(&format!("{}e{}", s, (lfsr.get64() % 700) as i64 - 360));
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_parse_with_rounding_modes() {
+    use crate::FP64;
+    fn parse(s: &str, rm: RoundingMode) -> f64 {
+        Float::try_from_str(s, FP64.with_rm(rm)).unwrap().as_f64()
+    }
+
+    let s = "9007199254740993";
+    assert_eq!(parse(s, RoundingMode::NearestTiesToEven), 9007199254740992.);
+    assert_eq!(parse(s, RoundingMode::NearestTiesToAway), 9007199254740994.);
+    assert_eq!(parse(s, RoundingMode::Positive), 9007199254740994.);
+    assert_eq!(parse(s, RoundingMode::Negative), 9007199254740992.);
+    assert_eq!(parse("-0.1", RoundingMode::Zero), -0.09999999999999999);
+    assert_eq!(parse("-0.1", RoundingMode::Negative), -0.1);
+    assert_eq!(parse("1e400", RoundingMode::Zero), f64::MAX);
+    assert_eq!(parse("1e-400", RoundingMode::Positive), 5e-324);
+    assert_eq!(parse("-1e-400", RoundingMode::Positive), -0.);
+    assert_eq!(parse("0.5", RoundingMode::Positive), 0.5);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_shortest_printing_round_trip() {
+    use crate::utils;
+    use crate::{FP128, FP16};
+
+    // Check every FP16 value.
+    for i in 0..(1 << 15) {
+        let (exp, mantissa) = (i >> 10, (i & 0x3ff) as f64);
+        let val = match exp {
+            0 => mantissa * 2f64.powi(-24),
+            31 => continue,
+            _ => (1024. + mantissa) * 2f64.powi(exp - 25),
+        };
+        for val in [val, -val] {
+            let fp = Float::from_f64(val).cast(FP16);
+            let parsed = Float::try_from_str(&fp.to_string(), FP16).unwrap();
+            assert!(parsed == fp && parsed.get_sign() == fp.get_sign());
+        }
+    }
+
+    let mut lfsr = utils::Lfsr::new();
+    for _ in 0..200 {
+        let v = f64::from_bits(lfsr.get64());
+        let fp = Float::from_f64(v).cast(FP128);
+        let fp = &fp * &Float::pi(FP128);
+        if fp.is_normal() {
+            let parsed = Float::try_from_str(&fp.to_string(), FP128).unwrap();
+            assert_eq!(parsed, fp);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hex_string() {
+    use crate::{utils, FP16, FP256, FP64};
+    use std::string::ToString;
+
+    // Verify the results with python: float.hex(x)
+    for (v, s) in [
+        (core::f64::consts::PI, "0x1.921fb54442d18p+1"),
+        (1.0, "0x1p+0"),
+        (-0.1, "-0x1.999999999999ap-4"),
+        (5e-324, "0x0.0000000000001p-1022"),
+        (2.2250738585072014e-308, "0x1p-1022"),
+        (1e-310, "0x0.012688b70e62bp-1022"),
+        (f64::MAX, "0x1.fffffffffffffp+1023"),
+        (3.0, "0x1.8p+1"),
+        (-0.0, "-0x0p+0"),
+        (f64::NEG_INFINITY, "-inf"),
+    ] {
+        let fp = Float::from_f64(v);
+        assert_eq!(fp.to_hex_string(), s);
+        let parsed = Float::try_from(s).unwrap();
+        assert_eq!(parsed.as_f64().to_bits(), v.to_bits());
+    }
+    assert_eq!(Float::from_f64(1.5).cast(FP16).to_hex_string(), "0x1.8p+0");
+    assert!(Float::try_from("0x1.8").unwrap().as_f64() == 1.5);
+    assert!(Float::try_from("0X1P-2").unwrap().as_f64() == 0.25);
+    assert!(Float::try_from("0x.8p1").unwrap().as_f64() == 1.0);
+    assert!(Float::try_from("0x1.g").is_err());
+    assert!(Float::try_from("0x").is_err());
+    assert!(Float::try_from("0x1p").is_err());
+
+    // Round the numbers that don't fit.
+    let one = 1.0f64.to_bits();
+    let parse = |s: &str| Float::try_from(s).unwrap().as_f64().to_bits();
+    assert_eq!(parse("0x1.00000000000008p+0"), one);
+    assert_eq!(parse("0x1.000000000000080000001p+0"), one + 1);
+    assert_eq!(parse("0x1.00000000000018p+0"), one + 2);
+    assert_eq!(parse("0x1.fffffffffffff8p+1023"), f64::INFINITY.to_bits());
+    assert_eq!(parse("0x1p-1075"), 0);
+    assert_eq!(parse("0x1.0000001p-1075"), 1);
+    let rm = RoundingMode::Zero;
+    let x = Float::try_from_str("0x1.fffffffffffff8p+1023", FP64.with_rm(rm));
+    assert_eq!(x.unwrap().as_f64(), f64::MAX);
+
+    // Check the round-trip of random numbers, including wide formats.
+    let mut lfsr = utils::Lfsr::new();
+    for _ in 0..200 {
+        let v = f64::from_bits(lfsr.get64());
+        let fp = Float::from_f64(v);
+        let parsed = Float::try_from(fp.to_hex_string().as_str()).unwrap();
+        assert_eq!(v.is_nan(), parsed.is_nan());
+        assert!(v.is_nan() || parsed.as_f64().to_bits() == v.to_bits());
+
+        let wide = fp.cast(FP256) * Float::pi(FP256);
+        let s = wide.to_hex_string();
+        let parsed = Float::try_from_str(&s, FP256).unwrap();
+        assert_eq!(parsed.to_string(), wide.to_string());
+    }
+}
+
 #[test]
 fn test_fuzz_printing() {
     use crate::utils;
@@ -431,6 +967,88 @@ fn test_fuzz_printing() {
     }
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_shortest_printing_matches_rust() {
+    use crate::utils;
+    use std::format;
+
+    let mut lfsr = utils::Lfsr::new();
+
+    for _ in 0..2000 {
+        // Pick random bits, and keep the numbers in a small range of
+        // exponents every other iteration, to test the common values.
+        let mut v0 = lfsr.get64();
+        if v0 & 1 == 0 {
+            v0 = (v0 & !(0x7ff << 52)) | ((1000 + (v0 >> 52) % 48) << 52);
+        }
+        let f0 = f64::from_bits(v0);
+        let f1 = f32::from_bits(v0 as u32);
+        let fp0 = Float::from_f64(f0);
+        let fp1 = Float::from_f32(f1);
+        assert_eq!(format!("{:e}", f0), format!("{:e}", fp0));
+        assert_eq!(format!("{:e}", f1), format!("{:e}", fp1));
+
+        // Display switches to scientific notation outside of this range.
+        if (1e-6..1e21).contains(&f0.abs()) {
+            assert_eq!(format!("{}", f0), format!("{}", fp0));
+        }
+        if (1e-6..1e21).contains(&f1.abs()) {
+            assert_eq!(format!("{}", f1), format!("{}", fp1));
+        }
+    }
+
+    for v in utils::get_special_test_values() {
+        let fp = Float::from_f64(v);
+        assert_eq!(format!("{:e}", v), format!("{:e}", fp));
+        if v == 0. || !v.is_normal() || (1e-6..1e21).contains(&v.abs()) {
+            assert_eq!(format!("{}", v), format!("{}", fp));
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_formatter_flags() {
+    use crate::utils;
+    use std::format;
+
+    // Check that the formatter flags print the same thing as f64.
+    macro_rules! check_format {
+        ($val:expr, $($spec:literal),*) => {
+            $(
+                let fp = Float::from_f64($val);
+                assert_eq!(format!($spec, $val), format!($spec, fp));
+            )*
+        };
+    }
+
+    let mut values = std::vec![
+        0.125, 0.375, 2.5, -0.5, 1.5, 125., 0.35, 1e300, -1e-300, 3.14158,
+        9.9999, 0.0009995, 123456.789, 5e-324,
+    ];
+    values.extend(utils::get_special_test_values());
+    let mut lfsr = utils::Lfsr::new();
+    for _ in 0..200 {
+        let v = lfsr.get64();
+        values.push(f64::from_bits(
+            (v & !(0x7ff << 52)) | (1023 - 40 + v % 80) << 52,
+        ));
+    }
+
+    for v in values {
+        check_format!(v, "{:.0}", "{:.2}", "{:.7}", "{:+.3}", "{:10.1}");
+        check_format!(v, "{:<12.2}", "{:^12.2}", "{:*>12.2}", "{:012.3}");
+        check_format!(v, "{:e}", "{:E}", "{:.0e}", "{:.3e}", "{:+.20e}");
+        check_format!(v, "{:>15e}", "{:015.2E}", "{:#<16e}", "{:^+16.1e}");
+    }
+
+    // Display without a precision switches to scientific notation.
+    check_format!(1.5, "{:+}", "{:8}", "{:<8}", "{:08}", "{:^8}");
+    check_format!(f64::NAN, "{:+}", "{:8}", "{:08}", "{:.3}");
+    check_format!(-f64::INFINITY, "{:+}", "{:8}", "{:08}", "{:.3}");
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_print_sqrt() {
@@ -486,17 +1104,38 @@ impl BigInt {
             return "0".to_string();
         }
 
-        let mut buff = Vec::new();
-        let digits = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-        let ten = Self::from_u64(10);
-        let mut val = self.clone();
-        while !val.is_zero() {
-            let rem = val.inplace_div(&ten);
-            buff.insert(0, digits[rem.as_u64() as usize]);
+        let digits = self.to_digits::<10>();
+        digits.iter().map(|d| (b'0' + d) as char).collect()
+    }
+    /// Prints the bigint in base `radix`, using lowercase letters for the
+    /// digits above 9. The radix must be in the range 2..=36.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+        if self.is_zero() {
+            return "0".to_string();
         }
 
-        String::from_iter(buff)
+        // Power-of-two digits are groups of bits, and don't need divisions.
+        let digits = if radix.is_power_of_two() {
+            let bits = radix.trailing_zeros() as usize;
+            let groups = self.msb_index().div_ceil(bits);
+            (0..groups)
+                .rev()
+                .map(|g| {
+                    (0..bits).fold(0, |acc, j| {
+                        acc | ((self.get_bit(g * bits + j) as u8) << j)
+                    })
+                })
+                .collect()
+        } else {
+            self.to_radix_digits(radix as u8)
+        };
+        digits
+            .iter()
+            .map(|d| char::from_digit(*d as u32, radix).unwrap())
+            .collect()
     }
+
     /// Prints the bigint as a sequence of bits.
     pub fn as_binary(&self) -> String {
         let mut sb = String::new();
@@ -538,6 +1177,28 @@ impl BigInt {
     }
 }
 
+macro_rules! declare_radix_format {
+    ($trait_name:ident, $radix:expr, $prefix:expr, $upper:expr) => {
+        impl $trait_name for BigInt {
+            fn fmt(
+                &self,
+                f: &mut core::fmt::Formatter<'_>,
+            ) -> core::fmt::Result {
+                let mut digits = self.to_str_radix($radix);
+                if $upper {
+                    digits.make_ascii_uppercase();
+                }
+                f.pad_integral(true, $prefix, &digits)
+            }
+        }
+    };
+}
+
+declare_radix_format!(LowerHex, 16, "0x", false);
+declare_radix_format!(UpperHex, 16, "0x", true);
+declare_radix_format!(Octal, 8, "0o", false);
+declare_radix_format!(Binary, 2, "0b", false);
+
 #[cfg(feature = "std")]
 #[test]
 fn test_bigint_to_string() {
@@ -576,3 +1237,78 @@ fn test_bigint_to_decimal() {
         "815915283247897734345611269596115894272000000000"
     );
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_bigint_radix_round_trip() {
+    use crate::utils::Lfsr;
+    use std::format;
+
+    let mut lfsr = Lfsr::new();
+    for i in 0..500 {
+        let val = (lfsr.get64() as u128) << (i % 64) | lfsr.get64() as u128;
+        let num = BigInt::from_u128(val);
+        for radix in [2, 3, 7, 8, 10, 16, 31, 36] {
+            let text = num.to_str_radix(radix);
+            assert_eq!(text, format_radix(val, radix));
+            let parsed = BigInt::from_str_radix(&text, radix).unwrap();
+            assert_eq!(parsed, num);
+        }
+        assert_eq!(format!("{:x}", num), format!("{:x}", val));
+        assert_eq!(format!("{:#X}", num), format!("{:#X}", val));
+        assert_eq!(format!("{:o}", num), format!("{:o}", val));
+        assert_eq!(format!("{:#b}", num), format!("{:#b}", val));
+        assert_eq!(format!("{:040x}", num), format!("{:040x}", val));
+        assert_eq!(num.as_decimal().parse::<BigInt>().unwrap(), num);
+    }
+
+    // Formats the integer in base 'radix', using lowercase letters.
+    fn format_radix(mut val: u128, radix: u32) -> String {
+        let mut digits = Vec::new();
+        loop {
+            let digit = (val % radix as u128) as u32;
+            digits.push(char::from_digit(digit, radix).unwrap());
+            val /= radix as u128;
+            if val == 0 {
+                break;
+            }
+        }
+        digits.iter().rev().collect()
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_bigint_parse_radix() {
+    use core::str::FromStr;
+
+    let num = BigInt::from_str_radix("DeadBeef", 16).unwrap();
+    assert_eq!(num.as_u64(), 0xdeadbeef);
+    assert_eq!(BigInt::from_str_radix("+z", 36).unwrap().as_u64(), 35);
+    assert_eq!(BigInt::from_str("000123").unwrap().as_u64(), 123);
+    assert_eq!(BigInt::zero().to_str_radix(7), "0");
+    assert!(BigInt::from_str("").is_err());
+    assert!(BigInt::from_str("-1").is_err());
+    assert!(BigInt::from_str("12a").is_err());
+    assert!(BigInt::from_str_radix("2", 2).is_err());
+
+    // Check a large number, that is converted with the recursive split.
+    let num = BigInt::from_u64(3).powi(40000) - 1;
+    for radix in [10, 16, 36] {
+        let text = num.to_str_radix(radix);
+        assert_eq!(BigInt::from_str_radix(&text, radix).unwrap(), num);
+    }
+    let digits = "9".repeat(20000);
+    let num = BigInt::from_str(&digits).unwrap();
+    assert_eq!(num, BigInt::from_u64(10).powi(20000) - 1);
+    assert_eq!(num.as_decimal(), digits);
+}
+
+#[test]
+fn test_status_to_string() {
+    use alloc::format;
+    assert_eq!(format!("{}", Status::OK), "OK");
+    assert_eq!(format!("{}", Status::DIV_BY_ZERO), "DivByZero");
+    let status = Status::INEXACT | Status::UNDERFLOW;
+    assert_eq!(format!("{}", status), "Underflow|Inexact");
+}
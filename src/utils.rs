@@ -42,6 +42,19 @@ pub fn get_special_test_values() -> [f64; 22] {
     ]
 }
 
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+/// Checks that two results are equal, or at most `ulps` units in the last place
+/// apart. The native implementations of many math functions are not correctly
+/// rounded, so tests that compare to them need some slack.
+pub fn assert_close(r0: f64, r1: f64, ulps: u64) {
+    assert_eq!(r0.is_nan(), r1.is_nan());
+    if !r0.is_nan() {
+        assert_eq!(r0.is_sign_negative(), r1.is_sign_negative());
+        assert!(r0.to_bits().abs_diff(r1.to_bits()) <= ulps, "{} {}", r0, r1);
+    }
+}
+
 // Linear-feedback shift register. We use this as a random number generator for
 // tests.
 pub struct Lfsr {